@@ -0,0 +1,322 @@
+//! Parsers for binary formats: fixed-width integers and floats, variable-length integers, and length-prefixed frames.
+//!
+//! *"Space is big. Really big. You just won't believe how vastly, hugely, mind-bogglingly big it is."*
+//!
+//! The parsers in this module work on any input that produces [`u8`] tokens by value, such as `&[u8]`,
+//! [`IoInput`](crate::input::IoInput) and [`ReadInput`](crate::input::ReadInput), with the exception of
+//! [`length_prefixed`], which works on `&[u8]`.
+//!
+//! Fixed-width parsers come in big-endian (`_be`) and little-endian (`_le`) flavours, and report running out of input
+//! like [`any`] does.
+//!
+//! # Examples
+//!
+//! ```
+//! # use chumsky::{prelude::*, binary::*};
+//! // A tiny chunked file format: a magic number, followed by chunks that each have a 4-byte tag and a varint length
+//! let chunk = array::<_, extra::Err<Simple<u8>>, 4>()
+//!     .then(length_prefixed(varint(), any().repeated().collect::<Vec<_>>()));
+//! let file = magic(b"\x7fCHK")
+//!     .ignore_then(u16_le())
+//!     .then(chunk.repeated().collect::<Vec<_>>());
+//!
+//! let bytes = b"\x7fCHK\x01\x00NAME\x03foo";
+//! assert_eq!(
+//!     file.parse(bytes).into_result(),
+//!     Ok((1, vec![(*b"NAME", b"foo".to_vec())])),
+//! );
+//! ```
+
+use super::*;
+
+/// A parser that reads `N` bytes into an array.
+pub fn array<'src, I, E, const N: usize>() -> impl Parser<'src, I, [u8; N], E> + Copy
+where
+    I: ValueInput<'src, Token = u8>,
+    E: ParserExtra<'src, I>,
+{
+    custom(|inp: &mut InputRef<'src, '_, I, E>| {
+        let mut bytes = [0; N];
+        for byte in &mut bytes {
+            *byte = inp.parse(any())?;
+        }
+        Ok(bytes)
+    })
+}
+
+/// A parser that matches an exact sequence of bytes, such as the magic number at the start of a file.
+pub fn magic<'src, I, E>(bytes: &'src [u8]) -> impl Parser<'src, I, (), E> + Copy
+where
+    I: ValueInput<'src, Token = u8>,
+    E: ParserExtra<'src, I>,
+{
+    just(bytes).ignored()
+}
+
+/// A parser that reads a [`u8`].
+pub fn u8<'src, I, E>() -> impl Parser<'src, I, u8, E> + Copy
+where
+    I: ValueInput<'src, Token = u8>,
+    E: ParserExtra<'src, I>,
+{
+    any()
+}
+
+/// A parser that reads an [`i8`].
+pub fn i8<'src, I, E>() -> impl Parser<'src, I, i8, E> + Copy
+where
+    I: ValueInput<'src, Token = u8>,
+    E: ParserExtra<'src, I>,
+{
+    any().map(|byte: u8| byte as i8)
+}
+
+macro_rules! impl_fixed_width {
+    ($($T:ident => $be:ident, $le:ident;)*) => {
+        $(
+            #[doc = concat!("A parser that reads a big-endian [`", stringify!($T), "`].")]
+            pub fn $be<'src, I, E>() -> impl Parser<'src, I, $T, E> + Copy
+            where
+                I: ValueInput<'src, Token = u8>,
+                E: ParserExtra<'src, I>,
+            {
+                array::<I, E, { core::mem::size_of::<$T>() }>().map($T::from_be_bytes)
+            }
+
+            #[doc = concat!("A parser that reads a little-endian [`", stringify!($T), "`].")]
+            pub fn $le<'src, I, E>() -> impl Parser<'src, I, $T, E> + Copy
+            where
+                I: ValueInput<'src, Token = u8>,
+                E: ParserExtra<'src, I>,
+            {
+                array::<I, E, { core::mem::size_of::<$T>() }>().map($T::from_le_bytes)
+            }
+        )*
+    };
+}
+
+impl_fixed_width! {
+    u16 => u16_be, u16_le;
+    u32 => u32_be, u32_le;
+    u64 => u64_be, u64_le;
+    u128 => u128_be, u128_le;
+    i16 => i16_be, i16_le;
+    i32 => i32_be, i32_le;
+    i64 => i64_be, i64_le;
+    i128 => i128_be, i128_le;
+    f32 => f32_be, f32_le;
+    f64 => f64_be, f64_le;
+}
+
+/// A parser that reads an unsigned [LEB128](https://en.wikipedia.org/wiki/LEB128) integer.
+///
+/// Encodings of values that do not fit in a [`u64`] are rejected: a tenth byte may only contain the final bit of the
+/// value, and no byte may follow it.
+pub fn uleb128<'src, I, E>() -> impl Parser<'src, I, u64, E> + Copy
+where
+    I: ValueInput<'src, Token = u8>,
+    E: ParserExtra<'src, I>,
+{
+    custom(|inp: &mut InputRef<'src, '_, I, E>| {
+        let mut value = 0;
+        for shift in (0..63).step_by(7) {
+            let byte: u8 = inp.parse(any())?;
+            value |= u64::from(byte & 0x7F) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        let last = inp.parse(any().filter(|byte: &u8| *byte <= 1))?;
+        Ok(value | u64::from(last) << 63)
+    })
+}
+
+/// A parser that reads a signed [LEB128](https://en.wikipedia.org/wiki/LEB128) integer.
+///
+/// Encodings of values that do not fit in an [`i64`] are rejected: a tenth byte may only contain the final bit of the
+/// value (`0x00` or `0x7F`), and no byte may follow it.
+pub fn sleb128<'src, I, E>() -> impl Parser<'src, I, i64, E> + Copy
+where
+    I: ValueInput<'src, Token = u8>,
+    E: ParserExtra<'src, I>,
+{
+    custom(|inp: &mut InputRef<'src, '_, I, E>| {
+        let mut value = 0;
+        for shift in (0..63).step_by(7) {
+            let byte: u8 = inp.parse(any())?;
+            value |= i64::from(byte & 0x7F) << shift;
+            if byte & 0x80 == 0 {
+                // Sign-extend from the last bit that was read
+                if byte & 0x40 != 0 {
+                    value |= !0 << (shift + 7);
+                }
+                return Ok(value);
+            }
+        }
+        let last = inp.parse(any().filter(|byte: &u8| *byte == 0x00 || *byte == 0x7F))?;
+        Ok(value | i64::from(last & 1) << 63)
+    })
+}
+
+/// A parser that reads a [protocol buffers varint](https://protobuf.dev/programming-guides/encoding/#varints).
+///
+/// Varints share their encoding with [`uleb128`]. Protocol buffers encode negative `int32` and `int64` values as
+/// 10-byte varints, so these can be recovered by casting the output to [`i64`].
+pub fn varint<'src, I, E>() -> impl Parser<'src, I, u64, E> + Copy
+where
+    I: ValueInput<'src, Token = u8>,
+    E: ParserExtra<'src, I>,
+{
+    uleb128()
+}
+
+/// A parser that reads a [ZigZag-encoded](https://protobuf.dev/programming-guides/encoding/#signed-ints) protocol
+/// buffers varint, as used for the `sint32` and `sint64` types.
+pub fn zigzag_varint<'src, I, E>() -> impl Parser<'src, I, i64, E> + Copy
+where
+    I: ValueInput<'src, Token = u8>,
+    E: ParserExtra<'src, I>,
+{
+    varint().map(|n: u64| (n >> 1) as i64 ^ -((n & 1) as i64))
+}
+
+/// A parser that reads a length with `len`, and then runs `inner` on exactly that many of the following bytes.
+///
+/// Like [`Parser::nested_in`], `inner` only sees the bytes of the frame: it must consume all of them, and cannot read
+/// beyond them. Spans produced by `inner` are relative to the start of the frame.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, binary::*};
+/// let string = length_prefixed(
+///     u16_be::<_, extra::Err<Simple<u8>>>(),
+///     any().repeated().to_slice(),
+/// );
+///
+/// assert_eq!(string.parse(b"\x00\x03abc").into_result(), Ok(&b"abc"[..]));
+/// // `inner` must consume the whole frame
+/// assert!(length_prefixed(u8(), u8::<_, extra::Err<Simple<u8>>>()).parse(b"\x02ab").has_errors());
+/// // The frame must not extend beyond the end of the input
+/// assert!(string.parse(b"\x00\x04abc").has_errors());
+/// ```
+pub fn length_prefixed<'src, L, N, P, O, E>(
+    len: L,
+    inner: P,
+) -> impl Parser<'src, &'src [u8], O, E> + Clone
+where
+    E: ParserExtra<'src, &'src [u8]>,
+    L: Parser<'src, &'src [u8], N, E> + Clone,
+    N: TryInto<usize>,
+    P: Parser<'src, &'src [u8], O, E> + Clone,
+{
+    let frame = custom(move |inp: &mut InputRef<'src, '_, &'src [u8], E>| {
+        // Lengths that cannot be represented can never fit in the input
+        let n = inp.parse(&len)?.try_into().unwrap_or(usize::MAX);
+        let before = inp.cursor();
+        inp.parse(any().repeated().exactly(n))?;
+        Ok(inp.slice_since(&before..))
+    });
+    inner.nested_in(frame)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type E<'src> = extra::Err<Simple<'src, u8>>;
+
+    #[test]
+    fn fixed_width() {
+        let bytes: &[u8] = &[0x12, 0x34, 0x56, 0x78];
+        assert_eq!(
+            u32_be::<_, E<'_>>().parse(bytes).into_result(),
+            Ok(0x12345678)
+        );
+        assert_eq!(
+            u32_le::<_, E<'_>>().parse(bytes).into_result(),
+            Ok(0x78563412)
+        );
+        assert_eq!(
+            i16_be::<_, E<'_>>()
+                .repeated()
+                .collect::<Vec<_>>()
+                .parse(bytes)
+                .into_result(),
+            Ok(vec![0x1234, 0x5678]),
+        );
+        assert_eq!(
+            f64_le::<_, E<'_>>()
+                .parse(&1.5f64.to_le_bytes()[..])
+                .into_result(),
+            Ok(1.5),
+        );
+        assert_eq!(i8::<_, E<'_>>().parse(&[0xFF][..]).into_result(), Ok(-1));
+
+        let errs = u64_be::<_, E<'_>>().parse(bytes).into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::from(4..4));
+    }
+
+    #[test]
+    fn leb128() {
+        let max = [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01];
+        let min = [0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x7F];
+
+        let unsigned: &[(&[u8], u64)] = &[
+            (&[0x00], 0),
+            (&[0x7F], 127),
+            (&[0xE5, 0x8E, 0x26], 624485),
+            (&[0xC0, 0xBB, 0x78], 1973696),
+            (&max, u64::MAX),
+        ];
+        for (bytes, value) in unsigned {
+            assert_eq!(
+                uleb128::<_, E<'_>>().parse(*bytes).into_result(),
+                Ok(*value)
+            );
+        }
+
+        let signed: &[(&[u8], i64)] = &[
+            (&[0x00], 0),
+            (&[0x7F], -1),
+            (&[0xE5, 0x8E, 0x26], 624485),
+            (&[0xC0, 0xBB, 0x78], -123456),
+            (&min, i64::MIN),
+        ];
+        for (bytes, value) in signed {
+            assert_eq!(
+                sleb128::<_, E<'_>>().parse(*bytes).into_result(),
+                Ok(*value)
+            );
+        }
+
+        // Values that overflow a `u64`
+        assert!(uleb128::<_, E<'_>>().parse(&[0xFF; 10][..]).has_errors());
+        assert!(uleb128::<_, E<'_>>().parse(&[0x80; 11][..]).has_errors());
+        // Running out of input mid-varint
+        assert!(uleb128::<_, E<'_>>().parse(&[0x80][..]).has_errors());
+
+        assert_eq!(
+            zigzag_varint::<_, E<'_>>()
+                .repeated()
+                .collect::<Vec<_>>()
+                .parse(&[0x00, 0x01, 0x02, 0x03, 0xFE, 0xFF, 0x03][..])
+                .into_result(),
+            Ok(vec![0, -1, 1, -2, 32767]),
+        );
+    }
+
+    #[test]
+    fn length_prefixed_frames() {
+        let frame = length_prefixed(u8::<_, E<'_>>(), u16_be().repeated().collect::<Vec<_>>());
+        let frames = frame.repeated().collect::<Vec<_>>();
+
+        assert_eq!(
+            frames.parse(&[2, 0, 1, 2, 4, 0, 0][..]).into_result(),
+            Ok(vec![vec![1], vec![0x0400], vec![]]),
+        );
+        // Odd-sized frames can't be fully consumed by the inner parser
+        assert!(frames.parse(&[3, 0, 1, 0][..]).has_errors());
+    }
+}
@@ -13,6 +13,36 @@ where
         M::invoke(*self, inp)
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        (**self).node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        (**self).gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        (**self).prefixes()
+    }
+
+    fn skip_many(&self, inp: &mut InputRef<'src, '_, I, E>) -> usize {
+        (**self).skip_many(inp)
+    }
+
     go_extra!(O);
 }
 
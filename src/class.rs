@@ -0,0 +1,805 @@
+//! Character classes: compact sets of characters built from ranges, negations and Unicode properties.
+//!
+//! *"The Answer to the Great Question... Of Life, the Universe and Everything... Is... Forty-two," said Deep Thought,
+//! with infinite majesty and calm.*
+//!
+//! A [`CharClass`] can be passed to [`one_of`] and [`none_of`] like any other sequence of tokens. Classes are stored
+//! as a bitmap of their ASCII characters and a sorted table of ranges, so a class containing every alphabetic
+//! character is as quick to match against as `"abc"`. When a class fails to match, errors describe it as a single
+//! pattern, such as `[a-zA-Z_]` or `\p{Alphabetic}`, rather than listing every character that it contains.
+//!
+//! Classes can be written with [`char_class`], using the syntax of the inside of a regex bracket expression, created
+//! from Unicode properties with functions like [`alphabetic`] (or [`category`], with the `unicode-categories`
+//! feature), and combined with [`CharClass::union`], [`CharClass::intersection`] and [`CharClass::negate`].
+//!
+//! Building a class from a Unicode property means checking every character. With the `std` feature, this only happens
+//! the first time each property is used; otherwise, classes should be built once, along with the rest of the parser,
+//! rather than within a parser.
+//!
+//! # Examples
+//!
+//! ```
+//! # use chumsky::{prelude::*, class::{self, char_class}};
+//! let ident = one_of::<_, _, extra::Err<Rich<char>>>(char_class("a-zA-Z_"))
+//!     .then(one_of(char_class("a-zA-Z0-9_")).repeated())
+//!     .to_slice();
+//! assert_eq!(ident.parse("foo_1").into_result(), Ok("foo_1"));
+//!
+//! // The class is named in errors as a whole
+//! let errs = ident.parse("9").into_errors();
+//! assert_eq!(errs[0].to_string(), "found '9' expected [a-zA-Z_]");
+//!
+//! // Classes can also be built from Unicode properties
+//! let word = one_of::<_, _, extra::Err<Rich<char>>>(class::alphabetic())
+//!     .repeated()
+//!     .at_least(1)
+//!     .to_slice();
+//! assert_eq!(word.parse("grüße").into_result(), Ok("grüße"));
+//! ```
+
+use super::*;
+use alloc::{format, string::ToString};
+use core::{iter::Peekable, ops::RangeInclusive, str::CharIndices};
+#[cfg(feature = "std")]
+use std::sync::Mutex;
+
+/// An error produced when the description of a [`CharClass`] is invalid. See [`char_class`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ClassError {
+    /// A range ended before it started, such as `z-a`.
+    ReversedRange(char, char),
+    /// The escape sequence starting at the given byte offset was incomplete or didn't name a character.
+    InvalidEscape(usize),
+}
+
+impl fmt::Display for ClassError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ReversedRange(start, end) => {
+                write!(f, "range {start:?}-{end:?} ends before it starts")
+            }
+            Self::InvalidEscape(offset) => write!(f, "invalid escape sequence at offset {offset}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ClassError {}
+
+// How a class is described in errors and grammars.
+#[derive(Clone, Debug)]
+enum Pattern {
+    // A bracketed set of items, such as `[a-z_]`
+    Set { items: String, negated: bool },
+    // A named property, such as `\p{Alphabetic}`
+    Property { name: &'static str, negated: bool },
+}
+
+impl Pattern {
+    // The pattern as an item of a larger set, splicing in the items of sets where that doesn't change their meaning
+    fn item(&self) -> String {
+        match self {
+            Self::Set {
+                items,
+                negated: false,
+            } if !str::contains(items, "&&") => items.clone(),
+            pattern => pattern.to_string(),
+        }
+    }
+
+    fn negate(self) -> Self {
+        match self {
+            Self::Set { items, negated } => Self::Set {
+                items,
+                negated: !negated,
+            },
+            Self::Property { name, negated } => Self::Property {
+                name,
+                negated: !negated,
+            },
+        }
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Set { items, negated } => {
+                write!(f, "[{}{items}]", if *negated { "^" } else { "" })
+            }
+            Self::Property { name, negated } => {
+                write!(f, "\\{}{{{name}}}", if *negated { 'P' } else { 'p' })
+            }
+        }
+    }
+}
+
+/// A set of characters, built from ranges, negations and Unicode properties.
+///
+/// See the [module-level documentation](self) for more information.
+#[derive(Clone)]
+pub struct CharClass {
+    // A bit for each ASCII character in the class
+    ascii: u128,
+    // Sorted, non-overlapping and non-adjacent ranges of every character in the class (including ASCII ones)
+    ranges: Vec<(char, char)>,
+    pattern: Pattern,
+    // The pattern of the class and of its negation, rendered once so that failing to match doesn't allocate
+    rendered: Arc<str>,
+    rendered_negated: Arc<str>,
+}
+
+impl CharClass {
+    fn from_ranges(mut ranges: Vec<(char, char)>, pattern: Pattern) -> Self {
+        ranges.sort_unstable();
+        let mut merged: Vec<(char, char)> = Vec::with_capacity(ranges.len());
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                Some(last) if last.1 >= start || next_char(last.1) == Some(start) => {
+                    last.1 = last.1.max(end)
+                }
+                _ => merged.push((start, end)),
+            }
+        }
+
+        let mut ascii = 0;
+        for &(start, end) in &merged {
+            for c in start as u32..=(end as u32).min(127) {
+                ascii |= 1 << c;
+            }
+        }
+
+        Self {
+            ascii,
+            ranges: merged,
+            rendered: pattern.to_string().into(),
+            rendered_negated: pattern.clone().negate().to_string().into(),
+            pattern,
+        }
+    }
+
+    /// Create a class from a property of characters, checking every character against it. With the `std` feature, the
+    /// class is only built the first time that each property is used.
+    fn from_property(name: &'static str, f: impl Fn(char) -> bool) -> Self {
+        #[cfg(feature = "std")]
+        {
+            static PROPERTIES: Mutex<Vec<CharClass>> = Mutex::new(Vec::new());
+
+            let mut properties = PROPERTIES.lock().unwrap_or_else(|err| err.into_inner());
+            let cached = properties.iter().find(
+                |class| matches!(class.pattern, Pattern::Property { name: n, .. } if n == name),
+            );
+            if let Some(class) = cached {
+                return class.clone();
+            }
+            let class = Self::build_property(name, f);
+            properties.push(class.clone());
+            class
+        }
+        #[cfg(not(feature = "std"))]
+        Self::build_property(name, f)
+    }
+
+    fn build_property(name: &'static str, f: impl Fn(char) -> bool) -> Self {
+        let mut ranges = Vec::new();
+        let mut start = None;
+        let mut prev = '\0';
+        for c in '\0'..=char::MAX {
+            match (start, f(c)) {
+                (None, true) => start = Some(c),
+                (Some(s), false) => {
+                    ranges.push((s, prev));
+                    start = None;
+                }
+                _ => {}
+            }
+            prev = c;
+        }
+        if let Some(s) = start {
+            ranges.push((s, char::MAX));
+        }
+        Self::from_ranges(
+            ranges,
+            Pattern::Property {
+                name,
+                negated: false,
+            },
+        )
+    }
+
+    /// Returns true if the class contains the given character.
+    #[inline]
+    pub fn contains(&self, c: char) -> bool {
+        if (c as u32) < 128 {
+            self.ascii & (1 << c as u32) != 0
+        } else {
+            let i = self.ranges.partition_point(|&(_, end)| end < c);
+            self.ranges.get(i).map_or(false, |&(start, _)| start <= c)
+        }
+    }
+
+    /// Iterate over the ranges of characters in the class, in order.
+    pub fn ranges(&self) -> impl Iterator<Item = RangeInclusive<char>> + '_ {
+        self.ranges.iter().map(|&(start, end)| start..=end)
+    }
+
+    /// Create a class containing the characters of both this class and `other`.
+    pub fn union(self, other: Self) -> Self {
+        let pattern = Pattern::Set {
+            items: format!("{}{}", self.pattern.item(), other.pattern.item()),
+            negated: false,
+        };
+        let mut ranges = self.ranges;
+        ranges.extend(other.ranges);
+        Self::from_ranges(ranges, pattern)
+    }
+
+    /// Create a class containing only the characters that are in both this class and `other`.
+    pub fn intersection(self, other: Self) -> Self {
+        let pattern = Pattern::Set {
+            items: format!("{}&&{}", self.pattern.item(), other.pattern.item()),
+            negated: false,
+        };
+        let (mut a, mut b) = (
+            self.ranges.iter().peekable(),
+            other.ranges.iter().peekable(),
+        );
+        let mut ranges = Vec::new();
+        while let (Some(&&(a_start, a_end)), Some(&&(b_start, b_end))) = (a.peek(), b.peek()) {
+            let (start, end) = (a_start.max(b_start), a_end.min(b_end));
+            if start <= end {
+                ranges.push((start, end));
+            }
+            // Move past whichever range ends first, since it can't overlap any later ranges
+            if a_end < b_end {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+        Self::from_ranges(ranges, pattern)
+    }
+
+    /// Create a class containing every character that is *not* in this class.
+    pub fn negate(self) -> Self {
+        let mut ranges = Vec::new();
+        let mut start = Some('\0');
+        for &(s, e) in &self.ranges {
+            if let Some(start) = start.filter(|&start| start < s) {
+                ranges.push((start, prev_char(s).unwrap_or(start)));
+            }
+            start = next_char(e);
+        }
+        if let Some(start) = start {
+            ranges.push((start, char::MAX));
+        }
+        Self::from_ranges(ranges, self.pattern.negate())
+    }
+}
+
+impl Default for CharClass {
+    /// Create an empty class, which contains no characters.
+    fn default() -> Self {
+        Self::from_ranges(
+            Vec::new(),
+            Pattern::Set {
+                items: String::new(),
+                negated: false,
+            },
+        )
+    }
+}
+
+impl PartialEq for CharClass {
+    fn eq(&self, other: &Self) -> bool {
+        self.ranges == other.ranges
+    }
+}
+
+impl Eq for CharClass {}
+
+impl fmt::Debug for CharClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CharClass({})", self.rendered)
+    }
+}
+
+impl fmt::Display for CharClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.rendered)
+    }
+}
+
+impl FromStr for CharClass {
+    type Err = ClassError;
+
+    /// Parse a class from the syntax accepted by [`char_class`].
+    fn from_str(spec: &str) -> Result<Self, ClassError> {
+        let (negated, body) = match spec.strip_prefix('^') {
+            Some(body) => (true, body),
+            None => (false, spec),
+        };
+        let offset = spec.len() - body.len();
+
+        let mut chars = body.char_indices().peekable();
+        let mut ranges = Vec::new();
+        while let Some(start) = parse_char(&mut chars, offset)? {
+            let mut rest = chars.clone();
+            // A `-` is only a range if there's a character on both sides of it
+            let end = if rest.next().map(|(_, c)| c) == Some('-') && rest.peek().is_some() {
+                chars.next();
+                parse_char(&mut chars, offset)?.unwrap_or(start)
+            } else {
+                start
+            };
+            if start > end {
+                return Err(ClassError::ReversedRange(start, end));
+            }
+            ranges.push((start, end));
+        }
+
+        let mut items = String::new();
+        for &(start, end) in &ranges {
+            push_char(&mut items, start);
+            if start != end {
+                items.push('-');
+                push_char(&mut items, end);
+            }
+        }
+        let class = Self::from_ranges(
+            ranges,
+            Pattern::Set {
+                items,
+                negated: false,
+            },
+        );
+        Ok(if negated { class.negate() } else { class })
+    }
+}
+
+// Parse a single (possibly escaped) character of a class.
+fn parse_char(
+    chars: &mut Peekable<CharIndices>,
+    offset: usize,
+) -> Result<Option<char>, ClassError> {
+    let Some((i, c)) = chars.next() else {
+        return Ok(None);
+    };
+    if c != '\\' {
+        return Ok(Some(c));
+    }
+    let invalid = ClassError::InvalidEscape(offset + i);
+    Ok(Some(match chars.next().ok_or(invalid.clone())?.1 {
+        'n' => '\n',
+        'r' => '\r',
+        't' => '\t',
+        '0' => '\0',
+        'u' => {
+            if chars.next().map(|(_, c)| c) != Some('{') {
+                return Err(invalid);
+            }
+            let mut hex = String::new();
+            loop {
+                match chars.next().ok_or(invalid.clone())?.1 {
+                    '}' => break,
+                    c => hex.push(c),
+                }
+            }
+            u32::from_str_radix(&hex, 16)
+                .ok()
+                .and_then(char::from_u32)
+                .ok_or(invalid)?
+        }
+        c => c,
+    }))
+}
+
+// Write a character of a class so that it can't be confused with the syntax of a class.
+fn push_char(items: &mut String, c: char) {
+    match c {
+        '\\' | '[' | ']' | '^' | '-' | '&' => {
+            items.push('\\');
+            items.push(c);
+        }
+        c if c.is_control() => items.extend(c.escape_debug()),
+        c => items.push(c),
+    }
+}
+
+fn next_char(c: char) -> Option<char> {
+    match c {
+        '\u{D7FF}' => Some('\u{E000}'),
+        c => char::from_u32(c as u32 + 1),
+    }
+}
+
+fn prev_char(c: char) -> Option<char> {
+    match c {
+        '\u{E000}' => Some('\u{D7FF}'),
+        c => (c as u32).checked_sub(1).and_then(char::from_u32),
+    }
+}
+
+/// Create a [`CharClass`] from a description of the characters that it contains.
+///
+/// The description uses the syntax of the inside of a regex bracket expression: individual characters (like `_`),
+/// ranges of characters (like `a-z`) and, if the description starts with `^`, the negation of the class. `\` escapes
+/// the next character, and also supports `\n`, `\r`, `\t`, `\0` and `\u{...}`. A `-` at the start or end of the
+/// description is a literal `-`.
+///
+/// # Panics
+///
+/// Panics if the description is invalid. Use [`str::parse`] to handle invalid descriptions.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, class::char_class};
+/// let hex = char_class("0-9a-fA-F");
+/// assert!(hex.contains('c') && !hex.contains('g'));
+///
+/// let not_quote = char_class("^\"\\\\");
+/// assert!(not_quote.contains('a') && !not_quote.contains('"'));
+///
+/// let string = just::<_, _, extra::Err<Rich<char>>>('"')
+///     .ignore_then(one_of(not_quote).repeated().to_slice())
+///     .then_ignore(just('"'));
+/// assert_eq!(string.parse("\"hello\"").into_result(), Ok("hello"));
+/// ```
+#[track_caller]
+pub fn char_class(spec: &str) -> CharClass {
+    match spec.parse() {
+        Ok(class) => class,
+        Err(err) => panic!("invalid character class {spec:?}: {err}"),
+    }
+}
+
+/// A class of the characters with the Unicode `Alphabetic` property. See [`char::is_alphabetic`].
+pub fn alphabetic() -> CharClass {
+    CharClass::from_property("Alphabetic", char::is_alphabetic)
+}
+
+/// A class of the characters with the Unicode `Numeric` property. See [`char::is_numeric`].
+pub fn numeric() -> CharClass {
+    CharClass::from_property("Numeric", char::is_numeric)
+}
+
+/// A class of the characters that are either [`alphabetic`] or [`numeric`]. See [`char::is_alphanumeric`].
+pub fn alphanumeric() -> CharClass {
+    CharClass::from_property("Alphanumeric", char::is_alphanumeric)
+}
+
+/// A class of the characters with the Unicode `White_Space` property. See [`char::is_whitespace`].
+pub fn whitespace() -> CharClass {
+    CharClass::from_property("White_Space", char::is_whitespace)
+}
+
+/// A class of the characters with the Unicode `Lowercase` property. See [`char::is_lowercase`].
+pub fn lowercase() -> CharClass {
+    CharClass::from_property("Lowercase", char::is_lowercase)
+}
+
+/// A class of the characters with the Unicode `Uppercase` property. See [`char::is_uppercase`].
+pub fn uppercase() -> CharClass {
+    CharClass::from_property("Uppercase", char::is_uppercase)
+}
+
+/// A class of the characters in the Unicode `Cc` (control) general category. See [`char::is_control`].
+pub fn control() -> CharClass {
+    CharClass::from_property("Cc", char::is_control)
+}
+
+macro_rules! general_categories {
+    ($($(#[$attr:meta])* $name:ident),* $(,)?) => {
+        /// A Unicode general category, for use with [`category`].
+        #[cfg(feature = "unicode-categories")]
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+        pub enum GeneralCategory {
+            $($(#[$attr])* $name,)*
+        }
+
+        #[cfg(feature = "unicode-categories")]
+        impl GeneralCategory {
+            /// The abbreviated name of the category, such as `Lu`.
+            pub fn abbreviation(&self) -> &'static str {
+                match self {
+                    $(Self::$name => stringify!($name),)*
+                }
+            }
+        }
+    };
+}
+
+general_categories! {
+    /// Uppercase letters.
+    Lu,
+    /// Lowercase letters.
+    Ll,
+    /// Titlecase letters, such as `ǅ`.
+    Lt,
+    /// Modifier letters.
+    Lm,
+    /// Other letters, such as those of scripts without case.
+    Lo,
+    /// Nonspacing marks, such as combining accents.
+    Mn,
+    /// Spacing marks.
+    Mc,
+    /// Enclosing marks.
+    Me,
+    /// Decimal digits.
+    Nd,
+    /// Letter-like numbers, such as Roman numerals.
+    Nl,
+    /// Other numbers, such as fractions.
+    No,
+    /// Connector punctuation, such as `_`.
+    Pc,
+    /// Dashes.
+    Pd,
+    /// Opening punctuation, such as `(`.
+    Ps,
+    /// Closing punctuation, such as `)`.
+    Pe,
+    /// Initial quotation marks.
+    Pi,
+    /// Final quotation marks.
+    Pf,
+    /// Other punctuation.
+    Po,
+    /// Mathematical symbols.
+    Sm,
+    /// Currency symbols.
+    Sc,
+    /// Modifier symbols.
+    Sk,
+    /// Other symbols.
+    So,
+    /// Space separators.
+    Zs,
+    /// The line separator.
+    Zl,
+    /// The paragraph separator.
+    Zp,
+    /// Control characters.
+    Cc,
+    /// Format characters.
+    Cf,
+    /// Private use characters.
+    Co,
+    /// Unassigned code points.
+    Cn,
+}
+
+/// A class of the characters in a Unicode general category, such as [`GeneralCategory::Lu`] (uppercase letters).
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, class::{category, GeneralCategory::*}};
+/// let upper = category(Lu);
+/// assert!(upper.contains('Ä') && !upper.contains('ä'));
+///
+/// let brackets = category(Ps).union(category(Pe));
+/// assert!(brackets.contains('[') && brackets.contains('}'));
+/// ```
+#[cfg(feature = "unicode-categories")]
+pub fn category(category: GeneralCategory) -> CharClass {
+    use regex_syntax::hir::{Class, HirKind};
+
+    let name = category.abbreviation();
+    let hir = regex_syntax::Parser::new()
+        .parse(&format!("\\p{{{name}}}"))
+        .expect("general categories are always valid");
+    let ranges = match hir.kind() {
+        HirKind::Class(Class::Unicode(class)) => class
+            .ranges()
+            .iter()
+            .map(|range| (range.start(), range.end()))
+            .collect(),
+        _ => Vec::new(),
+    };
+    CharClass::from_ranges(
+        ranges,
+        Pattern::Property {
+            name,
+            negated: false,
+        },
+    )
+}
+
+impl<'p> Seq<'p, char> for CharClass {
+    type Item<'a>
+        = char
+    where
+        Self: 'a;
+
+    type Iter<'a>
+        = core::iter::FlatMap<
+        core::slice::Iter<'a, (char, char)>,
+        RangeInclusive<char>,
+        fn(&(char, char)) -> RangeInclusive<char>,
+    >
+    where
+        Self: 'a;
+
+    #[inline]
+    fn seq_iter(&self) -> Self::Iter<'_> {
+        self.ranges
+            .iter()
+            .flat_map((|&(start, end)| start..=end) as fn(&(char, char)) -> RangeInclusive<char>)
+    }
+
+    #[inline(always)]
+    fn contains(&self, val: &char) -> bool {
+        CharClass::contains(self, *val)
+    }
+
+    #[inline]
+    fn to_maybe_ref<'b>(item: Self::Item<'b>) -> MaybeRef<'p, char>
+    where
+        'p: 'b,
+    {
+        MaybeRef::Val(item)
+    }
+
+    fn describe(&self, negated: bool) -> Option<Arc<str>> {
+        Some(if negated {
+            self.rendered_negated.clone()
+        } else {
+            self.rendered.clone()
+        })
+    }
+}
+
+/// Bytes are treated as the characters with the same value (that is, as Latin-1).
+impl<'p> Seq<'p, u8> for CharClass {
+    type Item<'a>
+        = u8
+    where
+        Self: 'a;
+
+    type Iter<'a>
+        = core::iter::MapWhile<<Self as Seq<'p, char>>::Iter<'a>, fn(char) -> Option<u8>>
+    where
+        Self: 'a;
+
+    #[inline]
+    fn seq_iter(&self) -> Self::Iter<'_> {
+        <Self as Seq<'p, char>>::seq_iter(self)
+            .map_while((|c| u8::try_from(c).ok()) as fn(char) -> Option<u8>)
+    }
+
+    #[inline(always)]
+    fn contains(&self, val: &u8) -> bool {
+        CharClass::contains(self, char::from(*val))
+    }
+
+    #[inline]
+    fn to_maybe_ref<'b>(item: Self::Item<'b>) -> MaybeRef<'p, u8>
+    where
+        'p: 'b,
+    {
+        MaybeRef::Val(item)
+    }
+
+    fn describe(&self, negated: bool) -> Option<Arc<str>> {
+        <Self as Seq<'p, char>>::describe(self, negated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spec() {
+        let class = char_class("a-zA-Z0-9_");
+        assert_eq!(class.to_string(), "[a-zA-Z0-9_]");
+        assert!("azAZ09_".chars().all(|c| class.contains(c)));
+        assert!(!"-` é".chars().any(|c| class.contains(c)));
+
+        // Literal dashes, escapes and negation
+        let class = char_class("^-a\\-z\\u{e9}\\\\-");
+        assert_eq!(class.to_string(), "[^\\-a\\-zé\\\\\\-]");
+        assert!(!"-az\\é".chars().any(|c| class.contains(c)));
+        assert!("bé\u{10FFFF}"
+            .chars()
+            .all(|c| class.contains(c) != (c == 'é')));
+
+        assert_eq!(
+            "z-a".parse::<CharClass>(),
+            Err(ClassError::ReversedRange('z', 'a'))
+        );
+        assert_eq!(
+            "ab\\".parse::<CharClass>(),
+            Err(ClassError::InvalidEscape(2))
+        );
+        assert_eq!(
+            "^\\u{d800}".parse::<CharClass>(),
+            Err(ClassError::InvalidEscape(1))
+        );
+    }
+
+    #[test]
+    fn set_operations() {
+        let vowels = char_class("aeiou");
+        let letters = char_class("a-z");
+        let consonants = letters.clone().intersection(vowels.clone().negate());
+        assert_eq!(consonants.to_string(), "[a-z&&[^aeiou]]");
+        assert_eq!(
+            consonants.ranges().collect::<Vec<_>>(),
+            ['b'..='d', 'f'..='h', 'j'..='n', 'p'..='t', 'v'..='z'],
+        );
+        assert_eq!(consonants.clone().union(vowels.clone()), letters);
+        assert_eq!(
+            consonants.union(vowels).to_string(),
+            "[[a-z&&[^aeiou]]aeiou]"
+        );
+
+        // Surrogates aren't characters, so don't separate ranges, and negating twice gives the original class
+        let any = CharClass::default().negate();
+        assert_eq!(any.ranges().collect::<Vec<_>>(), ['\0'..=char::MAX]);
+        assert!(char_class("\u{D7FF}\u{E000}").negate().contains('\u{D7FE}'));
+        let class = char_class("\0a-z\u{D7FF}\u{E000}");
+        assert_eq!(class.clone().negate().negate(), class);
+        assert_eq!(
+            class.clone().negate().to_string(),
+            "[^\\0a-z\u{D7FF}\u{E000}]"
+        );
+
+        let alpha = alphabetic();
+        assert_eq!(alpha.to_string(), "\\p{Alphabetic}");
+        assert_eq!(alpha.clone().negate().to_string(), "\\P{Alphabetic}");
+        assert!(alpha.contains('ß') && !alpha.contains('1'));
+        assert_eq!(
+            alpha.union(char_class("_")).to_string(),
+            "[\\p{Alphabetic}_]"
+        );
+        let space = whitespace();
+        assert!(('\0'..=char::MAX).all(|c| space.contains(c) == c.is_whitespace()));
+
+        // Properties are only built once, and each class renders its pattern once
+        #[cfg(feature = "std")]
+        assert_eq!(alphabetic(), alphabetic());
+        let alpha = alphabetic();
+        let describe = |negated| <CharClass as Seq<char>>::describe(&alpha, negated).unwrap();
+        assert!(Arc::ptr_eq(&describe(false), &describe(false)));
+        assert_eq!(&*describe(true), "\\P{Alphabetic}");
+    }
+
+    #[test]
+    fn parsers() {
+        let ident = one_of::<_, _, extra::Err<Rich<char>>>(char_class("a-zA-Z_"))
+            .then(one_of(char_class("a-zA-Z0-9_")).repeated())
+            .to_slice();
+        assert_eq!(ident.parse("_foo9").into_result(), Ok("_foo9"));
+        let errs = ident.parse("9").into_errors();
+        assert_eq!(errs[0].to_string(), "found '9' expected [a-zA-Z_]");
+
+        let not_digit = none_of::<_, _, extra::Err<Rich<char>>>(char_class("0-9"));
+        let errs = not_digit.parse("5").into_errors();
+        assert_eq!(errs[0].to_string(), "found '5' expected [^0-9]");
+
+        let bytes = one_of::<_, &[u8], extra::Err<Simple<u8>>>(char_class("a-f"))
+            .repeated()
+            .collect::<Vec<_>>();
+        assert_eq!(bytes.parse(b"cafe").into_result(), Ok(b"cafe".to_vec()));
+        assert!(bytes.parse(b"\xe9").has_errors());
+    }
+
+    #[cfg(feature = "unicode-categories")]
+    #[test]
+    fn categories() {
+        use GeneralCategory::*;
+
+        assert!(category(Lu).contains('Q') && !category(Lu).contains('q'));
+        assert!(category(Nd).contains('٣'));
+        assert!(category(Cn).contains('\u{378}'));
+        assert_eq!(
+            category(Ps).union(category(Pe)).to_string(),
+            "[\\p{Ps}\\p{Pe}]"
+        );
+    }
+}
@@ -36,6 +36,25 @@ where
         self.parser.go_cfg::<M>(inp, cfg)
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
     go_extra!(O);
 }
 
@@ -77,6 +96,25 @@ where
         }
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
     go_extra!(());
 }
 
@@ -113,6 +151,25 @@ where
     ) -> IPResult<M, O> {
         self.parser.next_cfg(inp, &mut state.0, &state.1)
     }
+
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
 }
 
 /// See [`ConfigIterParser::try_configure`]
@@ -153,6 +210,25 @@ where
         }
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
     go_extra!(());
 }
 
@@ -188,6 +264,25 @@ where
     ) -> IPResult<M, O> {
         self.parser.next_cfg(inp, &mut state.0, &state.1)
     }
+
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
 }
 
 /// See [`Parser::to_slice`]
@@ -218,12 +313,40 @@ where
     where
         Self: Sized,
     {
+        // Leading trivia isn't part of the slice
+        inp.skip_trivia();
         let before = inp.cursor();
         self.parser.go::<Check>(inp)?;
 
         Ok(M::bind(|| inp.slice_since(&before..)))
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.parser.prefixes()
+    }
+
     go_extra!(I::Slice);
 }
 
@@ -252,6 +375,7 @@ where
 {
     #[inline(always)]
     fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, O> {
+        inp.skip_trivia();
         let before = inp.cursor();
         self.parser.go::<Emit>(inp).and_then(|out| {
             if (self.filter)(&out) {
@@ -264,6 +388,32 @@ where
         })
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.parser.prefixes()
+    }
+
     go_extra!(O);
 }
 
@@ -299,6 +449,32 @@ where
         Ok(M::map(out, &self.mapper))
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.parser.prefixes()
+    }
+
     go_extra!(O);
 }
 
@@ -336,6 +512,25 @@ where
             Err(()) => Err(()),
         }
     }
+
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
 }
 
 /// See [`Parser::map_with`].
@@ -366,6 +561,7 @@ where
 {
     #[inline(always)]
     fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, O> {
+        inp.skip_trivia();
         let before = inp.cursor();
         let out = self.parser.go::<M>(inp)?;
         Ok(M::map(out, |out| {
@@ -373,6 +569,32 @@ where
         }))
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.parser.prefixes()
+    }
+
     go_extra!(O);
 }
 
@@ -404,15 +626,40 @@ where
         inp: &mut InputRef<'src, '_, I, E>,
         state: &mut Self::IterState<M>,
     ) -> IPResult<M, O> {
+        let start = inp.save();
+        inp.skip_trivia();
         let before = inp.cursor();
         match self.parser.next::<M>(inp, state) {
             Ok(Some(o)) => Ok(Some(M::map(o, |o| {
                 (self.mapper)(o, &mut MapExtra::new(&before, inp))
             }))),
-            Ok(None) => Ok(None),
+            // Trivia after the last item belongs to whatever follows it
+            Ok(None) => {
+                inp.rewind(start);
+                Ok(None)
+            }
             Err(()) => Err(()),
         }
     }
+
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
 }
 
 /// See [`Parser::map_group`].
@@ -452,6 +699,32 @@ where
         Ok(M::map(out, |out| self.mapper.call(out)))
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.parser.prefixes()
+    }
+
     go_extra!(O);
 }
 
@@ -491,6 +764,25 @@ where
             Err(()) => Err(()),
         }
     }
+
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
 }
 
 /// See [`Parser::to_span`].
@@ -518,11 +810,38 @@ where
 {
     #[inline(always)]
     fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, I::Span> {
+        inp.skip_trivia();
         let before = inp.cursor();
         self.parser.go::<M>(inp)?;
         Ok(M::bind(|| inp.span_since(&before)))
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.parser.prefixes()
+    }
+
     go_extra!(I::Span);
 }
 
@@ -554,6 +873,7 @@ where
 {
     #[inline(always)]
     fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, O> {
+        inp.skip_trivia();
         let before = inp.cursor();
         // Remove the pre-inner alt, to be reinserted later so we always preserve it
         let old_alt = inp.errors.alt.take();
@@ -580,6 +900,32 @@ where
         }
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.parser.prefixes()
+    }
+
     go_extra!(O);
 }
 
@@ -611,6 +957,7 @@ where
 {
     #[inline(always)]
     fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, O> {
+        inp.skip_trivia();
         let before = inp.cursor();
         let out = self.parser.go::<Emit>(inp)?;
         match (self.mapper)(out, &mut MapExtra::new(&before, inp)) {
@@ -622,6 +969,32 @@ where
         }
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.parser.prefixes()
+    }
+
     go_extra!(O);
 }
 
@@ -657,6 +1030,32 @@ where
         Ok(M::bind(|| self.to.clone()))
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.parser.prefixes()
+    }
+
     go_extra!(O);
 }
 
@@ -690,6 +1089,25 @@ where
         Ok(M::bind(|| ()))
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
     go_extra!(());
 }
 
@@ -723,6 +1141,25 @@ where
     ) -> IPResult<M, O::Item> {
         Ok(iter.next().map(|out| M::bind(|| out)))
     }
+
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
 }
 
 /// See [`Parser::ignored`].
@@ -754,6 +1191,32 @@ where
         Ok(M::bind(|| ()))
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.parser.prefixes()
+    }
+
     go_extra!(());
 }
 
@@ -795,6 +1258,32 @@ where
         }))
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.parser.prefixes()
+    }
+
     go_extra!(O);
 }
 
@@ -816,6 +1305,32 @@ where
         }))
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.parser.prefixes()
+    }
+
     go_extra!(O);
 }
 
@@ -823,55 +1338,352 @@ where
 #[cfg(feature = "memoization")]
 #[derive(Copy, Clone)]
 pub struct Memoized<A> {
+    pub(crate) id: crate::memo::MemoId,
+    pub(crate) name: Option<&'static str>,
+    pub(crate) max_entries: Option<usize>,
     pub(crate) parser: A,
 }
 
 #[cfg(feature = "memoization")]
-impl<'src, I, E, A, O> Parser<'src, I, O, E> for Memoized<A>
-where
-    I: Input<'src>,
-    E: ParserExtra<'src, I>,
-    E::Error: Clone,
-    A: Parser<'src, I, O, E>,
-{
+impl<A> Memoized<A> {
+    /// Get the id of this parser, which can be used to look up its statistics in [`crate::memo::MemoStats`].
+    ///
+    /// The id is assigned when the parser is created and is shared by its clones.
+    pub fn id(&self) -> crate::memo::MemoId {
+        self.id
+    }
+
+    /// Give this parser a name, allowing its statistics to be found with [`crate::memo::MemoStats::by_name`].
+    pub fn named(self, name: &'static str) -> Self {
+        Self {
+            name: Some(name),
+            ..self
+        }
+    }
+
+    /// Bound the number of results that this parser keeps in the memo table during a parse.
+    ///
+    /// When the bound is exceeded, the oldest results are discarded first. Since parsers tend to move forward through
+    /// the input, these are usually those least likely to be needed again. Results that are in use by a
+    /// left-recursive parse are never discarded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let digit = one_of::<_, _, extra::Err<Simple<char>>>('0'..='9').memoized().max_entries(2);
+    /// let id = digit.id();
+    ///
+    /// let items = digit.clone().or(just('x')).repeated().collect::<String>();
+    ///
+    /// // `digit` fails (and remembers doing so) at each `x` and at the end of the input
+    /// let result = items.parse("xxxx");
+    /// assert_eq!(result.output().map(String::as_str), Some("xxxx"));
+    /// assert_eq!(result.memo_stats().get(id).unwrap().evictions, 3);
+    /// ```
+    pub fn max_entries(self, max_entries: usize) -> Self {
+        Self {
+            max_entries: Some(max_entries),
+            ..self
+        }
+    }
+
+    /// Parse again at a location where this parser is growing a seed, with recursive invocations producing the seed at
+    /// `level`. Since the output of a seed isn't kept, this is how it's produced when needed.
+    fn regrow<'src, I, O, E>(
+        &self,
+        inp: &mut InputRef<'src, '_, I, E>,
+        key: (usize, crate::memo::MemoId),
+        level: usize,
+    ) -> PResult<Emit, O>
+    where
+        I: Input<'src>,
+        E: ParserExtra<'src, I>,
+        A: Parser<'src, I, O, E>,
+    {
+        use crate::memo::Memo;
+
+        let outer = match inp.memos.get_mut(&key) {
+            Some(Memo::Growing { level: l, .. }) => core::mem::replace(l, level),
+            _ => return Err(()),
+        };
+        let res = self.parser.go::<Emit>(inp);
+        if let Some(Memo::Growing { level: l, .. }) = inp.memos.get_mut(&key) {
+            *l = outer;
+        }
+        res
+    }
+}
+
+#[cfg(feature = "memoization")]
+impl<'src, I, E, A, O> Parser<'src, I, O, E> for Memoized<A>
+where
+    I: Input<'src>,
+    E: ParserExtra<'src, I>,
+    E::Error: Clone,
+    A: Parser<'src, I, O, E>,
+{
     #[inline(always)]
     fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, O> {
-        let before = inp.cursor();
-        // TODO: Don't use address, since this might not be constant?
-        let key = (
-            I::cursor_location(&before.inner),
-            &self.parser as *const _ as *const () as usize,
-        );
+        use crate::memo::{Memo, Seed};
 
-        match inp.memos.entry(key) {
-            hashbrown::hash_map::Entry::Occupied(o) => {
-                if let Some(err) = o.get() {
-                    let err = err.clone();
-                    inp.add_alt_err(&before.inner /*&err.pos*/, err.err);
+        let before = inp.save();
+        let key = (I::cursor_location(&before.cursor().inner), self.id);
+
+        match inp.memos.get_mut(&key) {
+            Some(Memo::Failed(err)) => {
+                let err = err.clone();
+                inp.memos.stats.counts(self.id, self.name).hits += 1;
+                if let Some(err) = err {
+                    inp.add_alt_err(&before.cursor().inner /*&err.pos*/, err.err);
                 } else {
-                    let err_span = inp.span_since(&before);
-                    // TODO: Is this an appropriate way to handle infinite recursion?
+                    let err_span = inp.span_since(before.cursor());
                     inp.add_alt([], None, err_span);
                 }
                 return Err(());
             }
-            hashbrown::hash_map::Entry::Vacant(v) => {
-                v.insert(None);
+            Some(Memo::Growing { seeds, level }) if *level > 0 => {
+                let level = *level;
+                let Seed { end, errors } = seeds[level - 1].clone();
+                inp.memos.recursions += 1;
+                inp.memos.stats.counts(self.id, self.name).hits += 1;
+                return M::choose(
+                    inp,
+                    |inp| self.regrow(inp, key, level - 1),
+                    |inp| {
+                        inp.errors.secondary.extend(errors);
+                        inp.skip_to(end);
+                        Ok(())
+                    },
+                );
+            }
+            Some(memo) => {
+                // We've recursed back into a parser without consuming any input: fail, and take note that the parser
+                // is left-recursive so that its seed can be grown.
+                if let Memo::InProgress { left_recursive } = memo {
+                    *left_recursive = true;
+                }
+                inp.memos.recursions += 1;
+                let err_span = inp.span_since(before.cursor());
+                inp.add_alt([], None, err_span);
+                return Err(());
+            }
+            None => {
+                inp.memos.stats.counts(self.id, self.name).misses += 1;
+                inp.memos.insert(
+                    key,
+                    Memo::InProgress {
+                        left_recursive: false,
+                    },
+                );
             }
         }
 
+        let recursions = inp.memos.recursions;
         let res = self.parser.go::<M>(inp);
+        let left_recursive = matches!(
+            inp.memos.remove(&key),
+            Some(Memo::InProgress {
+                left_recursive: true
+            })
+        );
 
-        if res.is_err() {
-            let alt = inp.take_alt();
-            inp.memos.insert(key, alt);
-        } else {
-            inp.memos.remove(&key);
+        let out = match res {
+            Ok(out) if left_recursive => out,
+            Ok(out) => return Ok(out),
+            Err(()) => {
+                // Failures that depended on the partial result of a left-recursive parser might succeed later
+                if inp.memos.recursions == recursions {
+                    let alt = inp.take_alt();
+                    inp.memos
+                        .insert_failed(key, alt, self.max_entries, self.name);
+                }
+                return Err(());
+            }
+        };
+
+        // The parser is left-recursive at this location. Use the result as a seed and repeatedly parse again, feeding
+        // the seed to the recursive invocation, until the result stops getting longer (as described by Warth et al. in
+        // 'Packrat Parsers Can Support Left Recursion'). Only the ends of the seeds are needed to grow them, so this
+        // happens in check mode.
+        inp.memos.stats.counts(self.id, self.name).left_recursions += 1;
+        let mut seeds = vec![Seed {
+            end: inp.cursor().inner,
+            errors: inp.errors.secondary[before.err_count..].to_vec(),
+        }];
+        loop {
+            inp.rewind(before.clone());
+            let level = seeds.len();
+            inp.memos.insert(key, Memo::Growing { seeds, level });
+
+            let res = self.parser.go::<Check>(inp);
+            let end = inp.cursor().inner;
+            let errors = inp.errors.secondary[before.err_count..].to_vec();
+            seeds = match inp.memos.remove(&key) {
+                Some(Memo::Growing { seeds, .. }) => seeds,
+                _ => unreachable!(),
+            };
+            match res {
+                Ok(())
+                    if I::cursor_location(&end)
+                        > I::cursor_location(&seeds[seeds.len() - 1].end) =>
+                {
+                    seeds.push(Seed { end, errors });
+                }
+                _ => break,
+            }
+        }
+
+        inp.rewind(before);
+        let grown = seeds.len() > 1;
+        let Seed { end, errors } = seeds[seeds.len() - 1].clone();
+        if !grown {
+            inp.errors.secondary.extend(errors);
+            inp.skip_to(end);
+            return Ok(out);
+        }
+
+        // The output of the longest seed is produced by parsing one final time, in the requested mode
+        let level = seeds.len() - 1;
+        inp.memos.insert(key, Memo::Growing { seeds, level });
+        let res = M::choose(
+            &mut *inp,
+            |inp| self.regrow(inp, key, level),
+            |inp| {
+                inp.errors.secondary.extend(errors);
+                inp.skip_to(end);
+                Ok(())
+            },
+        );
+        inp.memos.remove(&key);
+        res
+    }
+
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::Memoized(Box::new(self.parser.node_info(scope)))
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.parser.prefixes()
+    }
+
+    go_extra!(O);
+}
+
+/// See [`Parser::incremental`].
+#[cfg(feature = "memoization")]
+#[derive(Copy, Clone)]
+pub struct Incremental<A> {
+    pub(crate) id: crate::memo::MemoId,
+    pub(crate) parser: A,
+}
+
+#[cfg(feature = "memoization")]
+impl<A> Incremental<A> {
+    /// Get the id under which this parser stores its results in an [`crate::incremental::Cache`].
+    ///
+    /// The id is assigned when the parser is created and is shared by its clones.
+    pub fn id(&self) -> crate::memo::MemoId {
+        self.id
+    }
+
+    /// Store results under the given id, usually that of the parser that this one replaces.
+    ///
+    /// Programs that build a new parser for each parse (such as a language server that rebuilds its parser whenever
+    /// its configuration changes) can use this to keep reusing the results of the old parser.
+    ///
+    /// The parser that the id was taken from must produce the same output for the same input, otherwise its results
+    /// will be reused where they shouldn't be.
+    pub fn with_id(self, id: crate::memo::MemoId) -> Self {
+        Self { id, ..self }
+    }
+}
+
+#[cfg(feature = "memoization")]
+impl<'src, I, E, A, O> Parser<'src, I, O, E> for Incremental<A>
+where
+    I: Input<'src>,
+    E: ParserExtra<'src, I>,
+    A: Parser<'src, I, O, E>,
+    O: Clone + crate::incremental::Rebase + 'static,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, O> {
+        let before = inp.save();
+        let key = (I::cursor_location(&before.cursor().inner), self.id);
+
+        if let Some(reuse) = inp.reuse.as_deref() {
+            if let Some(end) = reuse.end_of(key) {
+                let out = M::choose(reuse, |reuse| reuse.get::<O>(key).ok_or(()), |_| Ok(()));
+                if let Ok(out) = out {
+                    // Skip over the input that the reused output covers, a token at a time if the input can't jump
+                    if !inp.jump_to(end) {
+                        while I::cursor_location(&inp.cursor().inner) < end
+                            && inp.next_maybe_inner().is_some()
+                        {}
+                    }
+                    if I::cursor_location(&inp.cursor().inner) == end {
+                        return Ok(out);
+                    }
+                    // The input doesn't match the cache (perhaps it wasn't informed of an edit), so parse as normal
+                    inp.rewind(before);
+                }
+            }
+        }
+
+        let err_count = inp.errors.secondary.len();
+        let mut res = self.parser.go::<M>(inp);
+
+        // Results that required error recovery aren't reused, since doing so would lose the errors
+        if inp.errors.secondary.len() == err_count {
+            if let Ok(out) = &mut res {
+                let end = I::cursor_location(&inp.cursor().inner);
+                if let Some(reuse) = inp.reuse.as_deref_mut() {
+                    M::map(M::from_mut(out), |out| reuse.insert(key, end, out.clone()));
+                }
+            }
         }
 
         res
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
     go_extra!(O);
 }
 
@@ -908,6 +1720,36 @@ where
         Ok(M::combine(a, b, |a: OA, b: OB| (a, b)))
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::seq([
+            self.parser_a.node_info(scope),
+            self.parser_b.node_info(scope),
+        ])
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser_a.gen_tokens(scope)?;
+        self.parser_b.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.parser_a.prefixes()
+    }
+
     go_extra!((OA, OB));
 }
 
@@ -950,6 +1792,29 @@ where
             },
         }
     }
+
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::seq([
+            self.parser_a.node_info(scope),
+            self.parser_b.node_info(scope),
+        ])
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser_a.gen_tokens(scope)?;
+        self.parser_b.gen_tokens(scope)
+    }
 }
 
 /// See [`Parser::ignore_then`].
@@ -985,6 +1850,36 @@ where
         Ok(M::map(b, |b: OB| b))
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::seq([
+            self.parser_a.node_info(scope),
+            self.parser_b.node_info(scope),
+        ])
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser_a.gen_tokens(scope)?;
+        self.parser_b.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.parser_a.prefixes()
+    }
+
     go_extra!(OB);
 }
 
@@ -1021,6 +1916,36 @@ where
         Ok(M::map(a, |a: OA| a))
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::seq([
+            self.parser_a.node_info(scope),
+            self.parser_b.node_info(scope),
+        ])
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser_a.gen_tokens(scope)?;
+        self.parser_b.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.parser_a.prefixes()
+    }
+
     go_extra!(OA);
 }
 
@@ -1059,7 +1984,7 @@ where
         let alt = inp.errors.alt.take();
 
         #[cfg(feature = "memoization")]
-        let mut memos = HashMap::default();
+        let mut memos = crate::memo::Memos::default();
         let (start, mut cache) = inp2.begin();
         let res = inp.with_input(
             start,
@@ -1070,6 +1995,9 @@ where
             &mut memos,
         );
 
+        #[cfg(feature = "memoization")]
+        inp.memos.stats.merge(memos.stats);
+
         // TODO: Translate secondary error offsets too
         let new_alt = inp.errors.alt.take();
         inp.errors.alt = alt;
@@ -1080,6 +2008,27 @@ where
         res
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        // Only the outer input is described
+        self.parser_b.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        // Only the outer input is generated
+        self.parser_b.gen_tokens(scope)
+    }
+
     go_extra!(O);
 }
 
@@ -1117,6 +2066,26 @@ where
         inp.with_ctx(&p1, |inp| self.then.go::<M>(inp))
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::seq([self.parser.node_info(scope), self.then.node_info(scope)])
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)?;
+        self.then.gen_tokens(scope)
+    }
+
     go_extra!(OB);
 }
 
@@ -1156,6 +2125,26 @@ where
 
         inp.with_ctx(ctx, |inp| self.then.next(inp, inner_state))
     }
+
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::seq([self.parser.node_info(scope), self.then.node_info(scope)])
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)?;
+        self.then.gen_tokens(scope)
+    }
 }
 
 /// See [`Parser::then_with_ctx`].
@@ -1193,6 +2182,26 @@ where
         Ok(M::map(p2, |p2| (p1, p2)))
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::seq([self.parser.node_info(scope), self.then.node_info(scope)])
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)?;
+        self.then.gen_tokens(scope)
+    }
+
     go_extra!((OA, OB));
 }
 
@@ -1232,6 +2241,26 @@ where
 
         inp.with_ctx(ctx, |inp| self.then.next(inp, inner_state))
     }
+
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::seq([self.parser.node_info(scope), self.then.node_info(scope)])
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)?;
+        self.then.gen_tokens(scope)
+    }
 }
 
 /// See [`Parser::with_ctx`].
@@ -1262,6 +2291,32 @@ where
         inp.with_ctx(&self.ctx, |inp| self.parser.go::<M>(inp))
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.parser.prefixes()
+    }
+
     go_extra!(O);
 }
 
@@ -1293,6 +2348,32 @@ where
         inp.with_state(&mut self.state.clone(), |inp| self.parser.go::<M>(inp))
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.parser.prefixes()
+    }
+
     go_extra!(O);
 }
 
@@ -1333,6 +2414,38 @@ where
         Ok(a)
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::seq([
+            self.start.node_info(scope),
+            self.parser.node_info(scope),
+            self.end.node_info(scope),
+        ])
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.start.gen_tokens(scope)?;
+        self.parser.gen_tokens(scope)?;
+        self.end.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.start.prefixes()
+    }
+
     go_extra!(OA);
 }
 
@@ -1370,9 +2483,321 @@ where
         Ok(a)
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::seq([
+            self.padding.node_info(scope),
+            self.parser.node_info(scope),
+            self.padding.node_info(scope),
+        ])
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.padding.gen_tokens(scope)?;
+        self.parser.gen_tokens(scope)?;
+        self.padding.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.padding.prefixes()
+    }
+
     go_extra!(OA);
 }
 
+/// See [`Parser::with_trivia`].
+pub struct WithTrivia<A, T, OT> {
+    pub(crate) parser: A,
+    pub(crate) trivia: T,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<OT>,
+}
+
+impl<A: Copy, T: Copy, OT> Copy for WithTrivia<A, T, OT> {}
+impl<A: Clone, T: Clone, OT> Clone for WithTrivia<A, T, OT> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            trivia: self.trivia.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'src, I, E, A, T, OA, OT> Parser<'src, I, OA, E> for WithTrivia<A, T, OT>
+where
+    I: Input<'src>,
+    E: ParserExtra<'src, I>,
+    A: Parser<'src, I, OA, E>,
+    T: Parser<'src, I, OT, E>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, OA> {
+        let trivia = |inp: &mut InputRef<'src, '_, I, E>| {
+            // Trivia is invisible: failing to find any shouldn't show up in errors or commit to a branch
+            let alt = inp.take_alt();
+            let cut = inp.errors.cut;
+            let before = inp.save();
+            if self.trivia.go::<Check>(inp).is_err() {
+                inp.rewind(before);
+            }
+            inp.errors.alt = alt;
+            inp.errors.cut = cut;
+        };
+        inp.with_trivia(Some(&trivia), |inp| {
+            let out = self.parser.go::<M>(inp)?;
+            inp.skip_trivia();
+            Ok(out)
+        })
+    }
+
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
+    go_extra!(OA);
+}
+
+/// See [`Parser::no_trivia`].
+#[derive(Copy, Clone)]
+pub struct NoTrivia<A> {
+    pub(crate) parser: A,
+}
+
+impl<'src, I, O, E, A> Parser<'src, I, O, E> for NoTrivia<A>
+where
+    I: Input<'src>,
+    E: ParserExtra<'src, I>,
+    A: Parser<'src, I, O, E>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, O> {
+        inp.skip_trivia();
+        inp.with_trivia(None, |inp| self.parser.go::<M>(inp))
+    }
+
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.parser.prefixes()
+    }
+
+    go_extra!(O);
+}
+
+/// A parser that describes itself as a single item of a grammar, hiding the details of its implementation.
+///
+/// This is used by built-in parsers such as [`text::int`] so that grammars mention an integer rather than the
+/// characters that make it up.
+#[derive(Copy, Clone)]
+pub(crate) struct Described<A, D> {
+    pub(crate) parser: A,
+    #[allow(dead_code)]
+    pub(crate) desc: D,
+}
+
+impl<'src, I, O, E, A, D> Parser<'src, I, O, E> for Described<A, D>
+where
+    I: Input<'src>,
+    E: ParserExtra<'src, I>,
+    A: Parser<'src, I, O, E>,
+    D: AsRef<str>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, O> {
+        self.parser.go::<M>(inp)
+    }
+
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, _scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::Special(self.desc.as_ref().into())
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.parser.prefixes()
+    }
+
+    go_extra!(O);
+}
+
+/// A parser that is known to only accept inputs beginning with one of a set of token sequences.
+///
+/// This is used by built-in parsers such as [`text::keyword`] so that [`choice_dispatch`] can index them by their
+/// leading tokens.
+#[derive(Clone)]
+pub(crate) struct Prefixed<A, T> {
+    pub(crate) parser: A,
+    pub(crate) prefixes: Option<Vec<Vec<T>>>,
+}
+
+impl<'src, I, O, E, A> Parser<'src, I, O, E> for Prefixed<A, I::Token>
+where
+    I: Input<'src>,
+    E: ParserExtra<'src, I>,
+    A: Parser<'src, I, O, E>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, O> {
+        self.parser.go::<M>(inp)
+    }
+
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.prefixes.clone()
+    }
+
+    go_extra!(O);
+}
+
+/// See [`Parser::node`].
+#[derive(Copy, Clone)]
+pub struct Node<A, K> {
+    pub(crate) parser: A,
+    pub(crate) kind: K,
+}
+
+impl<'src, I, O, E, A, K> Parser<'src, I, O, E> for Node<A, K>
+where
+    I: Input<'src>,
+    E: ParserExtra<'src, I>,
+    E::State: BorrowMut<cst::Builder<K>>,
+    A: Parser<'src, I, O, E>,
+    K: Clone,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, O> {
+        // Leading trivia belongs to the parent node
+        inp.skip_trivia();
+        let at = I::cursor_location(&inp.cursor().inner);
+        let start = inp.state().borrow_mut().start(self.kind.clone(), at);
+        match self.parser.go::<M>(inp) {
+            Ok(out) => {
+                let at = I::cursor_location(&inp.cursor().inner);
+                inp.state().borrow_mut().finish_node(at);
+                Ok(out)
+            }
+            Err(()) => {
+                inp.state().borrow_mut().abandon(start);
+                Err(())
+            }
+        }
+    }
+
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.parser.prefixes()
+    }
+
+    go_extra!(O);
+}
+
 /// See [`Parser::or`].
 #[derive(Copy, Clone)]
 pub struct Or<A, B> {
@@ -1391,6 +2816,32 @@ where
         self.choice.go::<M>(inp)
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.choice.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.choice.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.choice.prefixes()
+    }
+
     go_extra!(O);
 }
 
@@ -1525,11 +2976,16 @@ where
     #[allow(clippy::nonminimal_bool)] // TODO: Remove this, lint is currently buggy
     fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, ()> {
         if self.at_most == !0 && self.at_least == 0 {
+            self.parser.skip_many(inp);
             loop {
                 let before = inp.save();
+                let outer_cut = core::mem::replace(&mut inp.errors.cut, false);
                 match self.parser.go::<Check>(inp) {
-                    Ok(()) => {}
+                    Ok(()) => inp.errors.cut = outer_cut,
+                    // A cut was passed, so the failure must be propagated rather than ending the repetition
+                    Err(()) if inp.errors.cut => break Err(()),
                     Err(()) => {
+                        inp.errors.cut = outer_cut;
                         inp.rewind(before);
                         break Ok(M::bind(|| ()));
                     }
@@ -1543,6 +2999,9 @@ where
             }
         } else {
             let mut state = self.make_iter::<Check>(inp)?;
+            if self.at_most == !0 {
+                state += self.parser.skip_many(inp);
+            }
             loop {
                 #[cfg(debug_assertions)]
                 let before = inp.cursor();
@@ -1564,6 +3023,33 @@ where
         }
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::Repeat {
+            item: Box::new(self.parser.node_info(scope)),
+            separator: None,
+            min: self.at_least,
+            max: (self.at_most != !0).then_some(self.at_most as usize),
+            leading: false,
+            trailing: false,
+        }
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        let max = (self.at_most != !0).then_some(self.at_most as usize);
+        scope.repeat(self.at_least, max, |_, scope| self.parser.gen_tokens(scope))
+    }
+
     go_extra!(());
 }
 
@@ -1594,12 +3080,16 @@ where
         }
 
         let before = inp.save();
+        let outer_cut = core::mem::replace(&mut inp.errors.cut, false);
         match self.parser.go::<M>(inp) {
             Ok(item) => {
+                inp.errors.cut = outer_cut;
                 *count += 1;
                 Ok(Some(item))
             }
+            Err(()) if inp.errors.cut => Err(()),
             Err(()) => {
+                inp.errors.cut = outer_cut;
                 inp.rewind(before);
                 if *count >= self.at_least {
                     Ok(None)
@@ -1609,6 +3099,33 @@ where
             }
         }
     }
+
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::Repeat {
+            item: Box::new(self.parser.node_info(scope)),
+            separator: None,
+            min: self.at_least,
+            max: (self.at_most != !0).then_some(self.at_most as usize),
+            leading: false,
+            trailing: false,
+        }
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        let max = (self.at_most != !0).then_some(self.at_most as usize);
+        scope.repeat(self.at_least, max, |_, scope| self.parser.gen_tokens(scope))
+    }
 }
 
 impl<'src, A, O, I, E> ConfigIterParser<'src, I, O, E> for Repeated<A, O, I, E>
@@ -1634,12 +3151,16 @@ where
         }
 
         let before = inp.save();
+        let outer_cut = core::mem::replace(&mut inp.errors.cut, false);
         match self.parser.go::<M>(inp) {
             Ok(item) => {
+                inp.errors.cut = outer_cut;
                 *count += 1;
                 Ok(Some(item))
             }
+            Err(()) if inp.errors.cut => Err(()),
             Err(()) => {
+                inp.errors.cut = outer_cut;
                 inp.rewind(before);
                 if *count >= at_least {
                     Ok(None)
@@ -1872,7 +3393,14 @@ where
         }
 
         let before_item = inp.save();
-        match self.parser.go::<M>(inp) {
+        let outer_cut = core::mem::replace(&mut inp.errors.cut, false);
+        let res = self.parser.go::<M>(inp);
+        if res.is_err() && inp.errors.cut {
+            // A cut was passed, so the failure must be propagated rather than ending the sequence
+            return Err(());
+        }
+        inp.errors.cut = outer_cut;
+        match res {
             Ok(item) => {
                 *state += 1;
                 Ok(Some(item))
@@ -1899,6 +3427,39 @@ where
             }
         }
     }
+
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::Repeat {
+            item: Box::new(self.parser.node_info(scope)),
+            separator: Some(Box::new(self.separator.node_info(scope))),
+            min: self.at_least,
+            max: (self.at_most != !0).then_some(self.at_most as usize),
+            leading: self.allow_leading,
+            trailing: self.allow_trailing,
+        }
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        scope.separated(
+            self.at_least,
+            (self.at_most != !0).then_some(self.at_most as usize),
+            self.allow_leading,
+            self.allow_trailing,
+            |scope| self.parser.gen_tokens(scope),
+            |scope| self.separator.gen_tokens(scope),
+        )
+    }
 }
 
 impl<'src, I, E, A, B, OA, OB> Parser<'src, I, (), E> for SeparatedBy<A, B, OA, OB, I, E>
@@ -1931,6 +3492,39 @@ where
         }
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::Repeat {
+            item: Box::new(self.parser.node_info(scope)),
+            separator: Some(Box::new(self.separator.node_info(scope))),
+            min: self.at_least,
+            max: (self.at_most != !0).then_some(self.at_most as usize),
+            leading: self.allow_leading,
+            trailing: self.allow_trailing,
+        }
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        scope.separated(
+            self.at_least,
+            (self.at_most != !0).then_some(self.at_most as usize),
+            self.allow_leading,
+            self.allow_trailing,
+            |scope| self.parser.gen_tokens(scope),
+            |scope| self.separator.gen_tokens(scope),
+        )
+    }
+
     go_extra!(());
 }
 
@@ -1985,6 +3579,25 @@ where
         state.0 += 1;
         Ok(out)
     }
+
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
 }
 
 /// See [`IterParser::collect`].
@@ -2047,6 +3660,25 @@ where
         }
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
     go_extra!(C);
 }
 
@@ -2107,6 +3739,25 @@ where
         Ok(M::map(output, |output| unsafe { C::take(output) }))
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
     go_extra!(C);
 }
 
@@ -2125,13 +3776,37 @@ where
     #[inline(always)]
     fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, Option<O>> {
         let before = inp.save();
-        Ok(match self.parser.go::<M>(inp) {
+        let outer_cut = core::mem::replace(&mut inp.errors.cut, false);
+        let out = match self.parser.go::<M>(inp) {
             Ok(out) => M::map::<O, _, _>(out, Some),
+            // A cut was passed, so the failure must be propagated rather than producing `None`
+            Err(()) if inp.errors.cut => return Err(()),
             Err(()) => {
                 inp.rewind(before);
                 M::bind::<Option<O>, _>(|| None)
             }
-        })
+        };
+        inp.errors.cut = outer_cut;
+        Ok(out)
+    }
+
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::Optional(Box::new(self.parser.node_info(scope)))
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        scope.optional(|scope| self.parser.gen_tokens(scope))
     }
 
     go_extra!(Option<O>);
@@ -2166,18 +3841,89 @@ where
         }
 
         let before = inp.save();
+        let outer_cut = core::mem::replace(&mut inp.errors.cut, false);
         match self.parser.go::<M>(inp) {
             Ok(item) => {
+                inp.errors.cut = outer_cut;
                 *finished = true;
                 Ok(Some(item))
             }
+            Err(()) if inp.errors.cut => Err(()),
             Err(()) => {
+                inp.errors.cut = outer_cut;
                 inp.rewind(before);
                 *finished = true;
                 Ok(None)
             }
         }
     }
+
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::Optional(Box::new(self.parser.node_info(scope)))
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        scope.optional(|scope| self.parser.gen_tokens(scope))
+    }
+}
+
+/// See [`Parser::cut`].
+#[derive(Copy, Clone)]
+pub struct Cut<A> {
+    pub(crate) parser: A,
+}
+
+impl<'src, I, O, E, A> Parser<'src, I, O, E> for Cut<A>
+where
+    I: Input<'src>,
+    E: ParserExtra<'src, I>,
+    A: Parser<'src, I, O, E>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, O> {
+        let out = self.parser.go::<M>(inp)?;
+        inp.errors.cut = true;
+        Ok(out)
+    }
+
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.parser.prefixes()
+    }
+
+    go_extra!(O);
 }
 
 /// See [`Parser::not`].
@@ -2208,12 +3954,15 @@ where
         let before = inp.save();
 
         let alt = inp.errors.alt.take();
+        let outer_cut = inp.errors.cut;
 
         let result = self.parser.go::<Check>(inp);
         let result_span = inp.span_since(before.cursor());
         inp.rewind(before);
 
         inp.errors.alt = alt;
+        // Lookahead never commits the enclosing parser
+        inp.errors.cut = outer_cut;
 
         match result {
             Ok(()) => {
@@ -2229,6 +3978,26 @@ where
         }
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::AnyExcept(Box::new(self.parser.node_info(scope)))
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        _scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        // There's no way to know which tokens aren't matched by the parser
+        Err(generate::unsupported::<Self>())
+    }
+
     go_extra!(());
 }
 
@@ -2311,6 +4080,25 @@ where
             }
         }
     }
+
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
 }
 
 /// See [`Parser::and_is`].
@@ -2348,7 +4136,11 @@ where
                 let after = inp.save();
                 inp.rewind(before);
 
-                match self.parser_b.go::<Check>(inp) {
+                let outer_cut = inp.errors.cut;
+                let res = self.parser_b.go::<Check>(inp);
+                // Lookahead never commits the enclosing parser
+                inp.errors.cut = outer_cut;
+                match res {
                     Ok(()) => {
                         // B succeeded -- go to the end of A and return its output
                         inp.rewind(after);
@@ -2368,6 +4160,32 @@ where
         }
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::seq([
+            grammar::Expr::Lookahead {
+                negated: false,
+                expr: Box::new(self.parser_b.node_info(scope)),
+            },
+            self.parser_a.node_info(scope),
+        ])
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        // Lookahead isn't taken into account
+        self.parser_a.gen_tokens(scope)
+    }
+
     go_extra!(OA);
 }
 
@@ -2438,6 +4256,29 @@ where
         }))
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::seq([
+            self.parser_a.node_info(scope),
+            self.parser_b.node_info(scope),
+        ])
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser_a.gen_tokens(scope)?;
+        self.parser_b.gen_tokens(scope)
+    }
+
     go_extra!(O);
 }
 
@@ -2482,6 +4323,7 @@ where
         let mut a_out = M::bind(Vec::new);
         let mut iter_state = self.parser_a.make_iter::<M>(inp)?;
         loop {
+            inp.skip_trivia();
             let before = inp.cursor();
             match self.parser_a.next::<M>(inp, &mut iter_state) {
                 Ok(Some(out)) => {
@@ -2511,6 +4353,29 @@ where
         }))
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::seq([
+            self.parser_a.node_info(scope),
+            self.parser_b.node_info(scope),
+        ])
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser_a.gen_tokens(scope)?;
+        self.parser_b.gen_tokens(scope)
+    }
+
     go_extra!(O);
 }
 
@@ -2575,6 +4440,36 @@ where
         }
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::seq([
+            self.parser_a.node_info(scope),
+            self.parser_b.node_info(scope),
+        ])
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser_a.gen_tokens(scope)?;
+        self.parser_b.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.parser_a.prefixes()
+    }
+
     go_extra!(O);
 }
 
@@ -2621,6 +4516,7 @@ where
         let mut iter_state = self.parser_b.make_iter::<M>(inp)?;
         loop {
             #[cfg(debug_assertions)]
+            inp.skip_trivia();
             let before = inp.cursor();
             match self.parser_b.next::<M>(inp, &mut iter_state) {
                 Ok(Some(b_out)) => {
@@ -2642,6 +4538,36 @@ where
         }
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::seq([
+            self.parser_a.node_info(scope),
+            self.parser_b.node_info(scope),
+        ])
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser_a.gen_tokens(scope)?;
+        self.parser_b.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.parser_a.prefixes()
+    }
+
     go_extra!(O);
 }
 
@@ -2670,6 +4596,29 @@ where
         }
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::Lookahead {
+            negated: false,
+            expr: Box::new(self.parser.node_info(scope)),
+        }
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        _scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        // Lookahead isn't taken into account
+        Ok(())
+    }
+
     go_extra!(O);
 }
 
@@ -2697,6 +4646,32 @@ where
             .go::<M>(inp)
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.parser.prefixes()
+    }
+
     go_extra!(O);
 }
 
@@ -2772,6 +4747,32 @@ where
         res
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.parser.prefixes()
+    }
+
     go_extra!(O);
 }
 
@@ -2806,6 +4807,7 @@ where
     where
         Self: Sized,
     {
+        inp.skip_trivia();
         let before = inp.cursor();
         let out = self.parser.go::<Emit>(inp)?;
 
@@ -2817,6 +4819,32 @@ where
         Ok(M::bind(|| out))
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.parser.prefixes()
+    }
+
     go_extra!(U);
 }
 
@@ -306,6 +306,13 @@ pub trait Seq<'p, T> {
     fn to_maybe_ref<'b>(item: Self::Item<'b>) -> MaybeRef<'p, T>
     where
         'p: 'b;
+
+    // A description of the whole sequence (or, if `negated`, of everything not in it), such as `[a-z]`, to use in
+    // errors and grammars instead of listing its items.
+    #[doc(hidden)]
+    fn describe(&self, _negated: bool) -> Option<Arc<str>> {
+        None
+    }
 }
 
 impl<'p, T: Clone> Seq<'p, T> for T {
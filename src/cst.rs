@@ -0,0 +1,370 @@
+//! Lossless concrete syntax trees.
+//!
+//! *"The Guide is definitive. Reality is frequently inaccurate."*
+//!
+//! An abstract syntax tree throws away everything that doesn't affect the meaning of a program: whitespace, comments,
+//! punctuation, and so on. Tools like formatters and refactoring engines need all of it. A concrete syntax tree (CST)
+//! keeps every part of the source, such that the source can be reproduced exactly from the tree.
+//!
+//! Trees are built as a side effect of parsing, using a [`Builder`] as the parser's state (see [`ParserExtra::State`]).
+//! [`Parser::node`] wraps the input consumed by a parser into a node of the given kind. Nodes are recorded as events
+//! that are discarded whenever the parser backtracks, so only nodes from the successful parse remain. Once parsing is
+//! complete, [`Builder::finish`] assembles the events into a [`SyntaxNode`].
+//!
+//! Input that is consumed by a node but not by any of its child nodes becomes a [`SyntaxElement::Token`] of that node,
+//! so tokens don't need nodes of their own unless you want to give them a kind.
+//!
+//! # Trivia
+//!
+//! When trivia has been declared with [`Parser::with_trivia`], a node skips trivia *before* it begins, so trivia that
+//! precedes a node is attached to its parent. Trivia between the tokens of a node is attached to the node itself. To
+//! have trivia appear with a kind of its own, give the trivia parser nodes (for example, `comment.node(Kind::Comment)`).
+//!
+//! # Limitations
+//!
+//! Nodes are located by their offset within the input, so they cannot be used within a nested input (see
+//! [`Parser::nested_in`]). Results that are produced without running a parser, such as those reused by
+//! `Parser::incremental`, don't produce nodes.
+//!
+//! # Examples
+//!
+//! ```
+//! # use chumsky::{prelude::*, cst::{Builder, SyntaxElement}};
+//! #[derive(Copy, Clone, Debug, PartialEq)]
+//! enum Kind {
+//!     Root,
+//!     Comment,
+//!     Let,
+//!     Name,
+//!     Number,
+//! }
+//!
+//! type Extra<'src> = extra::Full<Simple<'src, char>, Builder<Kind>, ()>;
+//!
+//! let comment = just::<_, _, Extra>("//")
+//!     .then(any().and_is(just('\n').not()).repeated())
+//!     .node(Kind::Comment);
+//! let trivia = text::whitespace().at_least(1).or(comment.ignored()).repeated();
+//!
+//! let stmt = text::ascii::keyword("let")
+//!     .ignore_then(text::ascii::ident().node(Kind::Name))
+//!     .then_ignore(just('='))
+//!     .then(text::int(10).node(Kind::Number))
+//!     .then_ignore(just(';'))
+//!     .node(Kind::Let);
+//! let file = stmt.repeated().with_trivia(trivia);
+//!
+//! let src = "let x = 1; // one\nlet y=2;\n";
+//! let mut builder = Builder::default();
+//! assert!(!file.parse_with_state(src, &mut builder).has_errors());
+//!
+//! let root = builder.finish(Kind::Root, src.len());
+//! // The tree reproduces the source exactly
+//! assert_eq!(root.to_text(src), src);
+//!
+//! let kinds = root.children().iter().map(|child| match child {
+//!     SyntaxElement::Node(node) => Some(node.kind()),
+//!     SyntaxElement::Token(_) => None,
+//! });
+//! assert_eq!(
+//!     kinds.collect::<Vec<_>>(),
+//!     [Some(&Kind::Let), None, Some(&Kind::Comment), None, Some(&Kind::Let), None],
+//! );
+//! ```
+
+use super::*;
+use crate::{
+    input::{Checkpoint, Cursor},
+    inspector::Inspector,
+};
+
+#[allow(unused)] // for intra-doc links
+use crate::extra::ParserExtra;
+
+#[derive(Clone, Debug)]
+enum Event<K> {
+    Start { kind: K, at: usize },
+    Finish { at: usize },
+}
+
+/// A parser state that records the nodes of a concrete syntax tree as they are parsed.
+///
+/// Nodes recorded by a parser that later backtracks are discarded. Once parsing is complete, use [`Builder::finish`] to
+/// obtain the tree.
+#[derive(Clone, Debug)]
+pub struct Builder<K> {
+    events: Vec<Event<K>>,
+}
+
+impl<K> Default for Builder<K> {
+    fn default() -> Self {
+        Self { events: Vec::new() }
+    }
+}
+
+impl<K> Builder<K> {
+    pub(crate) fn start(&mut self, kind: K, at: usize) -> usize {
+        self.events.push(Event::Start { kind, at });
+        self.events.len() - 1
+    }
+
+    pub(crate) fn finish_node(&mut self, at: usize) {
+        self.events.push(Event::Finish { at });
+    }
+
+    pub(crate) fn abandon(&mut self, start: usize) {
+        self.events.truncate(start);
+    }
+
+    /// Assemble the recorded nodes into a tree, with a root node of the given kind that spans the first `len` units of
+    /// the input (typically, the length of the input).
+    ///
+    /// Recorded nodes become children of the root, and any input that is not covered by a node becomes a token.
+    pub fn finish(self, root: K, len: usize) -> SyntaxNode<K> {
+        let mut stack = vec![SyntaxNode {
+            kind: root,
+            span: 0..len,
+            children: Vec::new(),
+        }];
+        for event in self.events {
+            match event {
+                Event::Start { kind, at } => stack.push(SyntaxNode {
+                    kind,
+                    span: at..at,
+                    children: Vec::new(),
+                }),
+                Event::Finish { at } => {
+                    let mut node = stack.pop().expect("node finished without being started");
+                    node.span.end = at;
+                    node.fill_tokens();
+                    stack
+                        .last_mut()
+                        .expect("node finished without being started")
+                        .children
+                        .push(SyntaxElement::Node(node));
+                }
+            }
+        }
+        let mut root = stack.pop().expect("node started without being finished");
+        assert!(stack.is_empty(), "node started without being finished");
+        root.fill_tokens();
+        root
+    }
+}
+
+impl<'src, K, I: Input<'src>> Inspector<'src, I> for Builder<K> {
+    type Checkpoint = usize;
+    const ON_TOKEN: bool = false;
+    #[inline(always)]
+    fn on_token(&mut self, _: &I::Token) {}
+    #[inline(always)]
+    fn on_save<'parse>(&self, _: &Cursor<'src, 'parse, I>) -> Self::Checkpoint {
+        self.events.len()
+    }
+    #[inline(always)]
+    fn on_rewind<'parse>(&mut self, cp: &Checkpoint<'src, 'parse, I, Self::Checkpoint>) {
+        self.events.truncate(*cp.inspector());
+    }
+}
+
+/// A node of a concrete syntax tree, produced by [`Builder::finish`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SyntaxNode<K> {
+    kind: K,
+    span: Range<usize>,
+    children: Vec<SyntaxElement<K>>,
+}
+
+/// A child of a [`SyntaxNode`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SyntaxElement<K> {
+    /// A child node.
+    Node(SyntaxNode<K>),
+    /// A run of input that is covered by the parent node, but not by any of its child nodes.
+    Token(Range<usize>),
+}
+
+impl<K> SyntaxElement<K> {
+    /// The span of input covered by this element.
+    pub fn span(&self) -> Range<usize> {
+        match self {
+            Self::Node(node) => node.span(),
+            Self::Token(span) => span.clone(),
+        }
+    }
+}
+
+impl<K> SyntaxNode<K> {
+    /// The kind of this node, as given to [`Parser::node`].
+    pub fn kind(&self) -> &K {
+        &self.kind
+    }
+
+    /// The span of input covered by this node.
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+
+    /// The children of this node, in order. Together, they cover the span of the node without gaps.
+    pub fn children(&self) -> &[SyntaxElement<K>] {
+        &self.children
+    }
+
+    /// Iterate over the child nodes of this node, skipping tokens.
+    pub fn child_nodes(&self) -> impl Iterator<Item = &Self> + '_ {
+        self.children.iter().filter_map(|child| match child {
+            SyntaxElement::Node(node) => Some(node),
+            SyntaxElement::Token(_) => None,
+        })
+    }
+
+    /// Iterate over the spans of all tokens within this node and its descendants, in order.
+    ///
+    /// Concatenating the source text of these spans reproduces the text of the node exactly.
+    pub fn tokens(&self) -> impl Iterator<Item = Range<usize>> + '_ {
+        let mut stack = vec![self.children.iter()];
+        core::iter::from_fn(move || loop {
+            match stack.last_mut()?.next() {
+                Some(SyntaxElement::Token(span)) => break Some(span.clone()),
+                Some(SyntaxElement::Node(node)) => stack.push(node.children.iter()),
+                None => {
+                    stack.pop();
+                }
+            }
+        })
+    }
+
+    /// The source text covered by this node.
+    pub fn text<'a>(&self, src: &'a str) -> &'a str {
+        &src[self.span()]
+    }
+
+    /// Reconstruct the source text of this node from its tokens.
+    pub fn to_text(&self, src: &str) -> String {
+        self.tokens().map(|span| &src[span]).collect()
+    }
+
+    /// Add tokens for the parts of this node's span that aren't covered by a child node.
+    fn fill_tokens(&mut self) {
+        let mut children = Vec::with_capacity(self.children.len() * 2 + 1);
+        let mut at = self.span.start;
+        for child in core::mem::take(&mut self.children) {
+            let span = child.span();
+            if span.start > at {
+                children.push(SyntaxElement::Token(at..span.start));
+            }
+            at = at.max(span.end);
+            children.push(child);
+        }
+        if self.span.end > at {
+            children.push(SyntaxElement::Token(at..self.span.end));
+        }
+        self.children = children;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    enum Kind {
+        Root,
+        Call,
+        Index,
+        Name,
+        Comment,
+    }
+
+    type Extra<'src> = extra::Full<EmptyErr, Builder<Kind>, ()>;
+
+    fn kinds(node: &SyntaxNode<Kind>) -> Vec<(Kind, Range<usize>)> {
+        let mut all = vec![(node.kind, node.span())];
+        for child in node.child_nodes() {
+            all.extend(kinds(child));
+        }
+        all
+    }
+
+    #[test]
+    fn backtracking() {
+        let name = text::ascii::ident::<_, Extra>().node(Kind::Name);
+        // Both alternatives begin with a name node, but only the name of the successful one should remain
+        let call = name.then_ignore(just("()")).node(Kind::Call);
+        let index = name.then_ignore(just("[]")).node(Kind::Index);
+        let expr = call.or(index).or(name);
+
+        let parse = |src: &'static str| {
+            let mut builder = Builder::default();
+            assert!(!expr.parse_with_state(src, &mut builder).has_errors());
+            let root = builder.finish(Kind::Root, src.len());
+            assert_eq!(root.to_text(src), src);
+            kinds(&root)
+        };
+
+        assert_eq!(
+            parse("foo[]"),
+            [(Kind::Root, 0..5), (Kind::Index, 0..5), (Kind::Name, 0..3)],
+        );
+        assert_eq!(parse("foo"), [(Kind::Root, 0..3), (Kind::Name, 0..3)]);
+
+        // Lookahead doesn't leave nodes behind
+        let ahead = name.rewind().ignore_then(any().repeated());
+        let mut builder = Builder::default();
+        assert!(!ahead.parse_with_state("abc", &mut builder).has_errors());
+        assert_eq!(
+            builder.finish(Kind::Root, 3).children(),
+            [SyntaxElement::Token(0..3)]
+        );
+    }
+
+    #[test]
+    fn trivia() {
+        let comment = just::<_, _, Extra>("/*")
+            .then(any().and_is(just("*/").not()).repeated())
+            .then(just("*/"))
+            .node(Kind::Comment);
+        let trivia = text::whitespace()
+            .at_least(1)
+            .or(comment.ignored())
+            .repeated();
+
+        let name = text::ascii::ident().node(Kind::Name);
+        let call = name
+            .then(
+                name.separated_by(just(','))
+                    .delimited_by(just('('), just(')')),
+            )
+            .node(Kind::Call)
+            .with_trivia(trivia);
+
+        let src = " /* a */ f ( x /* b */, y ) ";
+        let mut builder = Builder::default();
+        assert!(!call.parse_with_state(src, &mut builder).has_errors());
+        let root = builder.finish(Kind::Root, src.len());
+        assert_eq!(root.to_text(src), src);
+
+        // Leading and trailing trivia belongs to the root, trivia between tokens to the call
+        assert_eq!(
+            kinds(&root),
+            [
+                (Kind::Root, 0..src.len()),
+                (Kind::Comment, 1..8),
+                (Kind::Call, 9..27),
+                (Kind::Name, 9..10),
+                (Kind::Name, 13..14),
+                (Kind::Comment, 15..22),
+                (Kind::Name, 24..25),
+            ],
+        );
+        let call = root.child_nodes().nth(1).unwrap();
+        assert_eq!(call.text(src), "f ( x /* b */, y )");
+        assert_eq!(
+            call.children()
+                .iter()
+                .map(|child| &src[child.span()])
+                .collect::<Vec<_>>(),
+            ["f", " ( ", "x", " ", "/* b */", ", ", "y", " )"],
+        );
+    }
+}
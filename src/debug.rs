@@ -0,0 +1,877 @@
+//! Tools for finding out what a parser did while parsing an input.
+//!
+//! *"I'd far rather be happy than right any day."*
+//!
+//! Parsers marked with [`Parser::debug`] are given a name. When parsing with [`Parser::parse_traced`], each time one
+//! of these parsers is run it records where it was entered, where it exited and whether it succeeded in a [`Trace`].
+//! Rewinds of the input (which happen when a parser backtracks, such as when [`Parser::or`] tries another
+//! alternative) are recorded too, along with the positions that the input was rewound from and to.
+//!
+//! A [`Trace`] can be printed as an indented tree with its [`Display`](fmt::Display) implementation, or exported as a
+//! standalone HTML page with [`Trace::to_html`] or as a JSON timeline of events with [`Trace::to_json`].
+//!
+//! For large inputs, or when looking for the parsers that make a parse slow, a [`Profile`] can be used in place of a
+//! [`Trace`]. Rather than recording every run, it counts the invocations, successes, failures and retries (runs at a
+//! position where the same parser has already run) of each named parser, along with how far it advanced the input and
+//! the time spent in it, and reports the most costly parsers first.
+//!
+//! When not tracing, parsers marked with [`Parser::debug`] do nothing besides running the parser they wrap, so they
+//! can be left in place. With the `tracing` feature enabled, they also emit a span (at the `TRACE` level) for each run
+//! to the [`tracing`](https://docs.rs/tracing) crate, allowing them to appear in the logs of an application.
+//!
+//! Positions are given as the offset of the input's cursor, as produced by [`Input::cursor_location`] (for `&str`,
+//! this is a byte offset).
+//!
+//! # Examples
+//!
+//! ```
+//! # use chumsky::{prelude::*, debug::Trace};
+//! let digit = one_of::<_, _, extra::Err<Simple<char>>>('0'..='9').debug("digit");
+//! let sum = digit
+//!     .clone()
+//!     .then_ignore(just('+'))
+//!     .then(digit.clone())
+//!     .debug("sum");
+//! let expr = sum.to(()).or(digit.to(())).debug("expr");
+//!
+//! let mut trace = Trace::default();
+//! assert!(!expr.parse_traced("7", &mut trace).has_errors());
+//!
+//! assert_eq!(
+//!     trace.to_string(),
+//!     "expr 0..1 ok\n\
+//!      \x20 sum 0..1 failed\n\
+//!      \x20   digit 0..1 ok\n\
+//!      \x20 rewind 1 -> 0\n\
+//!      \x20 digit 0..1 ok\n",
+//! );
+//! ```
+
+use super::*;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use core::{fmt::Write, time::Duration};
+
+/// A parser that records its progress in a [`Trace`]. See [`Parser::debug`].
+#[derive(Copy, Clone)]
+pub struct Debug<A> {
+    pub(crate) parser: A,
+    pub(crate) name: &'static str,
+}
+
+impl<'src, I, O, E, A> Parser<'src, I, O, E> for Debug<A>
+where
+    I: Input<'src>,
+    E: ParserExtra<'src, I>,
+    A: Parser<'src, I, O, E>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, O> {
+        let start = I::cursor_location(&inp.cursor().inner);
+        #[cfg(feature = "tracing")]
+        let span = tracing::trace_span!("parser", name = self.name, start);
+        #[cfg(feature = "tracing")]
+        let _entered = span.enter();
+        if let Some(recorder) = inp.recorder.as_deref_mut() {
+            recorder.enter(self.name, start);
+        }
+
+        let res = self.parser.go::<M>(inp);
+
+        let end = I::cursor_location(&inp.cursor().inner);
+        if let Some(recorder) = inp.recorder.as_deref_mut() {
+            recorder.exit(self.name, start, end, res.is_ok());
+        }
+        #[cfg(feature = "tracing")]
+        tracing::trace!(end, success = res.is_ok(), "exit");
+        res
+    }
+
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.parser.prefixes()
+    }
+
+    go_extra!(O);
+}
+
+/// Something that the parsers marked with [`Parser::debug`] can report their progress to while parsing with
+/// [`Parser::parse_traced`]: either a [`Trace`] or a [`Profile`].
+///
+/// This trait is sealed and so cannot be implemented by other crates.
+pub trait Recorder: Sealed {
+    // Called before parsing begins.
+    #[doc(hidden)]
+    fn begin(&mut self);
+
+    // A parser began running at the given position.
+    #[doc(hidden)]
+    fn enter(&mut self, name: &'static str, pos: usize);
+
+    // A parser finished running.
+    #[doc(hidden)]
+    fn exit(&mut self, name: &'static str, start: usize, end: usize, success: bool);
+
+    // The input was rewound.
+    #[doc(hidden)]
+    fn rewind(&mut self, from: usize, to: usize);
+}
+
+/// Reborrow a recorder for a shorter lifetime, such as that of a nested [`InputRef`].
+pub(crate) fn reborrow<'a>(
+    recorder: &'a mut Option<&mut dyn Recorder>,
+) -> Option<&'a mut dyn Recorder> {
+    match recorder {
+        Some(recorder) => Some(&mut **recorder),
+        None => None,
+    }
+}
+
+/// Something that happened during a traced parse.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum EventKind {
+    /// A parser marked with [`Parser::debug`] began running at the given position.
+    Enter {
+        /// The name of the parser.
+        name: &'static str,
+        /// The position at which the parser began.
+        pos: usize,
+    },
+    /// A parser marked with [`Parser::debug`] finished running.
+    Exit {
+        /// The name of the parser.
+        name: &'static str,
+        /// The position at which the parser began.
+        start: usize,
+        /// The position that the parser reached. For a failed parser, this is where it stopped before the input was
+        /// rewound.
+        end: usize,
+        /// Whether the parser succeeded.
+        success: bool,
+    },
+    /// The input was rewound to an earlier position while a parser marked with [`Parser::debug`] was running.
+    Rewind {
+        /// The position that the input was at.
+        from: usize,
+        /// The position that the input was rewound to.
+        to: usize,
+    },
+}
+
+/// An event of a [`Trace`], along with the number of traced parsers that were running when it happened.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Event {
+    /// The number of parsers marked with [`Parser::debug`] that enclose the event. For [`EventKind::Enter`] and
+    /// [`EventKind::Exit`], this does not include the parser itself.
+    pub depth: usize,
+    /// What happened.
+    pub kind: EventKind,
+}
+
+/// A record of the parsers marked with [`Parser::debug`] that ran during a parse, created with
+/// [`Parser::parse_traced`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Trace {
+    events: Vec<Event>,
+    depth: usize,
+}
+
+/// A parser run, reconstructed from the events of a trace.
+enum Node {
+    Run {
+        name: &'static str,
+        start: usize,
+        end: usize,
+        success: bool,
+        children: Vec<Node>,
+    },
+    Rewind {
+        from: usize,
+        to: usize,
+    },
+    /// A parser that was entered but never exited, because the parse was abandoned.
+    Unfinished {
+        name: &'static str,
+        start: usize,
+        children: Vec<Node>,
+    },
+}
+
+impl Sealed for Trace {}
+impl Recorder for Trace {
+    fn begin(&mut self) {}
+
+    fn enter(&mut self, name: &'static str, pos: usize) {
+        self.events.push(Event {
+            depth: self.depth,
+            kind: EventKind::Enter { name, pos },
+        });
+        self.depth += 1;
+    }
+
+    fn exit(&mut self, name: &'static str, start: usize, end: usize, success: bool) {
+        self.depth = self.depth.saturating_sub(1);
+        self.events.push(Event {
+            depth: self.depth,
+            kind: EventKind::Exit {
+                name,
+                start,
+                end,
+                success,
+            },
+        });
+    }
+
+    fn rewind(&mut self, from: usize, to: usize) {
+        // Rewinds that happen outside of any traced parser, or that don't move the input, aren't interesting
+        if self.depth > 0 && from != to {
+            self.events.push(Event {
+                depth: self.depth,
+                kind: EventKind::Rewind { from, to },
+            });
+        }
+    }
+}
+
+impl Trace {
+    /// The events of the trace, in the order that they happened.
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    /// Discard all recorded events, allowing the trace to be reused for another parse.
+    pub fn clear(&mut self) {
+        self.events.clear();
+        self.depth = 0;
+    }
+
+    /// Reconstruct the tree of parser runs from the events.
+    fn tree(&self) -> Vec<Node> {
+        // Each open run, along with the children collected for it so far
+        let mut stack: Vec<(&'static str, usize, Vec<Node>)> = Vec::new();
+        let mut roots = Vec::new();
+        for event in &self.events {
+            let node = match event.kind {
+                EventKind::Enter { name, pos } => {
+                    stack.push((name, pos, Vec::new()));
+                    continue;
+                }
+                EventKind::Exit {
+                    name,
+                    start,
+                    end,
+                    success,
+                } => {
+                    let children = stack
+                        .pop()
+                        .map(|(_, _, children)| children)
+                        .unwrap_or_default();
+                    Node::Run {
+                        name,
+                        start,
+                        end,
+                        success,
+                        children,
+                    }
+                }
+                EventKind::Rewind { from, to } => Node::Rewind { from, to },
+            };
+            match stack.last_mut() {
+                Some((_, _, children)) => children.push(node),
+                None => roots.push(node),
+            }
+        }
+        while let Some((name, start, children)) = stack.pop() {
+            let node = Node::Unfinished {
+                name,
+                start,
+                children,
+            };
+            match stack.last_mut() {
+                Some((_, _, children)) => children.push(node),
+                None => roots.push(node),
+            }
+        }
+        roots
+    }
+
+    /// Render the trace as a standalone HTML page, showing each parser run as a collapsible section with a bar
+    /// indicating the part of the input that it covered.
+    pub fn to_html(&self) -> String {
+        fn render(nodes: &[Node], len: usize, out: &mut String) {
+            // Widths are given as a percentage of the furthest position reached
+            let pct = |pos: usize| pos as f64 * 100.0 / len.max(1) as f64;
+            for node in nodes {
+                let (class, label, start, end, children) = match node {
+                    Node::Run {
+                        name,
+                        start,
+                        end,
+                        success,
+                        children,
+                    } => (
+                        if *success { "ok" } else { "failed" },
+                        format!(
+                            "{} {}..{} {}",
+                            escape(name),
+                            start,
+                            end,
+                            if *success { "ok" } else { "failed" }
+                        ),
+                        *start,
+                        *end,
+                        &children[..],
+                    ),
+                    Node::Unfinished {
+                        name,
+                        start,
+                        children,
+                    } => (
+                        "unfinished",
+                        format!("{} @{} unfinished", escape(name), start),
+                        *start,
+                        *start,
+                        &children[..],
+                    ),
+                    Node::Rewind { from, to } => (
+                        "rewind",
+                        format!("rewind {from} -&gt; {to}"),
+                        *to.min(from),
+                        *to.max(from),
+                        &[][..],
+                    ),
+                };
+                let bar = format!(
+                    r#"<span class="bar"><span style="margin-left:{:.2}%;width:{:.2}%"></span></span>"#,
+                    pct(start),
+                    pct(end - start),
+                );
+                if children.is_empty() {
+                    writeln!(out, r#"<div class="{class} leaf">{bar}{label}</div>"#).unwrap();
+                } else {
+                    writeln!(
+                        out,
+                        r#"<details class="{class}" open><summary>{bar}{label}</summary>"#
+                    )
+                    .unwrap();
+                    render(children, len, out);
+                    writeln!(out, "</details>").unwrap();
+                }
+            }
+        }
+
+        let len = self
+            .events
+            .iter()
+            .map(|event| match event.kind {
+                EventKind::Enter { pos, .. } => pos,
+                EventKind::Exit { end, .. } => end,
+                EventKind::Rewind { from, .. } => from,
+            })
+            .max()
+            .unwrap_or(0);
+        let mut body = String::new();
+        render(&self.tree(), len, &mut body);
+        format!(
+            concat!(
+                "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Parse trace</title>\n<style>\n",
+                "body{{font:13px monospace}}\n",
+                "details,.leaf{{margin-left:16px}}\n",
+                "summary,.leaf{{padding:1px 0}}\n",
+                ".leaf{{padding-left:12px}}\n",
+                ".bar{{display:inline-block;width:160px;height:8px;margin-right:8px;background:#eee}}\n",
+                ".bar>span{{display:block;height:100%;min-width:1px}}\n",
+                ".ok>summary>.bar>span,.ok>.bar>span{{background:#3a3}}\n",
+                ".failed>summary>.bar>span,.failed>.bar>span{{background:#c33}}\n",
+                ".rewind{{color:#888}}\n.rewind>.bar>span{{background:#888}}\n",
+                ".unfinished>summary>.bar>span,.unfinished>.bar>span{{background:#888}}\n",
+                "</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+            ),
+            body,
+        )
+    }
+
+    /// Render the trace as a JSON array of events, in the order that they happened.
+    ///
+    /// Each event is an object with a `kind` (`"enter"`, `"exit"` or `"rewind"`), a `depth`, and the fields of the
+    /// corresponding [`EventKind`].
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("[");
+        for (i, event) in self.events.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            let depth = event.depth;
+            match &event.kind {
+                EventKind::Enter { name, pos } => write!(
+                    out,
+                    r#"{{"kind":"enter","depth":{depth},"name":{},"pos":{pos}}}"#,
+                    json_string(name),
+                ),
+                EventKind::Exit {
+                    name,
+                    start,
+                    end,
+                    success,
+                } => write!(
+                    out,
+                    r#"{{"kind":"exit","depth":{depth},"name":{},"start":{start},"end":{end},"success":{success}}}"#,
+                    json_string(name),
+                ),
+                EventKind::Rewind { from, to } => write!(
+                    out,
+                    r#"{{"kind":"rewind","depth":{depth},"from":{from},"to":{to}}}"#,
+                ),
+            }
+            .unwrap();
+        }
+        out.push(']');
+        out
+    }
+}
+
+impl fmt::Display for Trace {
+    /// Display the trace as a tree, with each parser run on its own line and the runs it contains indented beneath it.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn render(nodes: &[Node], indent: usize, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            for node in nodes {
+                write!(f, "{:indent$}", "", indent = indent * 2)?;
+                match node {
+                    Node::Run {
+                        name,
+                        start,
+                        end,
+                        success,
+                        children,
+                    } => {
+                        writeln!(
+                            f,
+                            "{name} {start}..{end} {}",
+                            if *success { "ok" } else { "failed" }
+                        )?;
+                        render(children, indent + 1, f)?;
+                    }
+                    Node::Unfinished {
+                        name,
+                        start,
+                        children,
+                    } => {
+                        writeln!(f, "{name} @{start} unfinished")?;
+                        render(children, indent + 1, f)?;
+                    }
+                    Node::Rewind { from, to } => writeln!(f, "rewind {from} -> {to}")?,
+                }
+            }
+            Ok(())
+        }
+        render(&self.tree(), 0, f)
+    }
+}
+
+/// Statistics about the runs of a parser marked with [`Parser::debug`], collected by a [`Profile`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct RuleStats {
+    /// The number of times that the parser was run.
+    pub invocations: usize,
+    /// The number of runs that succeeded.
+    pub successes: usize,
+    /// The number of runs that failed.
+    pub failures: usize,
+    /// The number of runs that began at a position at which the parser had already been run during the same parse.
+    /// A high number usually means that the parser is being re-parsed after backtracking, and may benefit from being
+    /// restructured or [memoized](Parser::memoized).
+    ///
+    /// To keep memory use bounded, a [`Profile`] forgets the positions before the start of the last successful run
+    /// of an outermost parser marked with [`Parser::debug`], so runs repeated after an unmarked parser backtracks
+    /// further than that aren't counted.
+    pub retries: usize,
+    /// The distance that the cursor advanced during successful runs, in the units of [`Input::cursor_location`]. This
+    /// is not necessarily a number of tokens: for `&str`, it is a number of bytes.
+    pub advanced: usize,
+    /// The amount of input that was rewound while this was the innermost running parser marked with
+    /// [`Parser::debug`], in the units of [`Input::cursor_location`].
+    pub rewound: usize,
+    /// The time spent running the parser, including the time spent in the parsers that it contains. Recursive runs
+    /// are only counted once. This is only measured when the `std` feature is enabled.
+    pub total_time: Duration,
+    /// The time spent running the parser, excluding the time spent in the parsers marked with [`Parser::debug`] that
+    /// it contains. This is only measured when the `std` feature is enabled.
+    pub self_time: Duration,
+}
+
+/// A parser run that a [`Profile`] is waiting to finish.
+#[derive(Clone, Debug)]
+struct Frame {
+    name: &'static str,
+    #[cfg(feature = "std")]
+    began: std::time::Instant,
+    /// The time spent in the runs that this run contains.
+    children: Duration,
+}
+
+/// A summary of the work done by each parser marked with [`Parser::debug`], collected with [`Parser::parse_traced`].
+///
+/// Unlike a [`Trace`], which records every run, a profile only keeps a running total for each name, so it can be used
+/// for large inputs. Statistics accumulate across parses until [`Profile::clear`] is called.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, debug::Profile};
+/// let number = text::int::<_, extra::Err<Simple<char>>>(10).debug("number");
+/// // Both alternatives begin with a number, so the number is parsed twice when the first fails
+/// let value = number
+///     .clone()
+///     .then_ignore(just('%'))
+///     .or(number)
+///     .debug("value");
+/// let values = value.separated_by(just(',')).collect::<Vec<_>>();
+///
+/// let mut profile = Profile::default();
+/// assert!(!values.parse_traced("1,20%,3", &mut profile).has_errors());
+///
+/// let number = profile.rule("number").unwrap();
+/// assert_eq!(number.invocations, 5);
+/// assert_eq!(number.retries, 2);
+/// assert_eq!(profile.rule("value").unwrap().advanced, 5);
+///
+/// // Print a table of the parsers, the most costly first
+/// println!("{profile}");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Profile {
+    rules: HashMap<&'static str, RuleStats>,
+    /// The parsers that have been run at each position during the current parse, used to detect retries.
+    seen: BTreeMap<usize, Vec<&'static str>>,
+    stack: Vec<Frame>,
+}
+
+impl Sealed for Profile {}
+impl Recorder for Profile {
+    fn begin(&mut self) {
+        self.seen.clear();
+        self.stack.clear();
+    }
+
+    fn enter(&mut self, name: &'static str, pos: usize) {
+        let stats = self.rules.entry(name).or_default();
+        stats.invocations += 1;
+        let names = self.seen.entry(pos).or_default();
+        if names.contains(&name) {
+            stats.retries += 1;
+        } else {
+            names.push(name);
+        }
+        self.stack.push(Frame {
+            name,
+            #[cfg(feature = "std")]
+            began: std::time::Instant::now(),
+            children: Duration::ZERO,
+        });
+    }
+
+    fn exit(&mut self, name: &'static str, start: usize, end: usize, success: bool) {
+        let Some(frame) = self.stack.pop() else {
+            return;
+        };
+        #[cfg(feature = "std")]
+        let elapsed = frame.began.elapsed();
+        #[cfg(not(feature = "std"))]
+        let elapsed = Duration::ZERO;
+        if let Some(parent) = self.stack.last_mut() {
+            parent.children += elapsed;
+        }
+
+        let recursive = self.stack.iter().any(|frame| frame.name == name);
+        let stats = self.rules.entry(name).or_default();
+        if success {
+            stats.successes += 1;
+            stats.advanced += end.saturating_sub(start);
+        } else {
+            stats.failures += 1;
+        }
+        // Once an outermost run succeeds, parsers that backtrack before its start are rare enough not to track
+        if success && self.stack.is_empty() {
+            self.seen = self.seen.split_off(&start);
+        }
+        stats.self_time += elapsed.saturating_sub(frame.children);
+        if !recursive {
+            stats.total_time += elapsed;
+        }
+    }
+
+    fn rewind(&mut self, from: usize, to: usize) {
+        if let Some(frame) = self.stack.last() {
+            self.rules.entry(frame.name).or_default().rewound += from.saturating_sub(to);
+        }
+    }
+}
+
+impl Profile {
+    /// The statistics collected for the parser with the given name, if it has been run.
+    pub fn rule(&self, name: &str) -> Option<&RuleStats> {
+        self.rules.get(name)
+    }
+
+    /// The statistics collected for each parser that has been run, in no particular order.
+    pub fn rules(&self) -> impl Iterator<Item = (&'static str, &RuleStats)> + '_ {
+        self.rules.iter().map(|(name, stats)| (*name, stats))
+    }
+
+    /// The statistics collected for each parser that has been run, the most costly first.
+    ///
+    /// Parsers are ordered by the time spent in them (excluding the parsers that they contain), then by the number of
+    /// retries, then by the amount of input rewound, then by the number of invocations.
+    pub fn hot_spots(&self) -> Vec<(&'static str, &RuleStats)> {
+        let mut rules = self.rules().collect::<Vec<_>>();
+        rules.sort_by(|(a_name, a), (b_name, b)| {
+            (b.self_time, b.retries, b.rewound, b.invocations, a_name).cmp(&(
+                a.self_time,
+                a.retries,
+                a.rewound,
+                a.invocations,
+                b_name,
+            ))
+        });
+        rules
+    }
+
+    /// Discard all collected statistics, allowing the profile to be reused.
+    pub fn clear(&mut self) {
+        self.rules.clear();
+        self.seen.clear();
+        self.stack.clear();
+    }
+}
+
+impl fmt::Display for Profile {
+    /// Display the statistics as a table, the most costly parser first (see [`Profile::hot_spots`]).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rules = self.hot_spots();
+        let width = rules
+            .iter()
+            .map(|(name, _)| name.len())
+            .chain([4])
+            .max()
+            .unwrap_or_default();
+        writeln!(
+            f,
+            "{:<width$} {:>8} {:>8} {:>8} {:>8} {:>9} {:>8} {:>12} {:>12}",
+            "rule", "calls", "ok", "failed", "retries", "advanced", "rewound", "self", "total",
+        )?;
+        for (name, stats) in rules {
+            writeln!(
+                f,
+                "{:<width$} {:>8} {:>8} {:>8} {:>8} {:>9} {:>8} {:>12} {:>12}",
+                name,
+                stats.invocations,
+                stats.successes,
+                stats.failures,
+                stats.retries,
+                stats.advanced,
+                stats.rewound,
+                format!("{:?}", stats.self_time),
+                format!("{:?}", stats.total_time),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Extra = extra::Err<EmptyErr>;
+
+    #[test]
+    fn events() {
+        let item = just::<_, &str, Extra>("ab")
+            .debug("ab")
+            .or(just("ac").debug("ac"));
+        let items = item
+            .separated_by(just(','))
+            .collect::<Vec<_>>()
+            .debug("items");
+        let prefixed = just::<_, &str, Extra>('!')
+            .ignore_then(items)
+            .debug("prefixed");
+
+        let mut trace = Trace::default();
+        assert!(!prefixed.parse_traced("!ab,ac", &mut trace).has_errors());
+        assert_eq!(
+            trace.to_string(),
+            "prefixed 0..6 ok\n\
+             \x20 items 1..6 ok\n\
+             \x20   ab 1..3 ok\n\
+             \x20   ab 4..5 failed\n\
+             \x20     rewind 6 -> 5\n\
+             \x20   rewind 5 -> 4\n\
+             \x20   ac 4..6 ok\n",
+        );
+        assert_eq!(trace.events().len(), 12);
+        assert_eq!(
+            trace.events()[3],
+            Event {
+                depth: 2,
+                kind: EventKind::Exit {
+                    name: "ab",
+                    start: 1,
+                    end: 3,
+                    success: true,
+                },
+            }
+        );
+
+        // Parsers without debug markers don't record anything, even when they backtrack
+        let mut other = Trace::default();
+        let plain = just::<_, &str, Extra>('!')
+            .ignore_then(just("ab").or(just("ac")).separated_by(just(',')));
+        assert!(!plain.parse_traced("!ac,ab", &mut other).has_errors());
+        assert!(other.events().is_empty());
+
+        trace.clear();
+        assert!(prefixed.parse_traced("ab", &mut trace).has_errors());
+        assert_eq!(trace.to_string(), "prefixed 0..0 failed\n  rewind 1 -> 0\n");
+    }
+
+    #[test]
+    fn export() {
+        let quote = just::<_, &str, Extra>('"').debug("\"quote\"");
+        let mut trace = Trace::default();
+        assert!(quote
+            .then(quote.debug("<b>"))
+            .parse_traced("\"'", &mut trace)
+            .has_errors());
+
+        assert_eq!(
+            trace.to_json(),
+            concat!(
+                r#"[{"kind":"enter","depth":0,"name":"\"quote\"","pos":0},"#,
+                r#"{"kind":"exit","depth":0,"name":"\"quote\"","start":0,"end":1,"success":true},"#,
+                r#"{"kind":"enter","depth":0,"name":"<b>","pos":1},"#,
+                r#"{"kind":"enter","depth":1,"name":"\"quote\"","pos":1},"#,
+                r#"{"kind":"rewind","depth":2,"from":2,"to":1},"#,
+                r#"{"kind":"exit","depth":1,"name":"\"quote\"","start":1,"end":1,"success":false},"#,
+                r#"{"kind":"exit","depth":0,"name":"<b>","start":1,"end":1,"success":false}]"#,
+            ),
+        );
+
+        let html = trace.to_html();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.ends_with("</html>\n"));
+        assert_eq!(html.matches("<details").count(), 2);
+        assert!(html.as_str().contains("&lt;b&gt; 1..1 failed"));
+        assert!(html.as_str().contains("&quot;quote&quot; 0..1 ok"));
+    }
+
+    #[test]
+    fn profile() {
+        let expr = recursive(|expr| {
+            let atom = text::int::<&str, Extra>(10)
+                .to(())
+                .or(expr.delimited_by(just('('), just(')')))
+                .debug("atom");
+            atom.clone()
+                .then_ignore(just('+'))
+                .then(atom.clone())
+                .to(())
+                .or(atom)
+                .debug("expr")
+        });
+
+        let mut profile = Profile::default();
+        assert!(!expr.parse_traced("(1+2)", &mut profile).has_errors());
+
+        let atom = profile.rule("atom").unwrap();
+        assert_eq!((atom.invocations, atom.successes, atom.failures), (6, 6, 0));
+        // Without a `+` after the outer atom, it's parsed again (along with everything inside it)
+        assert_eq!(atom.retries, 3);
+        assert_eq!(atom.advanced, 14);
+        // Recursive runs are only included in the total time once
+        assert!(atom.total_time >= atom.self_time);
+
+        let expr_stats = profile.rule("expr").unwrap();
+        assert_eq!((expr_stats.invocations, expr_stats.retries), (3, 1));
+        assert_eq!(expr_stats.rewound, 5);
+        assert!(expr_stats.total_time >= expr_stats.self_time);
+
+        // Retries are only counted within a single parse, but other statistics accumulate
+        assert!(!expr.parse_traced("3", &mut profile).has_errors());
+        let atom = profile.rule("atom").unwrap();
+        assert_eq!((atom.invocations, atom.retries), (8, 4));
+        assert_eq!(profile.hot_spots().len(), 2);
+        assert_eq!(profile.rules().count(), 2);
+        assert!(profile.to_string().starts_with("rule "));
+
+        // Positions before the last successful outermost run are forgotten, so long inputs don't grow the profile
+        profile.clear();
+        let items = text::int::<&str, Extra>(10)
+            .debug("int")
+            .separated_by(just(','))
+            .collect::<Vec<_>>();
+        let input = (0..1000)
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        assert!(!items.parse_traced(&input, &mut profile).has_errors());
+        assert_eq!(profile.rule("int").unwrap().invocations, 1000);
+        assert_eq!(profile.seen.len(), 1);
+
+        profile.clear();
+        assert!(profile.rule("atom").is_none());
+        assert_eq!(profile.to_string().lines().count(), 1);
+    }
+}
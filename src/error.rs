@@ -7,7 +7,7 @@
 //! like [`Cheap`], [`Simple`] or [`Rich`].
 
 use super::*;
-use alloc::{borrow::Cow, string::ToString};
+use alloc::{borrow::Cow, format, string::ToString};
 
 pub use label::LabelError;
 
@@ -302,6 +302,7 @@ impl<'a, T> From<DefaultExpected<'a, T>> for RichPattern<'a, T> {
             DefaultExpected::Any => Self::Any,
             DefaultExpected::SomethingElse => Self::SomethingElse,
             DefaultExpected::EndOfInput => Self::EndOfInput,
+            DefaultExpected::Class(pattern) => Self::Label(Cow::Owned(pattern.as_ref().into())),
         }
     }
 }
@@ -325,6 +326,32 @@ where
     }
 }
 
+impl<T: fmt::Debug> From<primitive::DuplicateItem<T>> for RichPattern<'_, T> {
+    fn from(dup: primitive::DuplicateItem<T>) -> Self {
+        let Some(prefix) = dup.prefix else {
+            return Self::Label(Cow::Owned(format!("no repeat of item {}", dup.index)));
+        };
+        let tokens = prefix
+            .iter()
+            .map(|tok| format!("{tok:?}"))
+            .collect::<Vec<_>>();
+        // Characters read better as a string than as a list of tokens
+        let item = if tokens
+            .iter()
+            .all(|tok| tok.len() > 2 && tok.starts_with('\'') && tok.ends_with('\''))
+        {
+            let chars = tokens
+                .iter()
+                .map(|tok| &tok[1..tok.len() - 1])
+                .collect::<String>();
+            format!("\"{chars}\"")
+        } else {
+            tokens.join(" ")
+        };
+        Self::Label(Cow::Owned(format!("no repeat of {item}")))
+    }
+}
+
 impl<'a, T> From<MaybeRef<'a, T>> for RichPattern<'a, T> {
     fn from(tok: MaybeRef<'a, T>) -> Self {
         Self::Token(tok)
@@ -0,0 +1,524 @@
+//! Generation of random inputs that a parser accepts.
+//!
+//! *"Time is an illusion. Lunchtime doubly so."*
+//!
+//! [`Parser::generate`] walks the structure of a parser and produces a random sequence of tokens that follows it:
+//! [`just`] produces its tokens, [`one_of`] picks one of its tokens, [`choice`] and [`Parser::or`] pick an
+//! alternative, [`Parser::repeated`] and [`Parser::separated_by`] pick a number of items, and so on. This is useful for
+//! fuzzing and property testing, such as checking that an interpreter doesn't crash on any program that its parser
+//! accepts, or that printing a parsed AST and parsing it again produces the same AST.
+//!
+//! Randomness comes from a [`Generator`], which is seeded so that generated inputs can be reproduced. It also controls
+//! the size of generated inputs:
+//!
+//! - [`Generator::max_depth`] limits how deeply [`Recursive`] parsers nest
+//! - [`Generator::max_size`] limits (approximately) how many tokens are generated
+//! - [`Generator::max_repeat`] limits how many extra items repetitions produce beyond their minimum
+//!
+//! Once an input gets too deep or too large, the generator tries to finish it as quickly as possible: repetitions
+//! produce as few items as they can, optional parsers produce nothing, and alternatives that recurse too deeply are
+//! abandoned in favour of others.
+//!
+//! # Limitations
+//!
+//! Generated inputs follow the *structure* of a parser, but checks that depend on outputs or on the surrounding input
+//! are not taken into account. In particular, [`Parser::filter`], [`Parser::try_map`], [`Parser::validate`],
+//! lookahead (such as [`Parser::and_is`] and [`Parser::rewind`]) and context-sensitive parsers may reject some of the
+//! inputs that are generated, so you may need to parse a generated input to check it before using it.
+//!
+//! The generator also doesn't know where one token must end for the next to begin. Parsers like [`text::int`] and
+//! [`text::ascii::ident`] consume as much input as they can, so two of them generated with nothing required between
+//! them run together: `text::int(10).then(text::int(10))` might generate `"1234"`, which parses as one integer
+//! followed by a missing one. [`Parser::padded`] always generates some whitespace after its parser to keep it apart from
+//! what follows, but separators that may be empty, like [`text::whitespace`], often generate nothing at all. Where a
+//! grammar relies on a separator between tokens, require at least one (with `text::whitespace().at_least(1)`, for
+//! example) or use [`Parser::padded`].
+//!
+//! Some parsers, like [`any`], [`none_of`] and [`select!`], can't be generated at all, because there's no way to know
+//! which tokens they accept. Generating a parser containing one of these produces [`GenerateError::Unsupported`]. Use
+//! [`Parser::generate_with`] to tell the generator what they should produce. The parsers in the [`text`] module, like
+//! [`text::int`] and [`text::ascii::ident`], already do this.
+//!
+//! # Examples
+//!
+//! ```
+//! # use chumsky::{prelude::*, generate::Generator};
+//! fn expr<'src>() -> impl Parser<'src, &'src str, (), extra::Err<Simple<'src, char>>> {
+//!     recursive(|expr| {
+//!         let atom = text::int(10)
+//!             .ignored()
+//!             .or(expr.delimited_by(just('('), just(')')));
+//!         atom.clone().foldl(one_of("+*").then(atom).repeated(), |_, _| ())
+//!     })
+//! }
+//!
+//! let mut generator = Generator::new(42).max_depth(4);
+//! for _ in 0..100 {
+//!     let input = expr().generate(&mut generator).unwrap().into_iter().collect::<String>();
+//!     assert!(!expr().parse(input.as_str()).has_errors(), "{input:?} was generated but not accepted");
+//! }
+//! ```
+
+use super::*;
+
+/// An error produced when a parser can't be generated. See [`Parser::generate`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum GenerateError {
+    /// The parser contains a parser (named by its type) that doesn't know which tokens it accepts, such as [`any`].
+    /// Use [`Parser::generate_with`] to tell the generator what it should produce.
+    Unsupported(&'static str),
+    /// No input could be found within the limits of the [`Generator`], usually because recursion can't be ended, or
+    /// because the parser can never succeed (such as [`one_of`] with no tokens).
+    Exhausted,
+}
+
+impl fmt::Display for GenerateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsupported(parser) => write!(f, "inputs cannot be generated for `{parser}`"),
+            Self::Exhausted => write!(f, "no input could be generated within the limits"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for GenerateError {}
+
+/// The error produced by parsers that don't know how to generate inputs, naming the parser by its type.
+pub(crate) fn unsupported<T: ?Sized>() -> GenerateError {
+    let name = core::any::type_name::<T>();
+    // Strip the module path and generic parameters, leaving just the name of the parser
+    let name = name.split('<').next().unwrap_or(name);
+    GenerateError::Unsupported(name.rsplit("::").next().unwrap_or(name))
+}
+
+/// A seedable source of randomness for [`Parser::generate`], along with limits on the size of generated inputs.
+///
+/// The same seed always produces the same sequence of inputs (for the same parser and limits).
+#[derive(Clone, Debug)]
+pub struct Generator {
+    state: u64,
+    max_depth: usize,
+    max_size: usize,
+    max_repeat: usize,
+}
+
+impl Generator {
+    /// The number of times an alternative may be abandoned during the generation of a single input before giving up.
+    const MAX_BACKTRACKS: usize = 10_000;
+
+    /// Create a new generator with the given seed.
+    ///
+    /// By default, recursion is limited to a depth of 16, inputs are limited to roughly 256 tokens, and repetitions
+    /// produce at most 4 items beyond their minimum.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            state: seed,
+            max_depth: 16,
+            max_size: 256,
+            max_repeat: 4,
+        }
+    }
+
+    /// Set the depth of recursion beyond which the generator tries to finish an input as quickly as possible.
+    /// Recursion never goes more than twice this deep.
+    pub fn max_depth(self, max_depth: usize) -> Self {
+        Self { max_depth, ..self }
+    }
+
+    /// Set the number of tokens beyond which the generator tries to finish an input as quickly as possible.
+    ///
+    /// This is a soft limit: tokens that are required to complete the input are still generated.
+    pub fn max_size(self, max_size: usize) -> Self {
+        Self { max_size, ..self }
+    }
+
+    /// Set the maximum number of items that a repetition produces beyond its minimum (such as the minimum set by
+    /// [`Repeated::at_least`]).
+    pub fn max_repeat(self, max_repeat: usize) -> Self {
+        Self { max_repeat, ..self }
+    }
+
+    /// Produce the next random number.
+    pub fn next_u64(&mut self) -> u64 {
+        // SplitMix64
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Produce a random number below `n`. Returns `0` if `n` is `0`.
+    pub fn below(&mut self, n: usize) -> usize {
+        if n == 0 {
+            0
+        } else {
+            (self.next_u64() % n as u64) as usize
+        }
+    }
+}
+
+/// An input that is in the process of being generated, passed to functions given to [`Parser::generate_with`].
+pub struct Scope<'a, T> {
+    generator: &'a mut Generator,
+    tokens: Vec<T>,
+    depth: usize,
+    backtracks: usize,
+}
+
+impl<'a, T> Scope<'a, T> {
+    pub(crate) fn new(generator: &'a mut Generator) -> Self {
+        Self {
+            generator,
+            tokens: Vec::new(),
+            depth: 0,
+            backtracks: 0,
+        }
+    }
+
+    pub(crate) fn into_tokens(self) -> Vec<T> {
+        self.tokens
+    }
+
+    /// The generator providing randomness for this input.
+    pub fn generator(&mut self) -> &mut Generator {
+        self.generator
+    }
+
+    /// Add a token to the end of the input.
+    pub fn push(&mut self, token: T) {
+        self.tokens.push(token);
+    }
+
+    /// Add several tokens to the end of the input.
+    pub fn extend<U: IntoIterator<Item = T>>(&mut self, tokens: U) {
+        self.tokens.extend(tokens);
+    }
+
+    /// Whether the input has become deep or large enough that it should be finished as quickly as possible. When
+    /// this is the case, functions given to [`Parser::generate_with`] should produce short inputs.
+    pub fn is_limited(&self) -> bool {
+        self.depth >= self.generator.max_depth || self.tokens.len() >= self.generator.max_size
+    }
+
+    /// Run `f` one level of recursion deeper, failing if recursion has gone too deep.
+    pub(crate) fn recurse(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<(), GenerateError>,
+    ) -> Result<(), GenerateError> {
+        if self.depth >= self.generator.max_depth.saturating_mul(2).max(1) {
+            return Err(GenerateError::Exhausted);
+        }
+        self.depth += 1;
+        let res = f(self);
+        self.depth -= 1;
+        res
+    }
+
+    /// Try `f`, undoing any tokens it produced if it fails because recursion went too deep.
+    fn attempt(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<(), GenerateError>,
+    ) -> Result<(), GenerateError> {
+        let before = self.tokens.len();
+        let res = f(self);
+        if let Err(GenerateError::Exhausted) = res {
+            self.tokens.truncate(before);
+            self.backtracks += 1;
+        }
+        res
+    }
+
+    /// Generate one of `n` alternatives, with `f` generating the alternative with the given index.
+    ///
+    /// Alternatives are picked at random, falling back to others if they recurse too deeply. Once the input is
+    /// limited, alternatives are tried in order, on the basis that earlier alternatives are usually simpler.
+    pub(crate) fn choice(
+        &mut self,
+        n: usize,
+        mut f: impl FnMut(usize, &mut Self) -> Result<(), GenerateError>,
+    ) -> Result<(), GenerateError> {
+        let first = if self.is_limited() {
+            0
+        } else {
+            self.generator.below(n)
+        };
+        for i in 0..n {
+            if self.backtracks > Generator::MAX_BACKTRACKS {
+                break;
+            }
+            match self.attempt(|scope| f((first + i) % n, scope)) {
+                Err(GenerateError::Exhausted) => {}
+                res => return res,
+            }
+        }
+        Err(GenerateError::Exhausted)
+    }
+
+    /// Generate `f` or nothing.
+    pub(crate) fn optional(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<(), GenerateError>,
+    ) -> Result<(), GenerateError> {
+        if self.is_limited() || self.generator.below(2) == 0 {
+            return Ok(());
+        }
+        match self.attempt(f) {
+            Err(GenerateError::Exhausted) => Ok(()),
+            res => res,
+        }
+    }
+
+    /// Generate between `min` and `max` items, with `f` generating the item with the given index.
+    pub(crate) fn repeat(
+        &mut self,
+        min: usize,
+        max: Option<usize>,
+        mut f: impl FnMut(usize, &mut Self) -> Result<(), GenerateError>,
+    ) -> Result<(), GenerateError> {
+        let extra = max.map_or(self.generator.max_repeat, |max| {
+            max.saturating_sub(min).min(self.generator.max_repeat)
+        });
+        let count = min + self.generator.below(extra + 1);
+        for i in 0..count {
+            if i >= min && self.is_limited() {
+                break;
+            }
+            match self.attempt(|scope| f(i, scope)) {
+                // Items beyond the minimum can be dropped if they recurse too deeply
+                Err(GenerateError::Exhausted) if i >= min => break,
+                res => res?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Produce the numbers below `n` in a random order.
+    pub(crate) fn shuffled(&mut self, n: usize) -> Vec<usize> {
+        let mut order = (0..n).collect::<Vec<_>>();
+        for i in (1..n).rev() {
+            order.swap(i, self.generator.below(i + 1));
+        }
+        order
+    }
+}
+
+impl<T> Scope<'_, T> {
+    /// Generate between `min` and `max` items, separated by `sep`, with optional leading and trailing separators.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn separated(
+        &mut self,
+        min: usize,
+        max: Option<usize>,
+        leading: bool,
+        trailing: bool,
+        mut item: impl FnMut(&mut Self) -> Result<(), GenerateError>,
+        mut sep: impl FnMut(&mut Self) -> Result<(), GenerateError>,
+    ) -> Result<(), GenerateError> {
+        let mut count = 0;
+        self.repeat(min, max, |i, scope| {
+            count = i + 1;
+            if i > 0 || (leading && scope.generator.below(2) == 0) {
+                sep(scope)?;
+            }
+            item(scope)
+        })?;
+        if trailing && count > 0 && self.generator.below(2) == 0 {
+            sep(self)?;
+        }
+        Ok(())
+    }
+}
+
+/// See [`Parser::generate_with`].
+#[derive(Copy, Clone)]
+pub struct GenerateWith<A, F> {
+    pub(crate) parser: A,
+    pub(crate) generate: F,
+}
+
+impl<'src, I, O, E, A, F> Parser<'src, I, O, E> for GenerateWith<A, F>
+where
+    I: Input<'src>,
+    E: ParserExtra<'src, I>,
+    A: Parser<'src, I, O, E>,
+    F: Fn(&mut Scope<'_, I::Token>) -> Result<(), GenerateError>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, O> {
+        self.parser.go::<M>(inp)
+    }
+
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    fn gen_tokens(&self, scope: &mut Scope<'_, I::Token>) -> Result<(), GenerateError> {
+        (self.generate)(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.parser.prefixes()
+    }
+
+    go_extra!(O);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Extra = extra::Err<EmptyErr>;
+
+    fn generate_all<'src, P, O>(parser: &P, generator: &mut Generator, n: usize) -> Vec<String>
+    where
+        P: Parser<'src, &'src str, O, Extra>,
+    {
+        (0..n)
+            .map(|_| parser.generate(generator).unwrap().into_iter().collect())
+            .collect()
+    }
+
+    fn config<'src>() -> impl Parser<'src, &'src str, (), Extra> {
+        let ident = text::ascii::ident();
+        let value = recursive(|value| {
+            choice((
+                text::int(10).ignored(),
+                text::keyword("null").ignored(),
+                ident.ignored(),
+                value
+                    .clone()
+                    .padded()
+                    .separated_by(just(','))
+                    .allow_trailing()
+                    .delimited_by(just('['), just(']'))
+                    .ignored(),
+                ident
+                    .then_ignore(just(':').padded())
+                    .then(value)
+                    .separated_by(just(',').padded())
+                    .at_least(1)
+                    .delimited_by(just('{'), just('}'))
+                    .ignored(),
+            ))
+        });
+        value.then_ignore(just(';').or_not()).then_ignore(end())
+    }
+
+    #[test]
+    fn accepted() {
+        let mut generator = Generator::new(1234).max_depth(5);
+        let inputs = generate_all(&config(), &mut generator, 200);
+        for input in &inputs {
+            assert!(
+                !config().parse(input).has_errors(),
+                "{input:?} was not accepted"
+            );
+        }
+        // Inputs vary, including nested ones
+        assert!(inputs.iter().any(|input| input.as_str().contains("[[")));
+        assert!(inputs.iter().any(|input| input.as_str().contains('{')));
+
+        // The same seed produces the same inputs
+        let mut generator = Generator::new(1234).max_depth(5);
+        assert_eq!(generate_all(&config(), &mut generator, 200), inputs);
+
+        // Padding keeps adjacent tokens apart
+        let ints = text::int::<_, Extra>(10).padded().repeated().at_least(2);
+        let inputs = generate_all(&ints, &mut Generator::new(1234), 100);
+        for input in &inputs {
+            assert!(
+                !ints.parse(input).has_errors(),
+                "{input:?} was not accepted"
+            );
+        }
+    }
+
+    #[test]
+    fn limits() {
+        let list = just::<_, &str, Extra>("ab")
+            .repeated()
+            .at_least(1)
+            .at_most(3)
+            .to_slice();
+        let mut generator = Generator::new(0);
+        for input in generate_all(&list, &mut generator, 100) {
+            assert!(matches!(input.len(), 2 | 4 | 6), "{input:?}");
+        }
+
+        let nested = recursive(|nested| {
+            nested
+                .delimited_by(just::<_, &str, Extra>('('), just(')'))
+                .or(just('x'))
+        });
+        let mut generator = Generator::new(7).max_depth(3);
+        for input in generate_all(&nested, &mut generator, 100) {
+            assert!(input.len() <= 2 * 6 + 1, "{input:?}");
+        }
+
+        let mut generator = Generator::new(0).max_repeat(0);
+        let items = one_of::<_, &str, Extra>("abc").repeated().at_least(2);
+        for input in generate_all(&items, &mut generator, 20) {
+            assert_eq!(input.len(), 2);
+        }
+
+        // Recursion that can never end
+        let endless = recursive(|endless| just::<_, &str, Extra>('a').then(endless).ignored());
+        assert_eq!(
+            endless.generate(&mut Generator::new(0)),
+            Err(GenerateError::Exhausted)
+        );
+    }
+
+    #[test]
+    fn keywords() {
+        fn stmt<'src>() -> impl Parser<'src, &'src str, (), Extra> {
+            text::ascii::keyword("let")
+                .padded()
+                .then(text::ascii::ident())
+                .then_ignore(just(';'))
+                .ignored()
+        }
+
+        let keyword = text::ascii::keyword::<&str, _, Extra>("let");
+        let mut generator = Generator::new(0);
+        assert_eq!(generate_all(&keyword, &mut generator, 10), vec!["let"; 10]);
+
+        for input in generate_all(&stmt(), &mut generator, 100) {
+            assert!(input.trim_start().starts_with("let"), "{input:?}");
+            assert!(
+                !stmt().parse(&input).has_errors(),
+                "{input:?} was not accepted"
+            );
+        }
+    }
+
+    #[test]
+    fn unsupported() {
+        let digit = any::<&str, Extra>().filter(|c: &char| c.is_ascii_digit());
+        assert_eq!(
+            digit.generate(&mut Generator::new(0)),
+            Err(GenerateError::Unsupported("Any"))
+        );
+
+        let digit = digit.generate_with(|scope| {
+            let n = scope.generator().below(10) as u32;
+            scope.push(char::from_digit(n, 10).unwrap());
+            Ok(())
+        });
+        let number = digit.repeated().at_least(1).to_slice();
+        let mut generator = Generator::new(3);
+        for input in generate_all(&number, &mut generator, 20) {
+            assert!(!number.parse(&input).has_errors(), "{input:?}");
+        }
+    }
+}
@@ -0,0 +1,1035 @@
+//! Introspection of the grammars described by parsers, allowing them to be exported as EBNF or railroad diagrams.
+//!
+//! *"It is a mistake to think you can solve any major problems just with potatoes."*
+//!
+//! [`Parser::grammar`] walks a parser and produces a [`Grammar`], a list of [`Rule`]s describing the language that the
+//! parser accepts. Labelled parsers marked with [`Labelled::as_rule`] become rules of their own, named after their
+//! label, as do [`Recursive`] parsers (which would otherwise describe themselves forever). A recursive parser that is
+//! marked in this way takes the name of its label.
+//!
+//! A [`Grammar`] can be rendered as [EBNF](https://en.wikipedia.org/wiki/Extended_Backus%E2%80%93Naur_form) text with
+//! [`Grammar::to_ebnf`], or as an SVG image of [railroad diagrams](https://en.wikipedia.org/wiki/Syntax_diagram) with
+//! [`Grammar::to_svg`], making it easy to generate reference documentation from the parser that you ship.
+//!
+//! Tokens are described using their [`Debug`](fmt::Debug) implementations, so only parsers of inputs with tokens that
+//! implement it can describe their grammar.
+//! Parsers whose grammar can't be known, such as [`custom`] parsers, [`select!`] and combinators from third-party crates,
+//! are described as [`Expr::Unknown`] or [`Expr::Special`].
+//!
+//! # Examples
+//!
+//! ```
+//! # use chumsky::prelude::*;
+//! let expr = recursive(|expr| {
+//!     let atom = text::int::<_, extra::Err<Simple<char>>>(10)
+//!         .labelled("number")
+//!         .as_rule()
+//!         .or(expr.delimited_by(just('('), just(')')));
+//!     atom.clone()
+//!         .then(just('+').or(just('-')).then(atom).repeated())
+//!         .to_slice()
+//! })
+//! .labelled("expr")
+//! .as_rule();
+//! assert_eq!(expr.parse("1+(2-3)").into_result(), Ok("1+(2-3)"));
+//!
+//! let grammar = expr.grammar();
+//! assert_eq!(grammar.start().name(), "expr");
+//! assert_eq!(
+//!     grammar.to_ebnf(),
+//!     "expr = ( number | \"(\", expr, \")\" ), { ( \"+\" | \"-\" ), ( number | \"(\", expr, \")\" ) } ;\n\
+//!      number = ? integer ? ;\n",
+//! );
+//! assert!(grammar.to_svg().starts_with("<svg"));
+//! ```
+
+use super::*;
+use alloc::format;
+use core::fmt::Write;
+
+/// A description of the input accepted by a parser.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Expr {
+    /// A specific sequence of tokens, given as the debug representation of each token.
+    Literal(Vec<String>),
+    /// Any one of the given tokens, given as their debug representations.
+    OneOf(Vec<String>),
+    /// Any one token other than the given tokens, given as their debug representations.
+    NoneOf(Vec<String>),
+    /// Any single token.
+    Any,
+    /// The end of the input.
+    End,
+    /// Nothing at all.
+    Empty,
+    /// Each of the expressions, one after another.
+    Seq(Vec<Expr>),
+    /// Any one of the expressions, trying them in order.
+    Choice(Vec<Expr>),
+    /// The expression, or nothing.
+    Optional(Box<Expr>),
+    /// The expression, repeated.
+    Repeat {
+        /// The repeated expression.
+        item: Box<Expr>,
+        /// The separator that appears between each repetition, if any.
+        separator: Option<Box<Expr>>,
+        /// The minimum number of repetitions.
+        min: usize,
+        /// The maximum number of repetitions, if bounded.
+        max: Option<usize>,
+        /// Whether a separator may appear before the first repetition.
+        leading: bool,
+        /// Whether a separator may appear after the last repetition.
+        trailing: bool,
+    },
+    /// Each of the expressions exactly once (or, if `optional`, at most once), in any order.
+    Permutation {
+        /// The expressions.
+        items: Vec<Expr>,
+        /// Whether each expression may be left out.
+        optional: bool,
+    },
+    /// A check that the expression does (or, if `negated`, does not) appear next, without consuming it.
+    Lookahead {
+        /// Whether the check is that the expression does *not* appear.
+        negated: bool,
+        /// The expression to look for.
+        expr: Box<Expr>,
+    },
+    /// Any single token that does not begin the expression.
+    AnyExcept(Box<Expr>),
+    /// A reference to the rule at the given index of [`Grammar::rules`].
+    Rule(usize),
+    /// The expression, with its results remembered so that it may be left-recursive. See [`Parser::memoized`].
+    Memoized(Box<Expr>),
+    /// Input described in prose, such as `whitespace`.
+    Special(String),
+    /// The definition of a [`Recursive`] parser that was declared but never defined.
+    Undefined,
+    /// Input accepted by a parser that cannot describe itself.
+    Unknown,
+}
+
+impl Expr {
+    /// Create a sequence, flattening nested sequences and removing empty expressions.
+    pub(crate) fn seq(exprs: impl IntoIterator<Item = Self>) -> Self {
+        let mut items = Vec::new();
+        for expr in exprs {
+            match expr {
+                Self::Seq(inner) => items.extend(inner),
+                Self::Empty => {}
+                expr => items.push(expr),
+            }
+        }
+        match items.len() {
+            0 => Self::Empty,
+            1 => items.pop().unwrap(),
+            _ => Self::Seq(items),
+        }
+    }
+
+    /// Create a choice, flattening nested choices.
+    pub(crate) fn choice(exprs: impl IntoIterator<Item = Self>) -> Self {
+        let mut items = Vec::new();
+        for expr in exprs {
+            match expr {
+                Self::Choice(inner) => items.extend(inner),
+                expr => items.push(expr),
+            }
+        }
+        match items.len() {
+            1 => items.pop().unwrap(),
+            _ => Self::Choice(items),
+        }
+    }
+
+    /// Describe a sequence of tokens, given their debug representations.
+    pub(crate) fn literal(tokens: impl IntoIterator<Item = String>) -> Self {
+        let tokens = tokens.into_iter().collect::<Vec<_>>();
+        if tokens.is_empty() {
+            Self::Empty
+        } else {
+            Self::Literal(tokens)
+        }
+    }
+
+    /// Describe a set of tokens (or, if `negated`, any token outside of the set), given their debug representations.
+    /// Sets too large to list are described in prose.
+    pub(crate) fn one_of(tokens: impl IntoIterator<Item = String>, negated: bool) -> Self {
+        const MAX_TOKENS: usize = 256;
+        let mut tokens = tokens.into_iter();
+        let descs = (&mut tokens).take(MAX_TOKENS).collect::<Vec<_>>();
+        match (tokens.next().is_some(), negated) {
+            (false, false) => Self::OneOf(descs),
+            (false, true) => Self::NoneOf(descs),
+            (true, negated) => Self::Special(format!(
+                "{} {} ...",
+                if negated { "none of" } else { "one of" },
+                descs[..8].join(" "),
+            )),
+        }
+    }
+}
+
+/// Render a sequence of tokens, given their debug representations, as it appears in EBNF.
+fn literal_text(tokens: &[String]) -> String {
+    // Sequences of characters are more readable as a string
+    let chars = tokens
+        .iter()
+        .map(|tok| {
+            tok.strip_prefix('\'')
+                .and_then(|tok| tok.strip_suffix('\''))
+                .filter(|c| !c.is_empty())
+        })
+        .collect::<Option<Vec<_>>>();
+    match chars {
+        Some(chars) => {
+            let mut s = String::from("\"");
+            for c in chars {
+                s.push_str(match c {
+                    "\\'" => "'",
+                    "\"" => "\\\"",
+                    c => c,
+                });
+            }
+            s.push('"');
+            s
+        }
+        None => tokens.join(" "),
+    }
+}
+
+/// Produce a rule name from a label.
+pub(crate) fn label_name<L: fmt::Debug>(label: &L) -> String {
+    let name = format!("{label:?}");
+    match name
+        .strip_prefix('"')
+        .and_then(|name| name.strip_suffix('"'))
+    {
+        Some(name) => name.into(),
+        None => name,
+    }
+}
+
+/// State used while walking a parser to describe its grammar.
+#[derive(Default)]
+pub struct NodeScope {
+    rules: Vec<(Option<String>, Expr)>,
+    labels: HashMap<String, usize>,
+    recursives: HashMap<usize, usize>,
+    undefined: Vec<usize>,
+}
+
+impl NodeScope {
+    /// Describe a labelled parser, which becomes a rule with the given name.
+    pub(crate) fn labelled(&mut self, name: String, f: impl FnOnce(&mut Self) -> Expr) -> Expr {
+        if let Some(id) = self.labels.get(&name) {
+            return Expr::Rule(*id);
+        }
+        let id = match f(self) {
+            // A labelled recursive parser takes the name of the label
+            Expr::Rule(id) if self.rules[id].0.is_none() => {
+                self.rules[id].0 = Some(name.clone());
+                id
+            }
+            expr => {
+                self.rules.push((Some(name.clone()), expr));
+                self.rules.len() - 1
+            }
+        };
+        self.labels.insert(name, id);
+        Expr::Rule(id)
+    }
+
+    /// Describe a recursive parser, identified by the address of its shared definition. `f` produces `None` if the
+    /// parser was declared but never defined.
+    pub(crate) fn recursive(
+        &mut self,
+        key: usize,
+        f: impl FnOnce(&mut Self) -> Option<Expr>,
+    ) -> Expr {
+        if let Some(id) = self.recursives.get(&key) {
+            return Expr::Rule(*id);
+        }
+        self.rules.push((None, Expr::Empty));
+        let id = self.rules.len() - 1;
+        self.recursives.insert(key, id);
+        self.rules[id].1 = f(self).unwrap_or_else(|| {
+            self.undefined.push(id);
+            Expr::Undefined
+        });
+        Expr::Rule(id)
+    }
+
+    pub(crate) fn into_grammar(mut self, expr: Expr) -> Grammar {
+        let start = match expr {
+            Expr::Rule(id) => id,
+            expr => {
+                self.rules.push((None, expr));
+                self.rules.len() - 1
+            }
+        };
+        let rules = self
+            .rules
+            .into_iter()
+            .enumerate()
+            .map(|(id, (name, expr))| Rule {
+                name: name.unwrap_or_else(|| {
+                    if id == start {
+                        "start".into()
+                    } else {
+                        format!("rule_{id}")
+                    }
+                }),
+                expr,
+            })
+            .collect();
+        Grammar {
+            rules,
+            start,
+            undefined: self.undefined,
+        }
+    }
+}
+
+/// A named rule of a [`Grammar`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Rule {
+    name: String,
+    expr: Expr,
+}
+
+impl Rule {
+    /// The name of the rule. This is its label, if it has one.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The expression that describes the input accepted by the rule.
+    pub fn expr(&self) -> &Expr {
+        &self.expr
+    }
+}
+
+/// A description of the language accepted by a parser, obtained with [`Parser::grammar`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grammar {
+    rules: Vec<Rule>,
+    pub(crate) start: usize,
+    /// The rules of [`Recursive`] parsers that were declared but never defined.
+    pub(crate) undefined: Vec<usize>,
+}
+
+impl Grammar {
+    /// The rules of the grammar. [`Expr::Rule`] refers to rules by their index in this slice.
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// The rule describing the parser from which the grammar was produced.
+    pub fn start(&self) -> &Rule {
+        &self.rules[self.start]
+    }
+
+    /// Find a rule by name.
+    pub fn rule(&self, name: &str) -> Option<&Rule> {
+        self.rules.iter().find(|rule| rule.name == name)
+    }
+
+    /// The rules in the order that they should be displayed: the start rule, followed by the others.
+    pub(crate) fn ordered(&self) -> impl Iterator<Item = &Rule> + '_ {
+        core::iter::once(self.start()).chain(
+            self.rules
+                .iter()
+                .enumerate()
+                .filter(move |(id, _)| *id != self.start)
+                .map(|(_, rule)| rule),
+        )
+    }
+
+    /// Render the grammar as ISO-style EBNF, with one rule per line.
+    ///
+    /// Input that EBNF can't express (such as `any`, the end of input, and lookahead) is written as a special sequence
+    /// between `?` marks. Permutations of a few items are written out in full, and rules that were never defined are
+    /// written as comments.
+    pub fn to_ebnf(&self) -> String {
+        let mut out = String::new();
+        for rule in self.ordered() {
+            let expr = self.ebnf(&rule.expr, 0);
+            if rule.expr == Expr::Undefined {
+                writeln!(out, "(* {} is declared but never defined *)", rule.name).unwrap();
+            } else if expr.is_empty() {
+                writeln!(out, "{} = ;", rule.name).unwrap();
+            } else {
+                writeln!(out, "{} = {} ;", rule.name, expr).unwrap();
+            }
+        }
+        out
+    }
+
+    /// Render an expression as EBNF. `prec` is 0 at the top level, 1 within a sequence and 2 where a single factor is
+    /// required.
+    pub(crate) fn ebnf(&self, expr: &Expr, prec: u8) -> String {
+        let group = |s: String, needed: bool| if needed { format!("( {s} )") } else { s };
+        match expr {
+            Expr::Literal(toks) => literal_text(toks),
+            Expr::OneOf(toks) => group(toks.join(" | "), prec > 0 && toks.len() > 1),
+            Expr::NoneOf(toks) => {
+                format!("( ? any ? - {} )", group(toks.join(" | "), toks.len() > 1))
+            }
+            Expr::Any => "? any ?".into(),
+            Expr::End => "? end of input ?".into(),
+            Expr::Empty => String::new(),
+            Expr::Seq(items) => group(
+                items
+                    .iter()
+                    .map(|item| self.ebnf(item, 1))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                prec > 1,
+            ),
+            Expr::Choice(items) => group(
+                items
+                    .iter()
+                    .map(|item| self.ebnf(item, 0))
+                    .collect::<Vec<_>>()
+                    .join(" | "),
+                prec > 0,
+            ),
+            Expr::Optional(item) => format!("[ {} ]", self.ebnf(item, 0)),
+            Expr::Repeat {
+                item,
+                separator,
+                min,
+                max,
+                leading,
+                trailing,
+            } => {
+                let sep = separator.as_ref().map(|sep| self.ebnf(sep, 1));
+                // Each repetition after the first is preceded by the separator
+                let rep = |prec: u8| match &sep {
+                    Some(sep) => group(format!("{sep}, {}", self.ebnf(item, 1)), prec > 1),
+                    None => self.ebnf(item, prec),
+                };
+                let rest = |min: usize, max: Option<usize>| {
+                    let mut parts = Vec::new();
+                    match min {
+                        0 => {}
+                        1 => parts.push(rep(1)),
+                        min => parts.push(format!("{min} * {}", rep(2))),
+                    }
+                    match max.map(|max| max.saturating_sub(min)) {
+                        None => parts.push(format!("{{ {} }}", rep(0))),
+                        Some(0) => {}
+                        Some(1) => parts.push(format!("[ {} ]", rep(0))),
+                        Some(n) => parts.push(format!("{n} * [ {} ]", rep(0))),
+                    }
+                    parts
+                };
+                let mut parts = Vec::new();
+                if let (true, Some(sep)) = (leading, &sep) {
+                    parts.push(format!("[ {sep} ]"));
+                }
+                match (&sep, max) {
+                    (_, Some(0)) => {}
+                    (None, _) => parts.extend(rest(*min, *max)),
+                    (Some(_), _) => {
+                        let mut reps = vec![self.ebnf(item, 1)];
+                        reps.extend(rest(min.saturating_sub(1), max.map(|max| max - 1)));
+                        let reps = reps.join(", ");
+                        parts.push(if *min == 0 {
+                            format!("[ {reps} ]")
+                        } else {
+                            reps
+                        });
+                    }
+                }
+                if let (true, Some(sep)) = (trailing, &sep) {
+                    parts.push(format!("[ {sep} ]"));
+                }
+                let compound = parts.len() > 1 || (sep.is_some() && *min > 0);
+                group(parts.join(", "), prec > 1 && compound)
+            }
+            Expr::Permutation { items, optional } => {
+                if let ([item], false) = (&items[..], optional) {
+                    return self.ebnf(item, prec);
+                }
+                let items = items
+                    .iter()
+                    .map(|item| self.ebnf(item, 1))
+                    .collect::<Vec<_>>();
+                match items.len() {
+                    0 => String::new(),
+                    n if n > MAX_PERMUTED => format!(
+                        "? {} in any order{} ?",
+                        items.join(", "),
+                        if *optional { ", each at most once" } else { "" },
+                    ),
+                    _ if *optional => permute(&items, true),
+                    _ => group(permute(&items, false), prec > 0),
+                }
+            }
+            Expr::Lookahead { negated, expr } => format!(
+                "? {}followed by {} ?",
+                if *negated { "not " } else { "" },
+                self.ebnf(expr, 0)
+            ),
+            Expr::AnyExcept(expr) => format!("( ? any ? - {} )", self.ebnf(expr, 2)),
+            Expr::Rule(id) => self.rules[*id].name.clone(),
+            Expr::Memoized(expr) => self.ebnf(expr, prec),
+            Expr::Special(desc) => format!("? {desc} ?"),
+            Expr::Undefined => String::new(),
+            Expr::Unknown => "? unknown ?".into(),
+        }
+    }
+
+    /// Render the grammar as an SVG image containing a railroad diagram for each rule, with the start rule first.
+    pub fn to_svg(&self) -> String {
+        const MARGIN: i64 = 20;
+        const TITLE: i64 = 24;
+
+        let diagrams = self
+            .ordered()
+            .map(|rule| (rule.name.as_str(), Diagram::from_expr(self, &rule.expr)))
+            .collect::<Vec<_>>();
+
+        let mut body = String::new();
+        let mut y = MARGIN;
+        let mut width = 0;
+        for (name, diagram) in &diagrams {
+            writeln!(
+                body,
+                r#"<text class="rule" x="{MARGIN}" y="{}">{}</text>"#,
+                y + 14,
+                escape(name)
+            )
+            .unwrap();
+            let line = y + TITLE + diagram.up().max(Diagram::BOX / 2);
+            // Start and end markers, joined by the diagram
+            let x = MARGIN;
+            let end = x + 20 + diagram.width();
+            writeln!(
+                body,
+                r#"<path d="M{x} {} v20 M{x} {line} h10 M{} {line} h10 M{} {} v20"/>"#,
+                line - 10,
+                end - 10,
+                end,
+                line - 10
+            )
+            .unwrap();
+            diagram.render(x + 10, line, &mut body);
+            width = width.max(end + MARGIN);
+            y = line + diagram.down().max(Diagram::BOX / 2) + MARGIN;
+        }
+
+        format!(
+            concat!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+                "\n<style>",
+                "path{{fill:none;stroke:#333;stroke-width:2}}",
+                "rect{{fill:#fff;stroke:#333;stroke-width:2}}",
+                "rect.special{{stroke-dasharray:4 2}}",
+                "text{{font:13px monospace;text-anchor:middle;dominant-baseline:central}}",
+                "text.rule{{font-weight:bold;text-anchor:start;dominant-baseline:auto}}",
+                "text.comment{{font-style:italic}}",
+                "</style>\n{body}</svg>\n",
+            ),
+            w = width.max(MARGIN * 2),
+            h = y,
+            body = body,
+        )
+    }
+}
+
+/// The most items of a permutation that [`Grammar::to_ebnf`] writes out every order of.
+const MAX_PERMUTED: usize = 4;
+
+/// Write out every order of the given (rendered) items as EBNF: a choice of each item followed by the orders of the
+/// others. If `optional`, any of the items may be left out, and the choice is itself optional.
+fn permute(items: &[String], optional: bool) -> String {
+    let alts = (0..items.len())
+        .map(|i| {
+            let rest = items
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, item)| item.clone())
+                .collect::<Vec<_>>();
+            match rest.len() {
+                0 => items[i].clone(),
+                _ if optional => format!("{}, {}", items[i], permute(&rest, true)),
+                1 => format!("{}, {}", items[i], rest[0]),
+                _ => format!("{}, ( {} )", items[i], permute(&rest, false)),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" | ");
+    if optional {
+        format!("[ {alts} ]")
+    } else {
+        alts
+    }
+}
+
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// An element of a railroad diagram. Each element is drawn with its entry on the left and its exit on the right, both
+/// on the same horizontal line. Elements extend `up` above the line and `down` below it.
+enum Diagram {
+    Terminal(String),
+    NonTerminal(String),
+    Special(String),
+    Comment(String),
+    Skip,
+    Seq(Vec<Diagram>),
+    /// The first alternative is drawn on the line, the others below it.
+    Choice(Vec<Diagram>),
+    /// The item is drawn on the line, with a path looping back through `back` below it.
+    Loop {
+        item: Box<Diagram>,
+        back: Box<Diagram>,
+    },
+}
+
+impl Diagram {
+    const BOX: i64 = 22;
+    const CHAR: i64 = 8;
+    const ARC: i64 = 10;
+    const GAP: i64 = 8;
+
+    fn from_expr(grammar: &Grammar, expr: &Expr) -> Self {
+        match expr {
+            Expr::Literal(toks) => Self::Terminal(literal_text(toks)),
+            Expr::OneOf(toks) if toks.len() <= 8 => {
+                Self::Choice(toks.iter().cloned().map(Self::Terminal).collect())
+            }
+            Expr::OneOf(toks) => Self::Special(format!("one of {} ...", toks[..8].join(" "))),
+            Expr::NoneOf(toks) if toks.len() <= 8 => {
+                Self::Special(format!("none of {}", toks.join(" ")))
+            }
+            Expr::NoneOf(toks) => Self::Special(format!("none of {} ...", toks[..8].join(" "))),
+            Expr::Any => Self::Special("any".into()),
+            Expr::End => Self::Special("end of input".into()),
+            Expr::Empty => Self::Skip,
+            Expr::Seq(items) => Self::Seq(
+                items
+                    .iter()
+                    .map(|item| Self::from_expr(grammar, item))
+                    .collect(),
+            ),
+            Expr::Choice(items) => Self::Choice(
+                items
+                    .iter()
+                    .map(|item| Self::from_expr(grammar, item))
+                    .collect(),
+            ),
+            Expr::Optional(item) => Self::Choice(vec![Self::Skip, Self::from_expr(grammar, item)]),
+            Expr::Repeat {
+                item,
+                separator,
+                min,
+                max,
+                leading,
+                trailing,
+            } => {
+                let item = Self::from_expr(grammar, item);
+                let sep = || separator.as_ref().map(|sep| Self::from_expr(grammar, sep));
+                let mut back = sep().unwrap_or(Self::Skip);
+                if *min > 1 || max.map_or(false, |max| max > 1) {
+                    let times = match max {
+                        Some(max) if max == min => format!("{min} times"),
+                        Some(max) => format!("{min} to {max} times"),
+                        None => format!("at least {min} times"),
+                    };
+                    back = Self::Seq(vec![Self::Comment(times), back]);
+                }
+                let mut items = Vec::new();
+                if let (true, Some(sep)) = (leading, sep()) {
+                    items.push(Self::Choice(vec![Self::Skip, sep]));
+                }
+                let reps = match max {
+                    Some(0) => Self::Skip,
+                    Some(1) => item,
+                    _ => Self::Loop {
+                        item: Box::new(item),
+                        back: Box::new(back),
+                    },
+                };
+                items.push(if *min == 0 {
+                    Self::Choice(vec![Self::Skip, reps])
+                } else {
+                    reps
+                });
+                if let (true, Some(sep)) = (trailing, sep()) {
+                    items.push(Self::Choice(vec![Self::Skip, sep]));
+                }
+                Self::Seq(items)
+            }
+            Expr::Permutation { items, optional } => {
+                let reps = Self::Loop {
+                    item: Box::new(Self::Choice(
+                        items
+                            .iter()
+                            .map(|item| Self::from_expr(grammar, item))
+                            .collect(),
+                    )),
+                    back: Box::new(Self::Comment(
+                        if *optional {
+                            "each at most once"
+                        } else {
+                            "each once"
+                        }
+                        .into(),
+                    )),
+                };
+                if *optional {
+                    Self::Choice(vec![Self::Skip, reps])
+                } else {
+                    reps
+                }
+            }
+            Expr::Lookahead { negated, expr } => Self::Seq(vec![
+                Self::Comment(
+                    if *negated {
+                        "not followed by"
+                    } else {
+                        "followed by"
+                    }
+                    .into(),
+                ),
+                Self::from_expr(grammar, expr),
+            ]),
+            Expr::AnyExcept(expr) => Self::Seq(vec![
+                Self::Comment("any except".into()),
+                Self::from_expr(grammar, expr),
+            ]),
+            Expr::Rule(id) => Self::NonTerminal(grammar.rules[*id].name.clone()),
+            Expr::Memoized(expr) => Self::from_expr(grammar, expr),
+            Expr::Special(desc) => Self::Special(desc.clone()),
+            Expr::Undefined => Self::Comment("never defined".into()),
+            Expr::Unknown => Self::Special("unknown".into()),
+        }
+    }
+
+    fn text_width(text: &str) -> i64 {
+        text.chars().count() as i64 * Self::CHAR + 20
+    }
+
+    fn width(&self) -> i64 {
+        match self {
+            Self::Terminal(text)
+            | Self::NonTerminal(text)
+            | Self::Special(text)
+            | Self::Comment(text) => Self::text_width(text),
+            Self::Skip => 0,
+            Self::Seq(items) => items.iter().map(|item| item.width() + 20).sum(),
+            Self::Choice(items) => items.iter().map(Self::width).max().unwrap_or(0) + Self::ARC * 4,
+            Self::Loop { item, back } => item.width().max(back.width()) + Self::ARC * 2,
+        }
+    }
+
+    fn up(&self) -> i64 {
+        match self {
+            Self::Terminal(_) | Self::NonTerminal(_) | Self::Special(_) => Self::BOX / 2,
+            Self::Comment(_) => Self::BOX - 4,
+            Self::Skip => 0,
+            Self::Seq(items) => items.iter().map(Self::up).max().unwrap_or(0),
+            Self::Choice(items) => items.first().map_or(0, Self::up),
+            Self::Loop { item, .. } => item.up(),
+        }
+    }
+
+    fn down(&self) -> i64 {
+        match self {
+            Self::Terminal(_) | Self::NonTerminal(_) | Self::Special(_) => Self::BOX / 2,
+            Self::Comment(_) | Self::Skip => 0,
+            Self::Seq(items) => items.iter().map(Self::down).max().unwrap_or(0),
+            Self::Choice(items) => {
+                let offsets = self.row_offsets();
+                items
+                    .last()
+                    .map_or(0, |last| offsets[items.len() - 1] + last.down())
+            }
+            Self::Loop { item, back } => {
+                self.loop_offset() + back.down().max(0).max(item.down() - self.loop_offset())
+            }
+        }
+    }
+
+    /// The vertical offsets of the alternatives of a choice from its line.
+    fn row_offsets(&self) -> Vec<i64> {
+        let Self::Choice(items) = self else {
+            return Vec::new();
+        };
+        let mut offsets = vec![0];
+        for pair in items.windows(2) {
+            let prev = *offsets.last().unwrap();
+            offsets.push(prev + (pair[0].down() + Self::GAP + pair[1].up()).max(Self::ARC * 2));
+        }
+        offsets
+    }
+
+    /// The vertical offset of the return path of a loop from its line.
+    fn loop_offset(&self) -> i64 {
+        let Self::Loop { item, back } = self else {
+            return 0;
+        };
+        (item.down() + Self::GAP + back.up()).max(Self::ARC * 2)
+    }
+
+    fn render(&self, x: i64, y: i64, out: &mut String) {
+        const R: i64 = Diagram::ARC;
+        match self {
+            Self::Terminal(text) | Self::NonTerminal(text) | Self::Special(text) => {
+                let (rx, class) = match self {
+                    Self::Terminal(_) => (R, ""),
+                    Self::NonTerminal(_) => (0, ""),
+                    _ => (0, r#" class="special""#),
+                };
+                let w = self.width();
+                writeln!(
+                    out,
+                    r#"<rect{class} x="{x}" y="{}" width="{w}" height="{}" rx="{rx}"/><text x="{}" y="{y}">{}</text>"#,
+                    y - Self::BOX / 2,
+                    Self::BOX,
+                    x + w / 2,
+                    escape(text),
+                )
+                .unwrap();
+            }
+            Self::Comment(text) => {
+                let w = self.width();
+                writeln!(
+                    out,
+                    r#"<path d="M{x} {y} h{w}"/><text class="comment" x="{}" y="{}">{}</text>"#,
+                    x + w / 2,
+                    y - Self::BOX / 2,
+                    escape(text),
+                )
+                .unwrap();
+            }
+            Self::Skip => {}
+            Self::Seq(items) => {
+                let mut x = x;
+                for item in items {
+                    let w = item.width();
+                    writeln!(out, r#"<path d="M{x} {y} h10 M{} {y} h10"/>"#, x + 10 + w).unwrap();
+                    item.render(x + 10, y, out);
+                    x += w + 20;
+                }
+            }
+            Self::Choice(items) => {
+                let w = self.width();
+                for (item, offset) in items.iter().zip(self.row_offsets()) {
+                    let iw = item.width();
+                    let row = y + offset;
+                    if offset == 0 {
+                        writeln!(
+                            out,
+                            r#"<path d="M{x} {y} h{} M{} {y} H{}"/>"#,
+                            R * 2,
+                            x + R * 2 + iw,
+                            x + w
+                        )
+                        .unwrap();
+                    } else {
+                        writeln!(
+                            out,
+                            r#"<path d="M{x} {y} a{R} {R} 0 0 1 {R} {R} V{} a{R} {R} 0 0 0 {R} {R} M{} {row} H{} a{R} {R} 0 0 0 {R} -{R} V{} a{R} {R} 0 0 1 {R} -{R}"/>"#,
+                            row - R,
+                            x + R * 2 + iw,
+                            x + w - R * 2,
+                            y + R,
+                        )
+                        .unwrap();
+                    }
+                    item.render(x + R * 2, row, out);
+                }
+            }
+            Self::Loop { item, back } => {
+                let w = self.width();
+                let (iw, bw) = (item.width(), back.width());
+                let row = y + self.loop_offset();
+                let back_x = x + R + (w - R * 2 - bw) / 2;
+                writeln!(
+                    out,
+                    concat!(
+                        r#"<path d="M{x} {y} h{R} M{} {y} H{} "#,
+                        r#"M{} {y} a{R} {R} 0 0 1 {R} {R} V{} a{R} {R} 0 0 1 -{R} {R} H{} "#,
+                        r#"M{back_x} {row} H{} a{R} {R} 0 0 1 -{R} -{R} V{} a{R} {R} 0 0 1 {R} -{R}"/>"#,
+                    ),
+                    x + R + iw,
+                    x + w,
+                    x + w - R,
+                    row - R,
+                    back_x + bw,
+                    x + R,
+                    y + R,
+                    x = x,
+                    y = y,
+                    R = R,
+                    row = row,
+                    back_x = back_x,
+                )
+                .unwrap();
+                item.render(x + R, y, out);
+                back.render(back_x, row, out);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recursive::Indirect;
+
+    type Extra = extra::Err<EmptyErr>;
+
+    fn ebnf<'src, O>(parser: impl Parser<'src, &'src str, O, Extra>) -> String {
+        parser.grammar().to_ebnf()
+    }
+
+    #[test]
+    fn rules() {
+        let value = recursive(|value| {
+            let list = value
+                .clone()
+                .separated_by(just(',').padded())
+                .collect::<Vec<_>>()
+                .delimited_by(just('['), just(']'));
+            // An unlabelled recursive parser nested within another gets a generated name
+            let parens =
+                recursive(|parens| parens.delimited_by(just('('), just(')')).or(value.clone()));
+            text::int::<&str, Extra>(10)
+                .labelled("number")
+                .as_rule()
+                .to(())
+                .or(list.to(()))
+                .or(parens)
+        })
+        .labelled("value")
+        .as_rule();
+
+        let grammar = value.grammar();
+        assert_eq!(grammar.start().name(), "value");
+        assert_eq!(grammar.rules().len(), 3);
+        assert_eq!(
+            grammar.rule("number").map(Rule::expr),
+            Some(&Expr::Special("integer".into()))
+        );
+        assert_eq!(
+            grammar.to_ebnf(),
+            "value = number | \"[\", [ value, { [ ? whitespace ? ], \",\", [ ? whitespace ? ], value } ], \"]\" | rule_2 ;\n\
+             number = ? integer ? ;\n\
+             rule_2 = \"(\", rule_2, \")\" | value ;\n",
+        );
+
+        // Anonymous parsers are given a start rule
+        assert_eq!(
+            ebnf(just("ab").then(end())),
+            "start = \"ab\", ? end of input ? ;\n"
+        );
+
+        // Declared parsers that were never defined are reported
+        let undefined = Recursive::<Indirect<&str, (), Extra>>::declare();
+        assert_eq!(
+            ebnf(undefined),
+            "(* start is declared but never defined *)\n"
+        );
+    }
+
+    #[test]
+    fn repetitions() {
+        let a = just::<_, &str, Extra>('a');
+        assert_eq!(ebnf(a.repeated().exactly(3)), "start = 3 * \"a\" ;\n");
+        assert_eq!(ebnf(a.repeated().at_most(2)), "start = 2 * [ \"a\" ] ;\n");
+        assert_eq!(
+            ebnf(a.repeated().at_least(2).at_most(3)),
+            "start = 2 * \"a\", [ \"a\" ] ;\n"
+        );
+        assert_eq!(
+            ebnf(a.separated_by(just(',')).at_least(2)),
+            "start = \"a\", \",\", \"a\", { \",\", \"a\" } ;\n"
+        );
+        assert_eq!(
+            ebnf(a.separated_by(just(',')).allow_leading().at_most(2)),
+            "start = [ \",\" ], [ \"a\", [ \",\", \"a\" ] ] ;\n"
+        );
+        assert_eq!(
+            ebnf(
+                one_of("xy")
+                    .repeated()
+                    .at_least(1)
+                    .to_slice()
+                    .then(a.or_not())
+            ),
+            "start = ( 'x' | 'y' ), { 'x' | 'y' }, [ \"a\" ] ;\n"
+        );
+        assert_eq!(
+            ebnf(none_of("ab").and_is(a.not()).rewind()),
+            "start = ? followed by ? followed by ( ? any ? - \"a\" ) ?, ( ? any ? - ( 'a' | 'b' ) ) ? ;\n"
+        );
+    }
+
+    #[test]
+    fn permutations() {
+        let (a, b, c) = (just::<_, &str, Extra>('a'), just('b'), just('c'));
+        assert_eq!(
+            ebnf(permutation((a, b))),
+            "start = \"a\", \"b\" | \"b\", \"a\" ;\n"
+        );
+        assert_eq!(
+            ebnf(permutation((a, b, c)).then(end())),
+            "start = ( \"a\", ( \"b\", \"c\" | \"c\", \"b\" ) | \"b\", ( \"a\", \"c\" | \"c\", \"a\" ) | \"c\", ( \"a\", \"b\" | \"b\", \"a\" ) ), ? end of input ? ;\n"
+        );
+        assert_eq!(
+            ebnf(permutation((a, b)).optional()),
+            "start = [ \"a\", [ \"b\" ] | \"b\", [ \"a\" ] ] ;\n"
+        );
+        assert_eq!(
+            ebnf(permutation((a, b, c, a, b))),
+            "start = ? \"a\", \"b\", \"c\", \"a\", \"b\" in any order ? ;\n"
+        );
+    }
+
+    #[test]
+    fn svg() {
+        let item = just::<_, &str, Extra>("<a>")
+            .or(text::ascii::keyword("b"))
+            .labelled("item")
+            .as_rule();
+        let list = item
+            .separated_by(just(','))
+            .at_least(1)
+            .collect::<Vec<_>>()
+            .labelled("list")
+            .as_rule();
+
+        let svg = list.grammar().to_svg();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>\n"));
+        // Rules are titled in order, starting with the start rule
+        let list_title = svg.find(">list</text>").unwrap();
+        let item_title = svg.find(">item</text>").unwrap();
+        assert!(list_title < item_title);
+        // Text is escaped
+        assert!(svg.as_str().contains("&quot;&lt;a&gt;&quot;"));
+        assert!(!svg.as_str().contains("\"<a>\""));
+        assert_eq!(svg.matches("<rect").count(), 4);
+    }
+}
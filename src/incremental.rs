@@ -0,0 +1,277 @@
+//! Types that allow the results of a parse to be reused by later parses of an edited input.
+//!
+//! *"It is known that there are an infinite number of worlds, simply because there is an infinite amount of space for
+//! them to be in."*
+//!
+//! Editors and language servers tend to parse the same file over and over again, with only a small region of the text
+//! changing between each parse. Parsers marked with [`Parser::incremental`] store their outputs in a [`Cache`] that
+//! outlives the parse. When the input is edited, the cache is told about the edit with [`Cache::edit`]: results that
+//! the edit touched are discarded, and results that lie after the edit are moved to their new position. A later call
+//! to [`Parser::parse_incremental`] will then skip over any region of the input for which a result is already known.
+//!
+//! Because the reused outputs were produced by an earlier parse, any spans they contain need to be moved by the same
+//! amount as the input was. This is the job of the [`Rebase`] trait, which outputs of incremental parsers must
+//! implement.
+//!
+//! # Caveats
+//!
+//! - Locations are those reported by [`Input::cursor_location`]: for `&str`, these are byte offsets.
+//!
+//! - A result is only considered untouched by an edit if there is at least one unchanged token between the end of the
+//!   result and the start of the edit. Parsers that look more than one token beyond the end of their output (such as
+//!   those using [`Parser::rewind`]) should not be marked as incremental.
+//!
+//! - Results that were produced while parser state or context was in use are reused as-is, so incremental parsers
+//!   should not depend on either.
+//!
+//! - Results are keyed on the [`MemoId`](crate::memo::MemoId) given to the parser when it was created, which is
+//!   shared by its clones. A parser that is rebuilt for each parse gets a new id, and so can't reuse the results of
+//!   the parser it replaces unless it is given that parser's id with [`Incremental::with_id`].
+//!
+//! # Example
+//!
+//! ```
+//! # use chumsky::{prelude::*, incremental::{Cache, Edit}};
+//! let item = text::ascii::ident::<_, extra::Err<Simple<char>>>()
+//!     .map_with(|ident: &str, e| (ident.to_string(), e.span()))
+//!     .incremental();
+//! let items = item.padded().repeated().collect::<Vec<_>>();
+//!
+//! let mut cache = Cache::new();
+//! let old = items.parse_incremental("foo bar baz", &mut cache).into_result().unwrap();
+//! assert_eq!(old[2], ("baz".to_string(), (8..11).into()));
+//!
+//! // Replace `bar` with `quux`
+//! cache.edit(Edit::new(4..7, 4));
+//! let new = items.parse_incremental("foo quux baz", &mut cache).into_result().unwrap();
+//! // `baz` was reused from the previous parse, but its span has been moved to account for the edit
+//! assert_eq!(new[1], ("quux".to_string(), (4..8).into()));
+//! assert_eq!(new[2], ("baz".to_string(), (9..12).into()));
+//! ```
+
+use super::*;
+use crate::memo::MemoId;
+use core::any::Any;
+
+/// A change made to an input between two parses.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Edit {
+    /// The range of the old input that was replaced.
+    pub range: Range<usize>,
+    /// The length of the text that replaced it.
+    pub len: usize,
+}
+
+impl Edit {
+    /// Create an edit that replaces the given range of the old input with `len` units (bytes, for `&str`) of new
+    /// input.
+    pub fn new(range: Range<usize>, len: usize) -> Self {
+        Self { range, len }
+    }
+
+    /// The amount by which locations after the edit move.
+    pub fn delta(&self) -> isize {
+        self.len as isize - (self.range.end - self.range.start) as isize
+    }
+}
+
+struct Entry {
+    end: usize,
+    delta: isize,
+    out: Box<dyn Any>,
+}
+
+impl Entry {
+    fn output<O: Clone + Rebase + 'static>(&self) -> Option<O> {
+        let mut out = self.out.downcast_ref::<O>()?.clone();
+        if self.delta != 0 {
+            out.rebase(self.delta);
+        }
+        Some(out)
+    }
+}
+
+/// A table of parser results that can be carried between parses of an input.
+///
+/// See the [module-level documentation](self) for more information.
+#[derive(Default)]
+pub struct Cache {
+    entries: HashMap<(usize, MemoId), Entry>,
+}
+
+impl Cache {
+    /// Create a new, empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inform the cache that the input has been edited.
+    ///
+    /// Results that overlap (or immediately precede) the edited range are discarded, and results that come after it
+    /// are moved to their new location.
+    pub fn edit(&mut self, edit: Edit) {
+        let delta = edit.delta();
+        self.entries = core::mem::take(&mut self.entries)
+            .into_iter()
+            .filter_map(|((start, id), mut entry)| {
+                if entry.end < edit.range.start {
+                    Some(((start, id), entry))
+                } else if start >= edit.range.end {
+                    entry.end = shift(entry.end, delta);
+                    entry.delta += delta;
+                    Some(((shift(start, delta), id), entry))
+                } else {
+                    None
+                }
+            })
+            .collect();
+    }
+
+    /// The number of results held in the cache.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the cache holds no results.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Discard all results held in the cache.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub(crate) fn get<O: Clone + Rebase + 'static>(&self, key: (usize, MemoId)) -> Option<O> {
+        self.entries.get(&key)?.output()
+    }
+
+    pub(crate) fn end_of(&self, key: (usize, MemoId)) -> Option<usize> {
+        self.entries.get(&key).map(|entry| entry.end)
+    }
+
+    pub(crate) fn insert<O: 'static>(&mut self, key: (usize, MemoId), end: usize, out: O) {
+        self.entries.insert(
+            key,
+            Entry {
+                end,
+                delta: 0,
+                out: Box::new(out),
+            },
+        );
+    }
+}
+
+fn shift(x: usize, by: isize) -> usize {
+    (x as isize + by) as usize
+}
+
+/// A trait implemented by parser outputs that can be moved to a new location in the input.
+///
+/// Implementations should shift any spans (or other input locations) they contain by the given amount. Types that do
+/// not contain locations can implement this trait by doing nothing.
+pub trait Rebase {
+    /// Move all locations within this value by `delta`.
+    fn rebase(&mut self, delta: isize);
+}
+
+impl<C> Rebase for SimpleSpan<usize, C> {
+    fn rebase(&mut self, delta: isize) {
+        self.start = shift(self.start, delta);
+        self.end = shift(self.end, delta);
+    }
+}
+
+impl Rebase for Range<usize> {
+    fn rebase(&mut self, delta: isize) {
+        self.start = shift(self.start, delta);
+        self.end = shift(self.end, delta);
+    }
+}
+
+impl<T: Rebase> Rebase for Option<T> {
+    fn rebase(&mut self, delta: isize) {
+        if let Some(x) = self {
+            x.rebase(delta);
+        }
+    }
+}
+
+impl<T: Rebase, E: Rebase> Rebase for Result<T, E> {
+    fn rebase(&mut self, delta: isize) {
+        match self {
+            Ok(x) => x.rebase(delta),
+            Err(e) => e.rebase(delta),
+        }
+    }
+}
+
+impl<T: Rebase> Rebase for Box<T> {
+    fn rebase(&mut self, delta: isize) {
+        (**self).rebase(delta);
+    }
+}
+
+impl<T: Rebase> Rebase for Vec<T> {
+    fn rebase(&mut self, delta: isize) {
+        self.iter_mut().for_each(|x| x.rebase(delta));
+    }
+}
+
+impl<T: Rebase, const N: usize> Rebase for [T; N] {
+    fn rebase(&mut self, delta: isize) {
+        self.iter_mut().for_each(|x| x.rebase(delta));
+    }
+}
+
+macro_rules! impl_rebase_for_locationless {
+    ($($T:ty),* $(,)?) => {
+        $(
+            impl Rebase for $T {
+                #[inline(always)]
+                fn rebase(&mut self, _delta: isize) {}
+            }
+        )*
+    };
+}
+
+impl_rebase_for_locationless!(
+    (),
+    bool,
+    char,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    f32,
+    f64,
+    String,
+    &'static str,
+);
+
+macro_rules! impl_rebase_for_tuple {
+    () => {};
+    ($head:ident $($X:ident)*) => {
+        impl_rebase_for_tuple!($($X)*);
+        impl_rebase_for_tuple!(~ $head $($X)*);
+    };
+    (~ $($X:ident)+) => {
+        #[allow(non_snake_case)]
+        impl<$($X: Rebase),+> Rebase for ($($X,)+) {
+            fn rebase(&mut self, delta: isize) {
+                let ($($X,)+) = self;
+                $($X.rebase(delta);)+
+            }
+        }
+    };
+}
+
+impl_rebase_for_tuple!(A_ B_ C_ D_ E_ F_ G_ H_ I_ J_ K_ L_);
@@ -10,7 +10,7 @@ use inspector::Inspector;
 pub use crate::stream::{BoxedExactSizeStream, BoxedStream, IterInput, Stream};
 
 use super::*;
-use alloc::string::ToString;
+use alloc::{borrow::Cow, string::ToString};
 #[cfg(feature = "std")]
 use std::io::{BufReader, Read, Seek};
 
@@ -27,6 +27,9 @@ use std::io::{BufReader, Read, Seek};
 /// - `&[T]`: [`SliceInput`], [`ValueInput`], [`BorrowInput`], [`ExactSizeInput`]
 /// - `Stream<I>`: [`ValueInput`], [`ExactSizeInput`] if `I: ExactSizeIterator`
 /// - `IterInput<I>`: [`ValueInput`], [`ExactSizeInput`] if `I: ExactSizeIterator`
+/// - `ChunkedStr`: [`SliceInput`], [`StrInput`], [`ValueInput`], [`ExactSizeInput`]
+/// - `SpliceInput`: [`SliceInput`], [`StrInput`], [`ValueInput`], [`ExactSizeInput`]
+/// - `BitInput`: [`ValueInput`], [`ExactSizeInput`]
 pub trait Input<'src>: 'src {
     /// The type of a span on this input.
     ///
@@ -133,6 +136,33 @@ pub trait Input<'src>: 'src {
     /// must not be shared between multiple inputs.
     unsafe fn span(cache: &mut Self::Cache, range: Range<&Self::Cursor>) -> Self::Span;
 
+    // Advance the cursor over a run of tokens without inspecting them one at a time: while they appear in `tokens`
+    // or, if `until` is set, until one of them is found (or the input ends). Returns the number of tokens skipped, or
+    // `None` (leaving the cursor untouched) if this input has no fast path for the given tokens.
+    #[doc(hidden)]
+    #[inline(always)]
+    unsafe fn skip_tokens(
+        cache: &mut Self::Cache,
+        cursor: &mut Self::Cursor,
+        tokens: &[Self::Token],
+        until: bool,
+    ) -> Option<usize>
+    where
+        Self::Token: PartialEq,
+    {
+        let _ = (cache, cursor, tokens, until);
+        None
+    }
+
+    // Produce the cursor at the given location, without pulling the tokens before it, or `None` if this input can't
+    // do so (or the location isn't the boundary of a token).
+    #[doc(hidden)]
+    #[inline(always)]
+    unsafe fn cursor_at(cache: &mut Self::Cache, location: usize) -> Option<Self::Cursor> {
+        let _ = (cache, location);
+        None
+    }
+
     // /// Split an input that produces tokens of type `(T, S)` into one that produces tokens of type `T` and spans of
     // /// type `S`.
     // ///
@@ -275,7 +305,8 @@ pub trait SliceInput<'src>: ExactSizeInput<'src> {
 
 /// A trait for types that represent string-like streams of input tokens.
 ///
-/// Currently, this trait is only implemented by [`&[u8]` and `&str` (and other inputs that behave like them).
+/// Currently, this trait is only implemented by [`&[u8]`, `&str` and [`ChunkedStr`] (and other inputs that behave like
+/// them).
 ///
 /// This trait is sealed because future versions of chumsky might place extra requirements upon inputs that implement
 /// it.
@@ -355,6 +386,46 @@ impl<'src> Input<'src> for &'src str {
     unsafe fn span(_this: &mut Self::Cache, range: Range<&Self::Cursor>) -> Self::Span {
         (*range.start..*range.end).into()
     }
+
+    #[inline]
+    unsafe fn skip_tokens(
+        this: &mut Self::Cache,
+        cursor: &mut Self::Cursor,
+        tokens: &[Self::Token],
+        until: bool,
+    ) -> Option<usize> {
+        // ASCII bytes never appear inside a multi-byte UTF-8 sequence, so scanning bytes is only valid for them
+        if !tokens.iter().all(char::is_ascii) {
+            return None;
+        }
+        let rest = this.as_bytes().get(*cursor..)?;
+        let mask = tokens.iter().fold(0u128, |m, t| m | (1 << *t as u32));
+        let in_set = |b: &u8| b.is_ascii() && mask & (1 << *b) != 0;
+        let len = if !until {
+            rest.iter().position(|b| !in_set(b))
+        } else if tokens.len() <= 3 {
+            let mut needles = [0; 3];
+            for (n, t) in needles.iter_mut().zip(tokens) {
+                *n = *t as u8;
+            }
+            util::find_bytes(rest, &needles[..tokens.len()])
+        } else {
+            rest.iter().position(in_set)
+        }
+        .unwrap_or(rest.len());
+        *cursor += len;
+        // When skipping while in the set every byte is an ASCII character, otherwise count the bytes that begin one
+        Some(if until {
+            rest[..len].iter().filter(|b| (**b as i8) >= -0x40).count()
+        } else {
+            len
+        })
+    }
+
+    #[inline]
+    unsafe fn cursor_at(this: &mut Self::Cache, location: usize) -> Option<Self::Cursor> {
+        this.is_char_boundary(location).then_some(location)
+    }
 }
 
 impl<'src> ExactSizeInput<'src> for &'src str {
@@ -434,6 +505,30 @@ impl<'src, T> Input<'src> for &'src [T] {
     unsafe fn span(_this: &mut Self::Cache, range: Range<&Self::Cursor>) -> Self::Span {
         (*range.start..*range.end).into()
     }
+
+    #[inline]
+    unsafe fn skip_tokens(
+        this: &mut Self::Cache,
+        cursor: &mut Self::Cursor,
+        tokens: &[Self::Token],
+        until: bool,
+    ) -> Option<usize>
+    where
+        Self::Token: PartialEq,
+    {
+        let rest = this.get(*cursor..)?;
+        let n = rest
+            .iter()
+            .position(|tok| tokens.contains(tok) == until)
+            .unwrap_or(rest.len());
+        *cursor += n;
+        Some(n)
+    }
+
+    #[inline]
+    unsafe fn cursor_at(this: &mut Self::Cache, location: usize) -> Option<Self::Cursor> {
+        (location <= this.len()).then_some(location)
+    }
 }
 
 impl<'src, T> ExactSizeInput<'src> for &'src [T] {
@@ -527,6 +622,30 @@ impl<'src, T: 'src, const N: usize> Input<'src> for &'src [T; N] {
     unsafe fn span(_this: &mut Self::Cache, range: Range<&Self::Cursor>) -> Self::Span {
         (*range.start..*range.end).into()
     }
+
+    #[inline]
+    unsafe fn skip_tokens(
+        this: &mut Self::Cache,
+        cursor: &mut Self::Cursor,
+        tokens: &[Self::Token],
+        until: bool,
+    ) -> Option<usize>
+    where
+        Self::Token: PartialEq,
+    {
+        let rest = this.get(*cursor..)?;
+        let n = rest
+            .iter()
+            .position(|tok| tokens.contains(tok) == until)
+            .unwrap_or(rest.len());
+        *cursor += n;
+        Some(n)
+    }
+
+    #[inline]
+    unsafe fn cursor_at(this: &mut Self::Cache, location: usize) -> Option<Self::Cursor> {
+        (location <= this.len()).then_some(location)
+    }
 }
 
 impl<'src, T: 'src, const N: usize> ExactSizeInput<'src> for &'src [T; N] {
@@ -806,6 +925,24 @@ where
         let inner_span = I::span(cache, range);
         (mapper)(inner_span)
     }
+
+    #[inline(always)]
+    unsafe fn skip_tokens(
+        (cache, _): &mut Self::Cache,
+        cursor: &mut Self::Cursor,
+        tokens: &[Self::Token],
+        until: bool,
+    ) -> Option<usize>
+    where
+        Self::Token: PartialEq,
+    {
+        I::skip_tokens(cache, cursor, tokens, until)
+    }
+
+    #[inline(always)]
+    unsafe fn cursor_at((cache, _): &mut Self::Cache, location: usize) -> Option<Self::Cursor> {
+        I::cursor_at(cache, location)
+    }
 }
 
 impl<'src, S, I: Input<'src>, F: 'src> ExactSizeInput<'src> for MappedSpan<S, I, F>
@@ -958,6 +1095,24 @@ where
             inner_span.start().into()..inner_span.end().into(),
         )
     }
+
+    #[inline(always)]
+    unsafe fn skip_tokens(
+        (cache, _): &mut Self::Cache,
+        cursor: &mut Self::Cursor,
+        tokens: &[Self::Token],
+        until: bool,
+    ) -> Option<usize>
+    where
+        Self::Token: PartialEq,
+    {
+        I::skip_tokens(cache, cursor, tokens, until)
+    }
+
+    #[inline(always)]
+    unsafe fn cursor_at((cache, _): &mut Self::Cache, location: usize) -> Option<Self::Cursor> {
+        I::cursor_at(cache, location)
+    }
 }
 
 impl<'src, S, I: Input<'src>> ExactSizeInput<'src> for WithContext<S, I>
@@ -1056,6 +1211,199 @@ where
     }
 }
 
+/// An input made up of a sequence of string chunks, such as the leaves of a rope.
+///
+/// Cursors and spans are byte offsets into the text formed by joining all of the chunks together, so a parser will
+/// produce the same spans as it would have if it had been given a single [`&str`]. Slices of this input are
+/// [`Cow<str>`]s: a slice that falls within a single chunk borrows from it, and only slices that cross a chunk
+/// boundary need to allocate.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, input::ChunkedStr};
+/// let ident = text::ident::<_, extra::Err<Simple<char>>>().padded();
+/// let idents = ident.repeated().collect::<Vec<_>>();
+///
+/// let chunks = ["hel", "lo wo", "", "rld ", "!"];
+/// let idents = idents.then_ignore(just('!'));
+/// assert_eq!(
+///     idents.parse(ChunkedStr::new(&chunks)).into_result(),
+///     Ok(vec!["hello".into(), "world".into()]),
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct ChunkedStr<'src> {
+    chunks: &'src [&'src str],
+    // The byte offset at which each chunk starts
+    starts: Vec<usize>,
+    len: usize,
+    // The index of the chunk that was most recently read from, since most reads are sequential
+    last: usize,
+}
+
+impl<'src> ChunkedStr<'src> {
+    /// Create a new input from a sequence of string chunks.
+    pub fn new(chunks: &'src [&'src str]) -> Self {
+        let mut len = 0;
+        let starts = chunks
+            .iter()
+            .map(|chunk| {
+                let start = len;
+                len += chunk.len();
+                start
+            })
+            .collect();
+        Self {
+            chunks,
+            starts,
+            len,
+            last: 0,
+        }
+    }
+
+    /// Get the chunks that make up this input.
+    pub fn chunks(&self) -> &'src [&'src str] {
+        self.chunks
+    }
+
+    /// Get the total length, in bytes, of this input.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if this input contains no text.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // Find the index of the (non-empty) chunk containing the given offset, if any
+    pub(crate) fn locate(&mut self, offset: usize) -> Option<usize> {
+        if offset >= self.len {
+            return None;
+        }
+        let contains = |idx: usize| {
+            self.starts[idx] <= offset && offset < self.starts[idx] + self.chunks[idx].len()
+        };
+        let idx = if contains(self.last) {
+            self.last
+        } else if self.last + 1 < self.chunks.len() && contains(self.last + 1) {
+            self.last + 1
+        } else {
+            // Empty chunks share their start with the next chunk, so the last chunk that starts at or before the offset
+            // is always the non-empty one that contains it
+            self.starts.partition_point(|start| *start <= offset) - 1
+        };
+        self.last = idx;
+        Some(idx)
+    }
+
+    pub(crate) fn chunk_start(&self, idx: usize) -> usize {
+        self.starts[idx]
+    }
+
+    pub(crate) fn slice_range(&mut self, range: Range<usize>) -> Cow<'src, str> {
+        let Some(first) = self.locate(range.start).filter(|_| range.start < range.end) else {
+            return Cow::Borrowed("");
+        };
+        let first_start = self.starts[first];
+        let chunk = self.chunks[first];
+        if range.end <= first_start + chunk.len() {
+            Cow::Borrowed(&chunk[range.start - first_start..range.end - first_start])
+        } else {
+            let mut s = String::with_capacity(range.end - range.start);
+            for (chunk, start) in self.chunks[first..].iter().zip(&self.starts[first..]) {
+                if *start >= range.end {
+                    break;
+                }
+                let from = range.start.saturating_sub(*start);
+                let to = (range.end - start).min(chunk.len());
+                s.push_str(&chunk[from..to]);
+            }
+            Cow::Owned(s)
+        }
+    }
+}
+
+impl<'src> Input<'src> for ChunkedStr<'src> {
+    type Cursor = usize;
+    type Span = SimpleSpan<usize>;
+
+    type Token = char;
+    type MaybeToken = char;
+
+    type Cache = Self;
+
+    #[inline]
+    fn begin(self) -> (Self::Cursor, Self::Cache) {
+        (0, self)
+    }
+
+    #[inline]
+    fn cursor_location(cursor: &Self::Cursor) -> usize {
+        *cursor
+    }
+
+    #[inline]
+    unsafe fn next_maybe(
+        this: &mut Self::Cache,
+        cursor: &mut Self::Cursor,
+    ) -> Option<Self::MaybeToken> {
+        let idx = this.locate(*cursor)?;
+        let c = this.chunks[idx][*cursor - this.starts[idx]..]
+            .chars()
+            .next()?;
+        *cursor += c.len_utf8();
+        Some(c)
+    }
+
+    #[inline]
+    unsafe fn span(_this: &mut Self::Cache, range: Range<&Self::Cursor>) -> Self::Span {
+        (*range.start..*range.end).into()
+    }
+}
+
+impl<'src> ExactSizeInput<'src> for ChunkedStr<'src> {
+    #[inline]
+    unsafe fn span_from(this: &mut Self::Cache, range: RangeFrom<&Self::Cursor>) -> Self::Span {
+        (*range.start..this.len).into()
+    }
+}
+
+impl<'src> ValueInput<'src> for ChunkedStr<'src> {
+    #[inline]
+    unsafe fn next(this: &mut Self::Cache, cursor: &mut Self::Cursor) -> Option<Self::Token> {
+        Self::next_maybe(this, cursor)
+    }
+}
+
+impl Sealed for ChunkedStr<'_> {}
+impl<'src> StrInput<'src> for ChunkedStr<'src> {
+    #[doc(hidden)]
+    fn stringify(slice: Self::Slice) -> String {
+        slice.into_owned()
+    }
+}
+
+impl<'src> SliceInput<'src> for ChunkedStr<'src> {
+    type Slice = Cow<'src, str>;
+
+    #[inline]
+    fn full_slice(this: &mut Self::Cache) -> Self::Slice {
+        this.slice_range(0..this.len)
+    }
+
+    #[inline]
+    unsafe fn slice(this: &mut Self::Cache, range: Range<&Self::Cursor>) -> Self::Slice {
+        this.slice_range(*range.start..*range.end)
+    }
+
+    #[inline]
+    unsafe fn slice_from(this: &mut Self::Cache, from: RangeFrom<&Self::Cursor>) -> Self::Slice {
+        this.slice_range(*from.start..this.len)
+    }
+}
+
 /// Input type which supports seekable readers. Uses a [`BufReader`] internally to buffer input and
 /// avoid unnecessary IO calls.
 ///
@@ -1136,6 +1484,376 @@ impl<'src, R: Read + Seek + 'src> ValueInput<'src> for IoInput<R> {
     }
 }
 
+/// The order in which a [`BitInput`] reads the bits of each byte.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BitOrder {
+    /// The most significant bit of each byte comes first, and multi-bit values are read most significant bit first.
+    /// This is the order used by most network protocols.
+    #[default]
+    MsbFirst,
+    /// The least significant bit of each byte comes first, and multi-bit values are read least significant bit
+    /// first. This is the order used by formats such as DEFLATE.
+    LsbFirst,
+}
+
+/// Input type over a byte slice that produces individual bits (as [`bool`]s) as tokens.
+///
+/// Cursors and spans are measured in bits from the start of the input. Use [`BitInput::bits`] to read fields that
+/// are several bits wide, and [`BitInput::align`], [`BitInput::aligned_bytes`] and [`BitInput::rest_bytes`] to
+/// return to byte-aligned data.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, input::BitInput};
+/// // A packed header made up of a 3-bit version, a 5-bit kind and a 12-bit length
+/// let header = BitInput::bits::<extra::Err<Simple<bool>>>(3)
+///     .then(BitInput::bits(5))
+///     .then(BitInput::bits(12))
+///     .then_ignore(BitInput::align());
+///
+/// let bytes = [0b010_00111, 0b0000_0001, 0b0010_0000];
+/// assert_eq!(header.parse(BitInput::new(&bytes)).into_result(), Ok(((2, 7), 18)));
+///
+/// // Too few bits produces an error
+/// assert!(header.parse(BitInput::new(&bytes[..2])).has_errors());
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct BitInput<'src> {
+    bytes: &'src [u8],
+    order: BitOrder,
+}
+
+impl<'src> BitInput<'src> {
+    /// Create a new bit input over the given bytes, reading the most significant bit of each byte first.
+    pub fn new(bytes: &'src [u8]) -> Self {
+        Self {
+            bytes,
+            order: BitOrder::default(),
+        }
+    }
+
+    /// Set the order in which the bits of each byte are read.
+    pub fn with_order(self, order: BitOrder) -> Self {
+        Self { order, ..self }
+    }
+
+    /// Get the bytes that this input reads from.
+    pub fn as_bytes(&self) -> &'src [u8] {
+        self.bytes
+    }
+
+    /// Get the order in which this input reads bits.
+    pub fn order(&self) -> BitOrder {
+        self.order
+    }
+
+    /// A parser that reads an `n`-bit unsigned integer.
+    ///
+    /// With [`BitOrder::MsbFirst`], the first bit read is the most significant bit of the output. With
+    /// [`BitOrder::LsbFirst`], it is the least significant bit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than 64.
+    pub fn bits<E>(n: u32) -> impl Parser<'src, Self, u64, E> + Copy
+    where
+        E: ParserExtra<'src, Self>,
+    {
+        assert!(n <= 64, "cannot read more than 64 bits into a u64");
+        custom(move |inp: &mut InputRef<'src, '_, Self, E>| {
+            let order = inp.cache.order;
+            let mut value = 0;
+            for i in 0..n {
+                let bit = inp.parse(any())? as u64;
+                value = match order {
+                    BitOrder::MsbFirst => (value << 1) | bit,
+                    BitOrder::LsbFirst => value | (bit << i),
+                };
+            }
+            Ok(value)
+        })
+    }
+
+    /// A parser that skips any bits remaining before the next byte boundary.
+    ///
+    /// This parser always succeeds, and consumes nothing if the input is already byte-aligned.
+    pub fn align<E>() -> impl Parser<'src, Self, (), E> + Copy
+    where
+        E: ParserExtra<'src, Self>,
+    {
+        custom(|inp: &mut InputRef<'src, '_, Self, E>| {
+            Self::skip_to_boundary(inp);
+            Ok(())
+        })
+    }
+
+    /// A parser that skips to the next byte boundary (like [`BitInput::align`]) and then reads `n` whole bytes.
+    ///
+    /// The output can be parsed further by a parser that operates on `&[u8]`.
+    pub fn aligned_bytes<E>(n: usize) -> impl Parser<'src, Self, &'src [u8], E> + Copy
+    where
+        E: ParserExtra<'src, Self>,
+    {
+        custom(move |inp: &mut InputRef<'src, '_, Self, E>| {
+            let start = Self::skip_to_boundary(inp);
+            let bytes = inp.cache.bytes;
+            if bytes.len() - start < n {
+                // Report the missing bytes at the end of the input
+                let end = bytes.len() * 8;
+                return Err(LabelError::expected_found(
+                    [DefaultExpected::Any],
+                    None,
+                    (end..end).into(),
+                ));
+            }
+            Self::skip_bits(inp, n * 8);
+            Ok(&bytes[start..start + n])
+        })
+    }
+
+    /// A parser that skips to the next byte boundary (like [`BitInput::align`]) and then reads all remaining bytes.
+    pub fn rest_bytes<E>() -> impl Parser<'src, Self, &'src [u8], E> + Copy
+    where
+        E: ParserExtra<'src, Self>,
+    {
+        custom(|inp: &mut InputRef<'src, '_, Self, E>| {
+            let start = Self::skip_to_boundary(inp);
+            Self::skip_bits(inp, (inp.cache.bytes.len() - start) * 8);
+            Ok(&inp.cache.bytes[start..])
+        })
+    }
+
+    // Skip to the next byte boundary, returning the index of the byte that follows it
+    fn skip_to_boundary<E: ParserExtra<'src, Self>>(
+        inp: &mut InputRef<'src, '_, Self, E>,
+    ) -> usize {
+        Self::skip_bits(inp, (8 - inp.cursor % 8) % 8);
+        inp.cursor / 8
+    }
+
+    // Skip `n` bits, or to the end of the input if fewer remain
+    fn skip_bits<E: ParserExtra<'src, Self>>(inp: &mut InputRef<'src, '_, Self, E>, mut n: usize) {
+        if <E::State as Inspector<'src, Self>>::ON_TOKEN {
+            // The inspector needs to see every bit
+            inp.skip_while(|_| {
+                let skip = n > 0;
+                n = n.saturating_sub(1);
+                skip
+            });
+        } else {
+            inp.cursor = (inp.cursor + n).min(inp.cache.bytes.len() * 8);
+        }
+    }
+}
+
+impl<'src> Input<'src> for BitInput<'src> {
+    type Cursor = usize;
+    type Span = SimpleSpan<usize>;
+
+    type Token = bool;
+    type MaybeToken = bool;
+
+    type Cache = Self;
+
+    #[inline]
+    fn begin(self) -> (Self::Cursor, Self::Cache) {
+        (0, self)
+    }
+
+    #[inline]
+    fn cursor_location(cursor: &Self::Cursor) -> usize {
+        *cursor
+    }
+
+    #[inline(always)]
+    unsafe fn next_maybe(
+        this: &mut Self::Cache,
+        cursor: &mut Self::Cursor,
+    ) -> Option<Self::MaybeToken> {
+        let byte = *this.bytes.get(*cursor / 8)?;
+        let shift = match this.order {
+            BitOrder::MsbFirst => 7 - *cursor % 8,
+            BitOrder::LsbFirst => *cursor % 8,
+        };
+        *cursor += 1;
+        Some((byte >> shift) & 1 == 1)
+    }
+
+    #[inline(always)]
+    unsafe fn span(_this: &mut Self::Cache, range: Range<&Self::Cursor>) -> Self::Span {
+        (*range.start..*range.end).into()
+    }
+}
+
+impl<'src> ExactSizeInput<'src> for BitInput<'src> {
+    #[inline(always)]
+    unsafe fn span_from(this: &mut Self::Cache, range: RangeFrom<&Self::Cursor>) -> Self::Span {
+        (*range.start..this.bytes.len() * 8).into()
+    }
+}
+
+impl<'src> ValueInput<'src> for BitInput<'src> {
+    #[inline(always)]
+    unsafe fn next(this: &mut Self::Cache, cursor: &mut Self::Cursor) -> Option<Self::Token> {
+        Self::next_maybe(this, cursor)
+    }
+}
+
+/// Input type which supports any reader, including non-seekable ones such as pipes, sockets and standard input.
+///
+/// Unlike [`IoInput`], which seeks the reader whenever the parser backtracks, `ReadInput` keeps the bytes it has read
+/// in an internal buffer so that they can be revisited. To stop this buffer from growing without bound, place a
+/// [`ReadInput::commit`] parser at points in the grammar beyond which the parser will never backtrack (for example,
+/// after each complete record of a log file). Committing discards all buffered input before the current position.
+///
+/// Only available with the `std` feature
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, input::ReadInput};
+/// let line = none_of::<_, _, extra::Err<Simple<u8>>>(b'\n')
+///     .repeated()
+///     .collect::<Vec<_>>()
+///     .then_ignore(just(b'\n'))
+///     // Once a line has been parsed, the bytes before it are no longer needed
+///     .then_ignore(ReadInput::commit());
+///
+/// // `&[u8]` implements `Read`, but not `Seek`
+/// let reader: &[u8] = b"hello\nworld\n";
+/// assert_eq!(
+///     line.repeated().collect::<Vec<_>>().parse(ReadInput::new(reader)).into_result(),
+///     Ok(vec![b"hello".to_vec(), b"world".to_vec()]),
+/// );
+/// ```
+#[cfg(feature = "std")]
+pub struct ReadInput<R> {
+    reader: R,
+    // Holds the bytes that have been read but not yet discarded, followed by space for bytes yet to be read
+    buffer: Vec<u8>,
+    // The number of bytes in the buffer that have been read
+    pub(crate) filled: usize,
+    // The offset of the first byte in the buffer
+    offset: usize,
+    eof: bool,
+}
+
+#[cfg(feature = "std")]
+impl<R: Read> ReadInput<R> {
+    /// The number of bytes requested from the reader at once.
+    const CHUNK_SIZE: usize = 8 * 1024;
+
+    /// Create a new `ReadInput` from a reader.
+    pub fn new(reader: R) -> ReadInput<R> {
+        ReadInput {
+            reader,
+            buffer: Vec::new(),
+            filled: 0,
+            offset: 0,
+            eof: false,
+        }
+    }
+
+    /// A parser that commits to the input parsed so far, allowing the input to discard its buffered bytes.
+    ///
+    /// After this parser has run, the parser must not backtrack to an earlier position in the input: doing so will
+    /// result in a panic. This parser always succeeds and consumes no input.
+    ///
+    /// The output type of this parser is `()`.
+    pub fn commit<'src, E>() -> impl Parser<'src, Self, (), E> + Copy
+    where
+        R: 'src,
+        E: ParserExtra<'src, Self>,
+    {
+        custom(|inp: &mut InputRef<'src, '_, Self, E>| {
+            let at = *inp.cursor().inner();
+            inp.cache.discard_before(at);
+            Ok(())
+        })
+    }
+
+    fn discard_before(&mut self, at: usize) {
+        let n = at.saturating_sub(self.offset).min(self.filled);
+        self.buffer.copy_within(n..self.filled, 0);
+        self.filled -= n;
+        self.offset += n;
+    }
+
+    // Ensure that the byte at the given offset is buffered, returning `false` if the reader is exhausted first
+    fn fill_to(&mut self, at: usize) -> bool {
+        while at - self.offset >= self.filled {
+            if self.eof {
+                return false;
+            }
+            if self.filled == self.buffer.len() {
+                self.buffer.resize(self.filled + Self::CHUNK_SIZE, 0);
+            }
+            match self.reader.read(&mut self.buffer[self.filled..]) {
+                Ok(n) => {
+                    self.filled += n;
+                    self.eof = n == 0;
+                }
+                // Like `IoInput`, read errors are treated as the end of the input
+                Err(err) => self.eof = err.kind() != std::io::ErrorKind::Interrupted,
+            }
+        }
+        true
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'src, R: Read + 'src> Input<'src> for ReadInput<R> {
+    type Cursor = usize;
+    type Span = SimpleSpan;
+
+    type Token = u8;
+    type MaybeToken = u8;
+
+    type Cache = Self;
+
+    fn begin(self) -> (Self::Cursor, Self::Cache) {
+        (0, self)
+    }
+
+    #[inline(always)]
+    fn cursor_location(cursor: &Self::Cursor) -> usize {
+        *cursor
+    }
+
+    #[inline(always)]
+    unsafe fn next_maybe(
+        this: &mut Self::Cache,
+        cursor: &mut Self::Cursor,
+    ) -> Option<Self::MaybeToken> {
+        Self::next(this, cursor)
+    }
+
+    #[inline]
+    unsafe fn span(_this: &mut Self::Cache, range: Range<&Self::Cursor>) -> Self::Span {
+        (*range.start..*range.end).into()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'src, R: Read + 'src> ValueInput<'src> for ReadInput<R> {
+    unsafe fn next(this: &mut Self::Cache, cursor: &mut Self::Cursor) -> Option<Self::Token> {
+        assert!(
+            *cursor >= this.offset,
+            "attempted to backtrack to offset {} of a `ReadInput`, but input before offset {} has been committed",
+            *cursor,
+            this.offset,
+        );
+        if this.fill_to(*cursor) {
+            let byte = this.buffer[*cursor - this.offset];
+            *cursor += 1;
+            Some(byte)
+        } else {
+            None
+        }
+    }
+}
+
 /// Represents a location in an input that can be rewound to.
 ///
 /// Checkpoints can be created with [`InputRef::save`] and rewound to with [`InputRef::rewind`].
@@ -1220,6 +1938,8 @@ impl<'src, I: Input<'src>> Ord for Cursor<'src, '_, I> {
 pub(crate) struct Errors<T, E> {
     pub(crate) alt: Option<Located<T, E>>,
     pub(crate) secondary: Vec<Located<T, E>>,
+    /// Set once a [`Parser::cut`] has succeeded, cleared again when the innermost enclosing backtracking scope exits.
+    pub(crate) cut: bool,
 }
 
 impl<T, E> Errors<T, E> {
@@ -1235,6 +1955,7 @@ impl<T, E> Default for Errors<T, E> {
         Self {
             alt: None,
             secondary: Vec::new(),
+            cut: false,
         }
     }
 }
@@ -1248,7 +1969,10 @@ pub(crate) struct InputOwn<'src, 's, I: Input<'src>, E: ParserExtra<'src, I>> {
     pub(crate) state: MaybeMut<'s, E::State>,
     pub(crate) ctx: E::Context,
     #[cfg(feature = "memoization")]
-    pub(crate) memos: HashMap<(usize, usize), Option<Located<I::Cursor, E::Error>>>,
+    pub(crate) memos: memo::Memos<I::Cursor, E::Error>,
+    #[cfg(feature = "memoization")]
+    pub(crate) reuse: Option<&'s mut incremental::Cache>,
+    pub(crate) recorder: Option<&'s mut dyn debug::Recorder>,
 }
 
 impl<'src, 's, I, E> InputOwn<'src, 's, I, E>
@@ -1270,7 +1994,10 @@ where
             state: MaybeMut::Val(E::State::default()),
             ctx: E::Context::default(),
             #[cfg(feature = "memoization")]
-            memos: HashMap::default(),
+            memos: memo::Memos::default(),
+            #[cfg(feature = "memoization")]
+            reuse: None,
+            recorder: None,
         }
     }
 
@@ -1286,7 +2013,25 @@ where
             state: MaybeMut::Ref(state),
             ctx: E::Context::default(),
             #[cfg(feature = "memoization")]
-            memos: HashMap::default(),
+            memos: memo::Memos::default(),
+            #[cfg(feature = "memoization")]
+            reuse: None,
+            recorder: None,
+        }
+    }
+
+    #[cfg(feature = "memoization")]
+    pub(crate) fn new_incremental(
+        input: I,
+        state: &'s mut E::State,
+        reuse: &'s mut incremental::Cache,
+    ) -> InputOwn<'src, 's, I, E>
+    where
+        E::Context: Default,
+    {
+        InputOwn {
+            reuse: Some(reuse),
+            ..Self::new_state(input, state)
         }
     }
 
@@ -1299,15 +2044,39 @@ where
             ctx: &self.ctx,
             #[cfg(feature = "memoization")]
             memos: &mut self.memos,
+            #[cfg(feature = "memoization")]
+            reuse: self.reuse.as_deref_mut(),
+            trivia: None,
+            recorder: debug::reborrow(&mut self.recorder),
         }
     }
 
-    pub(crate) fn into_errs(self) -> Vec<E::Error> {
-        self.errors
+    /// Produce the result of a parse from the output of the parser (if it succeeded) and the error to report if it
+    /// didn't.
+    pub(crate) fn into_result<O>(
+        #[allow(unused_mut)] mut self,
+        res: Result<O, ()>,
+        alt: E::Error,
+    ) -> ParseResult<O, E::Error> {
+        #[cfg(feature = "memoization")]
+        let memo_stats = core::mem::take(&mut self.memos.stats);
+        let mut errs = self
+            .errors
             .secondary
             .into_iter()
             .map(|err| err.err)
-            .collect()
+            .collect::<Vec<_>>();
+        let out = match res {
+            Ok(out) => Some(out),
+            Err(()) => {
+                errs.push(alt);
+                None
+            }
+        };
+        let result = ParseResult::new(out, errs);
+        #[cfg(feature = "memoization")]
+        let result = result.with_memo_stats(memo_stats);
+        result
     }
 }
 
@@ -1319,20 +2088,37 @@ pub struct InputRef<'src, 'parse, I: Input<'src>, E: ParserExtra<'src, I>> {
     pub(crate) state: &'parse mut E::State,
     pub(crate) ctx: &'parse E::Context,
     #[cfg(feature = "memoization")]
-    pub(crate) memos: &'parse mut HashMap<(usize, usize), Option<Located<I::Cursor, E::Error>>>,
+    pub(crate) memos: &'parse mut memo::Memos<I::Cursor, E::Error>,
+    #[cfg(feature = "memoization")]
+    pub(crate) reuse: Option<&'parse mut incremental::Cache>,
+    /// The trivia that token-level parsers should skip, set by [`Parser::with_trivia`].
+    pub(crate) trivia: Option<Trivia<'src, 'parse, I, E>>,
+    /// The recorder that parsers marked with [`Parser::debug`] report their progress to, if tracing is enabled.
+    pub(crate) recorder: Option<&'parse mut dyn debug::Recorder>,
 }
 
+/// A function that skips trivia, such as whitespace or comments, at the current location of an input.
+pub(crate) type Trivia<'src, 'parse, I, E> =
+    &'parse (dyn for<'a> Fn(&mut InputRef<'src, 'a, I, E>) + 'parse);
+
 impl<'src, 'parse, I: Input<'src>, E: ParserExtra<'src, I>> InputRef<'src, 'parse, I, E> {
     #[inline]
     pub(crate) fn with_ctx<'sub_parse, EM, O>(
         &'sub_parse mut self,
         new_ctx: &'sub_parse EM::Context,
-        f: impl FnOnce(&mut InputRef<'src, 'sub_parse, I, EM>) -> O,
+        f: impl for<'a> FnOnce(&mut InputRef<'src, 'a, I, EM>) -> O,
     ) -> O
     where
         'parse: 'sub_parse,
         EM: ParserExtra<'src, I, Error = E::Error, State = E::State>,
     {
+        // Trivia was declared with the outer context, so it must keep running with it
+        let (outer_ctx, outer_trivia) = (self.ctx, self.trivia);
+        let trivia = |inp: &mut InputRef<'src, '_, I, EM>| {
+            if let Some(trivia) = outer_trivia {
+                inp.skip_foreign_trivia::<E>(|state| state, outer_ctx, trivia);
+            }
+        };
         let mut new_inp = InputRef {
             cursor: self.cursor.clone(),
             cache: self.cache,
@@ -1341,6 +2127,10 @@ impl<'src, 'parse, I: Input<'src>, E: ParserExtra<'src, I>> InputRef<'src, 'pars
             errors: self.errors,
             #[cfg(feature = "memoization")]
             memos: self.memos,
+            #[cfg(feature = "memoization")]
+            reuse: self.reuse.as_deref_mut(),
+            trivia: outer_trivia.map(|_| &trivia as Trivia<'src, '_, I, EM>),
+            recorder: debug::reborrow(&mut self.recorder),
         };
         let res = f(&mut new_inp);
         self.cursor = new_inp.cursor;
@@ -1351,12 +2141,22 @@ impl<'src, 'parse, I: Input<'src>, E: ParserExtra<'src, I>> InputRef<'src, 'pars
     pub(crate) fn with_state<'sub_parse, S, O>(
         &'sub_parse mut self,
         new_state: &'sub_parse mut S,
-        f: impl FnOnce(&mut InputRef<'src, 'sub_parse, I, extra::Full<E::Error, S, E::Context>>) -> O,
+        f: impl for<'a> FnOnce(&mut InputRef<'src, 'a, I, extra::Full<E::Error, S, E::Context>>) -> O,
     ) -> O
     where
         'parse: 'sub_parse,
         S: 'src + Inspector<'src, I>,
     {
+        // Trivia was declared with the outer state, so it must keep running with it
+        let outer_trivia = self.trivia;
+        let outer_state = RefCell::new(&mut *self.state);
+        let trivia = |inp: &mut InputRef<'src, '_, I, extra::Full<E::Error, S, E::Context>>| {
+            if let Some(trivia) = outer_trivia {
+                let ctx = inp.ctx;
+                let mut state = outer_state.borrow_mut();
+                inp.skip_foreign_trivia::<E>(|_| &mut **state, ctx, trivia);
+            }
+        };
         let mut new_inp = InputRef {
             cursor: self.cursor.clone(),
             cache: self.cache,
@@ -1365,12 +2165,77 @@ impl<'src, 'parse, I: Input<'src>, E: ParserExtra<'src, I>> InputRef<'src, 'pars
             errors: self.errors,
             #[cfg(feature = "memoization")]
             memos: self.memos,
+            #[cfg(feature = "memoization")]
+            reuse: self.reuse.as_deref_mut(),
+            trivia: outer_trivia.map(|_| &trivia as Trivia<'src, '_, I, _>),
+            recorder: debug::reborrow(&mut self.recorder),
+        };
+        let res = f(&mut new_inp);
+        self.cursor = new_inp.cursor;
+        res
+    }
+
+    /// Run a parser with the given trivia, or with no trivia if `trivia` is `None`.
+    #[inline]
+    pub(crate) fn with_trivia<O>(
+        &mut self,
+        trivia: Option<Trivia<'src, '_, I, E>>,
+        f: impl for<'a> FnOnce(&mut InputRef<'src, 'a, I, E>) -> O,
+    ) -> O {
+        let mut new_inp = InputRef {
+            cursor: self.cursor.clone(),
+            cache: &mut *self.cache,
+            state: &mut *self.state,
+            ctx: self.ctx,
+            errors: &mut *self.errors,
+            #[cfg(feature = "memoization")]
+            memos: &mut *self.memos,
+            #[cfg(feature = "memoization")]
+            reuse: self.reuse.as_deref_mut(),
+            trivia,
+            recorder: debug::reborrow(&mut self.recorder),
         };
         let res = f(&mut new_inp);
         self.cursor = new_inp.cursor;
         res
     }
 
+    /// Skip any trivia at the current location. Trivia is disabled while it runs, so token-level parsers within the
+    /// trivia parser don't recurse into it.
+    #[inline]
+    pub(crate) fn skip_trivia(&mut self) {
+        if let Some(trivia) = self.trivia.take() {
+            trivia(self);
+            self.trivia = Some(trivia);
+        }
+    }
+
+    /// Skip trivia that was declared with a different state or context to the one this input currently has.
+    fn skip_foreign_trivia<'b, F>(
+        &'b mut self,
+        state: impl FnOnce(&'b mut E::State) -> &'b mut F::State,
+        ctx: &'b F::Context,
+        trivia: Trivia<'src, '_, I, F>,
+    ) where
+        F: ParserExtra<'src, I, Error = E::Error>,
+    {
+        let mut new_inp = InputRef {
+            cursor: self.cursor.clone(),
+            cache: &mut *self.cache,
+            state: state(&mut *self.state),
+            ctx,
+            errors: &mut *self.errors,
+            #[cfg(feature = "memoization")]
+            memos: &mut *self.memos,
+            #[cfg(feature = "memoization")]
+            reuse: self.reuse.as_deref_mut(),
+            trivia: None,
+            recorder: debug::reborrow(&mut self.recorder),
+        };
+        trivia(&mut new_inp);
+        self.cursor = new_inp.cursor;
+    }
+
     #[inline]
     pub(crate) fn with_input<'sub_parse, J, F, O>(
         &'sub_parse mut self,
@@ -1378,10 +2243,7 @@ impl<'src, 'parse, I: Input<'src>, E: ParserExtra<'src, I>> InputRef<'src, 'pars
         cache: &'sub_parse mut J::Cache,
         new_errors: &'sub_parse mut Errors<J::Cursor, F::Error>,
         f: impl FnOnce(&mut InputRef<'src, 'sub_parse, J, F>) -> O,
-        #[cfg(feature = "memoization")] memos: &'sub_parse mut HashMap<
-            (usize, usize),
-            Option<Located<J::Cursor, E::Error>>,
-        >,
+        #[cfg(feature = "memoization")] memos: &'sub_parse mut memo::Memos<J::Cursor, E::Error>,
     ) -> O
     where
         'parse: 'sub_parse,
@@ -1396,6 +2258,13 @@ impl<'src, 'parse, I: Input<'src>, E: ParserExtra<'src, I>> InputRef<'src, 'pars
             errors: new_errors,
             #[cfg(feature = "memoization")]
             memos,
+            // Results from a nested input have locations that don't correspond to the outer input
+            #[cfg(feature = "memoization")]
+            reuse: None,
+            // Trivia is a parser of the outer input, so it can't be skipped within the nested one
+            trivia: None,
+            // Positions within a nested input don't correspond to those of the outer input
+            recorder: None,
         };
         let out = f(&mut new_inp);
         self.errors.secondary.extend(
@@ -1408,6 +2277,7 @@ impl<'src, 'parse, I: Input<'src>, E: ParserExtra<'src, I>> InputRef<'src, 'pars
         if let Some(alt) = new_inp.errors.alt.take() {
             self.errors.alt = Some(Located::at(self.cursor.clone(), alt.err));
         }
+        self.errors.cut |= new_inp.errors.cut;
         out
     }
 
@@ -1450,6 +2320,12 @@ impl<'src, 'parse, I: Input<'src>, E: ParserExtra<'src, I>> InputRef<'src, 'pars
     ) {
         self.errors.secondary.truncate(checkpoint.err_count);
         self.state.on_rewind(&checkpoint);
+        if let Some(recorder) = self.recorder.as_deref_mut() {
+            recorder.rewind(
+                I::cursor_location(&self.cursor),
+                I::cursor_location(&checkpoint.cursor.inner),
+            );
+        }
         self.cursor = checkpoint.cursor.inner;
     }
 
@@ -1489,6 +2365,35 @@ impl<'src, 'parse, I: Input<'src>, E: ParserExtra<'src, I>> InputRef<'src, 'pars
         }
     }
 
+    /// Look at the tokens ahead of the current position until `f` returns `false`, without consuming them.
+    pub(crate) fn peek_while<F: FnMut(&I::Token) -> bool>(&mut self, mut f: F)
+    where
+        I: Input<'src>,
+    {
+        let mut cursor = self.cursor.clone();
+        // SAFETY: cursor was generated by previous call to `Input::next`
+        while let Some(token) = unsafe { I::next_maybe(self.cache, &mut cursor) } {
+            if !f(token.borrow()) {
+                break;
+            }
+        }
+    }
+
+    /// Skip over tokens in bulk using [`Input::skip_tokens`], either while they appear in `tokens` or (if `until` is
+    /// set) until one of them is found. Returns the number of tokens skipped, or `None` if no fast path is available
+    /// (either because the input has none or because the inspector needs to observe every token).
+    #[inline(always)]
+    pub(crate) fn skip_tokens(&mut self, tokens: &[I::Token], until: bool) -> Option<usize>
+    where
+        I::Token: PartialEq,
+    {
+        if <E::State as Inspector<'src, I>>::ON_TOKEN {
+            return None;
+        }
+        // SAFETY: cursor was generated by previous call to `Input::next`
+        unsafe { I::skip_tokens(self.cache, &mut self.cursor, tokens, until) }
+    }
+
     #[inline(always)]
     pub(crate) fn next_inner(&mut self) -> Option<I::Token>
     where
@@ -1792,6 +2697,32 @@ impl<'src, 'parse, I: Input<'src>, E: ParserExtra<'src, I>> InputRef<'src, 'pars
         });
     }
 
+    /// Move the input to a cursor that was previously produced by it, such as the end of a memoized seed.
+    #[cfg(feature = "memoization")]
+    #[inline(always)]
+    pub(crate) fn skip_to(&mut self, cursor: I::Cursor) {
+        self.cursor = cursor;
+    }
+
+    /// Move the input forward to the given location without pulling the tokens in between. Returns `false`, leaving
+    /// the input untouched, if the input has no way to do so or the inspector needs to observe every token.
+    #[cfg(feature = "memoization")]
+    #[inline]
+    pub(crate) fn jump_to(&mut self, location: usize) -> bool {
+        if <E::State as Inspector<'src, I>>::ON_TOKEN || location < I::cursor_location(&self.cursor)
+        {
+            return false;
+        }
+        // SAFETY: the cache was generated by this input
+        match unsafe { I::cursor_at(self.cache, location) } {
+            Some(cursor) => {
+                self.cursor = cursor;
+                true
+            }
+            None => false,
+        }
+    }
+
     // Take the alt error, if one exists
     pub(crate) fn take_alt(&mut self) -> Option<Located<I::Cursor, E::Error>> {
         self.errors.alt.take()
@@ -1877,4 +2808,15 @@ impl<'src, 'b, I: Input<'src>, E: ParserExtra<'src, I>> MapExtra<'src, 'b, I, E>
     pub fn ctx(&self) -> &E::Context {
         self.ctx
     }
+    /// Get the line and column at which the output starts, using the [`LineIndex`](span::LineIndex) held by the
+    /// parser state.
+    ///
+    /// See [`AsLineIndex`](span::AsLineIndex) for an example.
+    #[inline]
+    pub fn line_col(&self) -> span::LineCol
+    where
+        E::State: span::AsLineIndex,
+    {
+        span::AsLineIndex::as_line_index(&*self.state).line_col(I::cursor_location(self.before))
+    }
 }
@@ -22,6 +22,11 @@ pub trait Inspector<'src, I: Input<'src>> {
     /// For implementation reasons, this is required to be `Clone`.
     type Checkpoint: Clone;
 
+    // Whether [`Inspector::on_token`] does anything. Inspectors that ignore tokens can set this to `false`, which
+    // allows scanning primitives to skip over runs of input in bulk rather than token-by-token.
+    #[doc(hidden)]
+    const ON_TOKEN: bool = true;
+
     /// This function is called when a new token is read from the input stream.
     // impl note: this should be called only when `self.cursor` is updated, not when we only peek at the next token.
     fn on_token(&mut self, token: &I::Token);
@@ -36,6 +41,7 @@ pub trait Inspector<'src, I: Input<'src>> {
 
 impl<'src, I: Input<'src>> Inspector<'src, I> for () {
     type Checkpoint = ();
+    const ON_TOKEN: bool = false;
     #[inline(always)]
     fn on_token(&mut self, _: &<I as Input<'src>>::Token) {}
     #[inline(always)]
@@ -51,6 +57,7 @@ impl<'src, I: Input<'src>> Inspector<'src, I> for () {
 pub struct SimpleState<T>(pub T);
 impl<'src, T, I: Input<'src>> Inspector<'src, I> for SimpleState<T> {
     type Checkpoint = ();
+    const ON_TOKEN: bool = false;
     #[inline(always)]
     fn on_token(&mut self, _: &<I as Input<'src>>::Token) {}
     #[inline(always)]
@@ -89,6 +96,7 @@ impl<T> From<T> for SimpleState<T> {
 pub struct RollbackState<T>(pub T);
 impl<'src, T: Clone, I: Input<'src>> Inspector<'src, I> for RollbackState<T> {
     type Checkpoint = T;
+    const ON_TOKEN: bool = false;
     #[inline(always)]
     fn on_token(&mut self, _: &<I as Input<'src>>::Token) {}
     #[inline(always)]
@@ -124,6 +132,7 @@ impl<T> From<T> for RollbackState<T> {
 pub struct TruncateState<T>(pub Vec<T>);
 impl<'src, T: Clone, I: Input<'src>> Inspector<'src, I> for TruncateState<T> {
     type Checkpoint = usize;
+    const ON_TOKEN: bool = false;
     #[inline(always)]
     fn on_token(&mut self, _: &<I as Input<'src>>::Token) {}
     #[inline(always)]
@@ -64,6 +64,8 @@ pub struct Labelled<A, L> {
     pub(crate) parser: A,
     pub(crate) label: L,
     pub(crate) is_context: bool,
+    #[cfg(feature = "grammar")]
+    pub(crate) rule_name: Option<fn(&L) -> String>,
 }
 
 impl<A, L> Labelled<A, L> {
@@ -76,6 +78,21 @@ impl<A, L> Labelled<A, L> {
             ..self
         }
     }
+
+    /// Use the label as the name of a rule when describing the grammar of this parser with [`Parser::grammar`].
+    ///
+    /// The rule is named after the [`Debug`](fmt::Debug) representation of the label, without quotes if the label is a
+    /// string.
+    #[cfg(feature = "grammar")]
+    pub fn as_rule(self) -> Self
+    where
+        L: fmt::Debug,
+    {
+        Self {
+            rule_name: Some(grammar::label_name::<L>),
+            ..self
+        }
+    }
 }
 
 impl<'src, I, O, E, A, L> Parser<'src, I, O, E> for Labelled<A, L>
@@ -120,5 +137,36 @@ where
         res
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        match self.rule_name {
+            Some(rule_name) => {
+                scope.labelled(rule_name(&self.label), |scope| self.parser.node_info(scope))
+            }
+            None => self.parser.node_info(scope),
+        }
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.parser.prefixes()
+    }
+
     go_extra!(O);
 }
@@ -28,22 +28,36 @@ macro_rules! go_extra {
     };
 }
 
+pub mod binary;
 mod blanket;
 #[cfg(feature = "unstable")]
 pub mod cache;
+pub mod class;
 pub mod combinator;
 pub mod container;
+pub mod cst;
+pub mod debug;
 #[cfg(feature = "either")]
 mod either;
 pub mod error;
 #[cfg(feature = "extension")]
 pub mod extension;
 pub mod extra;
+#[cfg(feature = "generate")]
+pub mod generate;
+#[cfg(feature = "grammar")]
+pub mod grammar;
 #[cfg(docsrs)]
 pub mod guide;
+#[cfg(feature = "memoization")]
+pub mod incremental;
 pub mod input;
 pub mod inspector;
 pub mod label;
+#[cfg(feature = "grammar")]
+pub mod lint;
+#[cfg(feature = "memoization")]
+pub mod memo;
 #[cfg(feature = "lexical-numbers")]
 pub mod number;
 #[cfg(feature = "pratt")]
@@ -54,9 +68,11 @@ pub mod recovery;
 pub mod recursive;
 #[cfg(feature = "regex")]
 pub mod regex;
+pub mod source;
 pub mod span;
 mod stream;
 pub mod text;
+pub mod token_tree;
 #[cfg(feature = "bytes")]
 mod tokio;
 pub mod util;
@@ -75,7 +91,9 @@ pub mod prelude {
         extra,
         input::Input,
         primitive::{
-            any, any_ref, choice, custom, empty, end, group, just, map_ctx, none_of, one_of, todo,
+            any, any_ref, choice, choice_dispatch, custom, empty, end, group, just, map_ctx,
+            none_of, one_of, permutation, take_till1, take_until, take_until_literal, take_while,
+            todo,
         },
         recovery::{nested_delimiters, skip_then_retry_until, skip_until, via_parser},
         recursive::{recursive, Recursive},
@@ -90,13 +108,14 @@ use alloc::{
     boxed::Box,
     rc::{self, Rc},
     string::String,
+    sync::Arc,
     vec,
     vec::Vec,
 };
 #[cfg(feature = "nightly")]
 use core::marker::Tuple;
 use core::{
-    borrow::Borrow,
+    borrow::{Borrow, BorrowMut},
     cell::{Cell, RefCell},
     cmp::{Eq, Ord, Ordering},
     fmt,
@@ -172,6 +191,8 @@ pub enum DefaultExpected<'a, T> {
     SomethingElse,
     /// The end of input was expected.
     EndOfInput,
+    /// A token from a class of tokens, described by a pattern such as `[a-zA-Z_]`, was expected.
+    Class(Arc<str>),
 }
 
 impl<T> DefaultExpected<'_, T> {
@@ -186,6 +207,7 @@ impl<T> DefaultExpected<'_, T> {
             Self::Any => DefaultExpected::Any,
             Self::SomethingElse => DefaultExpected::SomethingElse,
             Self::EndOfInput => DefaultExpected::EndOfInput,
+            Self::Class(pattern) => DefaultExpected::Class(pattern),
         }
     }
 }
@@ -198,15 +220,56 @@ impl<T> DefaultExpected<'_, T> {
 ///
 /// If you don't care for recovered outputs and you with to treat success/failure as a binary, you may use
 /// [`ParseResult::into_result`].
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone)]
 pub struct ParseResult<T, E> {
     output: Option<T>,
     errs: Vec<E>,
+    #[cfg(feature = "memoization")]
+    memo_stats: memo::MemoStats,
+}
+
+// Statistics describe how the result was produced rather than the result itself, so they're ignored by comparisons
+
+impl<T: PartialEq, E: PartialEq> PartialEq for ParseResult<T, E> {
+    fn eq(&self, other: &Self) -> bool {
+        (&self.output, &self.errs) == (&other.output, &other.errs)
+    }
+}
+
+impl<T: Eq, E: Eq> Eq for ParseResult<T, E> {}
+
+impl<T: PartialOrd, E: PartialOrd> PartialOrd for ParseResult<T, E> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        (&self.output, &self.errs).partial_cmp(&(&other.output, &other.errs))
+    }
+}
+
+impl<T: Ord, E: Ord> Ord for ParseResult<T, E> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.output, &self.errs).cmp(&(&other.output, &other.errs))
+    }
+}
+
+impl<T: Hash, E: Hash> Hash for ParseResult<T, E> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.output.hash(state);
+        self.errs.hash(state);
+    }
 }
 
 impl<T, E> ParseResult<T, E> {
     pub(crate) fn new(output: Option<T>, errs: Vec<E>) -> ParseResult<T, E> {
-        ParseResult { output, errs }
+        ParseResult {
+            output,
+            errs,
+            #[cfg(feature = "memoization")]
+            memo_stats: memo::MemoStats::default(),
+        }
+    }
+
+    #[cfg(feature = "memoization")]
+    pub(crate) fn with_memo_stats(self, memo_stats: memo::MemoStats) -> Self {
+        Self { memo_stats, ..self }
     }
 
     /// Whether this result contains output
@@ -230,6 +293,14 @@ impl<T, E> ParseResult<T, E> {
         self.errs.iter()
     }
 
+    /// Get statistics about how [memoized](Parser::memoized) parsers were used during the parse.
+    ///
+    /// See the [`memo`] module for more information.
+    #[cfg(feature = "memoization")]
+    pub fn memo_stats(&self) -> &memo::MemoStats {
+        &self.memo_stats
+    }
+
     /// Convert this `ParseResult` into an option containing the output, if any exists
     pub fn into_output(self) -> Option<T> {
         self.output
@@ -329,6 +400,46 @@ pub trait Parser<'src, I: Input<'src>, O, E: ParserExtra<'src, I> = extra::Defau
     #[doc(hidden)]
     fn go_check(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<Check, O>;
 
+    #[cfg(feature = "grammar")]
+    #[doc(hidden)]
+    fn node_info(&self, _scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::Unknown
+    }
+
+    #[cfg(feature = "generate")]
+    #[doc(hidden)]
+    fn gen_tokens(
+        &self,
+        _scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        Err(generate::unsupported::<Self>())
+    }
+
+    // Sequences of tokens, one of which begins every input that the parser accepts (after any trivia), or `None` if
+    // they aren't known. Used by `choice_dispatch` to skip alternatives that can't match.
+    #[doc(hidden)]
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        None
+    }
+
+    // Consume as many consecutive matches of this parser as can be found cheaply (for example, by scanning the input in
+    // bulk), returning how many were consumed. Used by `Repeated` to fast-forward through long runs of trivial items.
+    // Implementations must only consume input that repeated calls to `go` would also have consumed.
+    #[doc(hidden)]
+    fn skip_many(&self, inp: &mut InputRef<'src, '_, I, E>) -> usize {
+        let _ = inp;
+        0
+    }
+
     /// Parse a stream of tokens, yielding an output if possible, and any errors encountered along the way.
     ///
     /// If `None` is returned (i.e: parsing failed) then there will *always* be at least one item in the error `Vec`.
@@ -366,15 +477,7 @@ pub trait Parser<'src, I: Input<'src>, O, E: ParserExtra<'src, I> = extra::Defau
             // TODO: Why is this needed?
             E::Error::expected_found([], None, fake_span)
         });
-        let mut errs = own.into_errs();
-        let out = match res {
-            Ok(out) => Some(out),
-            Err(()) => {
-                errs.push(alt);
-                None
-            }
-        };
-        ParseResult::new(out, errs)
+        own.into_result(res, alt)
     }
 
     /// Parse a stream of tokens, ignoring any output, and returning any errors encountered along the way.
@@ -415,15 +518,108 @@ pub trait Parser<'src, I: Input<'src>, O, E: ParserExtra<'src, I> = extra::Defau
             // TODO: Why is this needed?
             E::Error::expected_found([], None, fake_span)
         });
-        let mut errs = own.into_errs();
-        let out = match res {
-            Ok(()) => Some(()),
-            Err(()) => {
-                errs.push(alt);
-                None
-            }
+        own.into_result(res, alt)
+    }
+
+    /// Parse a stream of tokens, reusing the results of [incremental](Parser::incremental) parsers held in `cache`
+    /// from a previous parse, and storing new results in it for later parses.
+    ///
+    /// When the input is changed between parses, the cache should be informed of the change with
+    /// [`incremental::Cache::edit`]. See the [`incremental`] module for more information.
+    ///
+    /// If you want to include non-default state, use [`Parser::parse_incremental_with_state`] instead.
+    #[cfg(feature = "memoization")]
+    fn parse_incremental(
+        &self,
+        input: I,
+        cache: &mut incremental::Cache,
+    ) -> ParseResult<O, E::Error>
+    where
+        Self: Sized,
+        I: Input<'src>,
+        E::State: Default,
+        E::Context: Default,
+    {
+        self.parse_incremental_with_state(input, &mut E::State::default(), cache)
+    }
+
+    /// Parse a stream of tokens, reusing the results of [incremental](Parser::incremental) parsers held in `cache`
+    /// from a previous parse, and storing new results in it for later parses.
+    /// The provided state will be passed on to parsers that expect it, such as [`map_with`](Parser::map_with).
+    ///
+    /// If you want to just use a default state value, use [`Parser::parse_incremental`] instead.
+    #[cfg(feature = "memoization")]
+    fn parse_incremental_with_state(
+        &self,
+        input: I,
+        state: &mut E::State,
+        cache: &mut incremental::Cache,
+    ) -> ParseResult<O, E::Error>
+    where
+        Self: Sized,
+        I: Input<'src>,
+        E::Context: Default,
+    {
+        let mut own = InputOwn::new_incremental(input, state, cache);
+        let mut inp = own.as_ref_start();
+        let res = self.then_ignore(end()).go::<Emit>(&mut inp);
+        let alt = inp.take_alt().map(|alt| alt.err).unwrap_or_else(|| {
+            let fake_span = inp.span_since(&inp.cursor());
+            // TODO: Why is this needed?
+            E::Error::expected_found([], None, fake_span)
+        });
+        own.into_result(res, alt)
+    }
+
+    /// Parse a stream of tokens, reporting the progress of parsers marked with [`Parser::debug`] to `recorder`: either
+    /// a [`debug::Trace`], which records each parser run, or a [`debug::Profile`], which collects statistics about the
+    /// work done by each parser.
+    ///
+    /// See the [`debug`] module for more information.
+    ///
+    /// If you want to include non-default state, use [`Parser::parse_traced_with_state`] instead.
+    fn parse_traced<R: debug::Recorder>(
+        &self,
+        input: I,
+        recorder: &mut R,
+    ) -> ParseResult<O, E::Error>
+    where
+        Self: Sized,
+        I: Input<'src>,
+        E::State: Default,
+        E::Context: Default,
+    {
+        self.parse_traced_with_state(input, &mut E::State::default(), recorder)
+    }
+
+    /// Parse a stream of tokens, reporting the progress of parsers marked with [`Parser::debug`] to `recorder`.
+    /// The provided state will be passed on to parsers that expect it, such as [`map_with`](Parser::map_with).
+    ///
+    /// If you want to just use a default state value, use [`Parser::parse_traced`] instead.
+    fn parse_traced_with_state<R: debug::Recorder>(
+        &self,
+        input: I,
+        state: &mut E::State,
+        recorder: &mut R,
+    ) -> ParseResult<O, E::Error>
+    where
+        Self: Sized,
+        I: Input<'src>,
+        E::Context: Default,
+    {
+        recorder.begin();
+        let mut own = InputOwn {
+            recorder: Some(recorder),
+            ..InputOwn::new_state(input, state)
         };
-        ParseResult::new(out, errs)
+        let mut inp = own.as_ref_start();
+        let res = self.then_ignore(end()).go::<Emit>(&mut inp);
+        let alt = inp.take_alt().map(|alt| alt.err).unwrap_or_else(|| {
+            let fake_span = inp.span_since(&inp.cursor());
+            // TODO: Why is this needed?
+            E::Error::expected_found([], None, fake_span)
+        });
+        own.into_result(res, alt)
     }
 
     /// Convert the output of this parser into a slice of the input, based on the current parser's
@@ -827,14 +1023,77 @@ pub trait Parser<'src, I: Input<'src>, O, E: ParserExtra<'src, I> = extra::Defau
     /// with `O(n)`, albeit with very significant per-element overhead and high memory usage.
     ///
     /// Memoization also works with recursion, so this can be used to write parsers using
-    /// [left recursion](https://en.wikipedia.org/wiki/Left_recursion).
-    // TODO: Example
+    /// [left recursion](https://en.wikipedia.org/wiki/Left_recursion). When a memoized parser invokes itself at the
+    /// same input location, either directly or via other parsers, the inner invocation initially fails. If the outer
+    /// invocation nevertheless succeeds, its result is used as a 'seed': the parser runs again with the inner invocation
+    /// producing the seed, and this repeats for as long as the result keeps getting longer. This is the 'seed growing'
+    /// technique described by Warth et al. in
+    /// [Packrat Parsers Can Support Left Recursion](https://web.cs.ucla.edu/~todd/research/pepm08.pdf), and it means
+    /// that left-recursive rules produce the longest possible match with the left-associativity you'd expect.
+    ///
+    /// The outputs of seeds aren't kept, so the output of a left-recursive rule is produced by parsing it one final time
+    /// once its seed has stopped growing.
+    ///
+    /// Each memoized parser is given a [`memo::MemoId`] when it is created, which is shared by its clones. After a parse,
+    /// [`ParseResult::memo_stats`] can be used to find out how often memoization avoided running the parser. See the
+    /// [`memo`] module for more information.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// // expr = expr '-' int | int
+    /// let expr = recursive(|expr| {
+    ///     let int = text::int::<_, extra::Err<Simple<char>>>(10).from_str::<i64>().unwrapped();
+    ///
+    ///     expr.then_ignore(just('-'))
+    ///         .then(int)
+    ///         .map(|(a, b)| a - b)
+    ///         .or(int)
+    ///         .memoized()
+    /// });
+    ///
+    /// // Subtraction is left-associative: (10 - 3) - 2
+    /// assert_eq!(expr.parse("10-3-2").into_result(), Ok(5));
+    /// ```
     #[cfg(feature = "memoization")]
     fn memoized(self) -> Memoized<Self>
     where
         Self: Sized,
     {
-        Memoized { parser: self }
+        Memoized {
+            id: memo::MemoId::fresh(),
+            name: None,
+            max_entries: None,
+            parser: self,
+        }
+    }
+
+    /// Allow the output of this parser to be reused by later parses of an edited input.
+    ///
+    /// When parsing with [`Parser::parse_incremental`], successful outputs of this parser are stored in the provided
+    /// [`incremental::Cache`]. If a later parse invokes this parser at a location for which the cache already holds a
+    /// result that was not touched by an edit, the parser is not run again: the stored output is reused (with its
+    /// spans moved to their new location, via [`incremental::Rebase`]) and the input skips ahead to the end of it.
+    ///
+    /// Outside of [`Parser::parse_incremental`], this parser behaves exactly like the original parser.
+    ///
+    /// Incrementality is most effective when applied to coarse, self-contained rules such as top-level items or
+    /// statements. See the [`incremental`] module for an example and a list of caveats.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    #[cfg(feature = "memoization")]
+    fn incremental(self) -> Incremental<Self>
+    where
+        Self: Sized,
+        O: Clone + incremental::Rebase + 'static,
+    {
+        Incremental {
+            id: memo::MemoId::fresh(),
+            parser: self,
+        }
     }
 
     /// Transform all outputs of this parser to a predetermined value.
@@ -882,6 +1141,8 @@ pub trait Parser<'src, I: Input<'src>, O, E: ParserExtra<'src, I> = extra::Defau
             parser: self,
             label,
             is_context: false,
+            #[cfg(feature = "grammar")]
+            rule_name: None,
         }
     }
 
@@ -1308,6 +1569,157 @@ pub trait Parser<'src, I: Input<'src>, O, E: ParserExtra<'src, I> = extra::Defau
         }
     }
 
+    /// Declare trivia (such as whitespace and comments) that token-level parsers within this parser skip automatically.
+    ///
+    /// While this parser runs, token-level parsers such as [`just`], [`one_of`], [`none_of`], [`any`], [`select!`],
+    /// [`text::int`], and the `ident` and `keyword` parsers of [`text::ascii`] and [`text::unicode`] skip any trivia
+    /// before the token they parse, and any trivia after the last token is skipped before this parser finishes. This
+    /// avoids the need to add [`Parser::padded_by`] to every token. Use [`Parser::no_trivia`] to build tokens such as
+    /// string literals out of individual characters.
+    ///
+    /// Trivia is disabled while the trivia parser itself runs, so it may freely use [`just`] and friends. A failure to
+    /// find trivia doesn't contribute to errors. Trivia is not skipped within nested inputs (see [`Parser::nested_in`]).
+    ///
+    /// Parsers that report spans or slices of the input, such as [`Parser::map_with`], [`Parser::to_span`], and
+    /// [`Parser::to_slice`], skip trivia before they begin, so their spans don't include any leading trivia.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let comment = just::<_, _, extra::Err<Simple<char>>>("//")
+    ///     .then(any().and_is(just('\n').not()).repeated());
+    /// let trivia = text::whitespace().at_least(1).or(comment.ignored()).repeated();
+    ///
+    /// let string = just('"')
+    ///     .ignore_then(none_of('"').repeated().to_slice())
+    ///     .then_ignore(just('"'))
+    ///     .no_trivia();
+    /// let call = text::ascii::ident()
+    ///     .then(string.separated_by(just(',')).collect::<Vec<_>>().delimited_by(just('('), just(')')))
+    ///     .with_trivia(trivia);
+    ///
+    /// assert_eq!(
+    ///     call.parse("print ( \" a\" , // a comment\n \"b \" ) ").into_result(),
+    ///     Ok(("print", vec![" a", "b "])),
+    /// );
+    /// ```
+    fn with_trivia<U, T>(self, trivia: T) -> WithTrivia<Self, T, U>
+    where
+        Self: Sized,
+        T: Parser<'src, I, U, E>,
+    {
+        WithTrivia {
+            parser: self,
+            trivia,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Parse a pattern without skipping the trivia declared with [`Parser::with_trivia`] between its tokens.
+    ///
+    /// Trivia before the pattern is still skipped, so the pattern as a whole acts as a single token. This is useful for
+    /// things like string literals and numbers, within which whitespace and comments are significant.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let float = text::int::<_, extra::Err<Simple<char>>>(10)
+    ///     .then(just('.').then(text::digits(10)).or_not())
+    ///     .to_slice()
+    ///     .no_trivia();
+    /// let floats = float.repeated().collect::<Vec<_>>().with_trivia(text::whitespace());
+    ///
+    /// assert_eq!(
+    ///     floats.parse(" 1.5 2 3.25 ").into_result(),
+    ///     Ok(vec!["1.5", "2", "3.25"]),
+    /// );
+    /// // Whitespace isn't allowed within a float
+    /// assert!(floats.parse("1 .5").has_errors());
+    /// ```
+    fn no_trivia(self) -> NoTrivia<Self>
+    where
+        Self: Sized,
+    {
+        NoTrivia { parser: self }
+    }
+
+    /// Wrap the input consumed by this parser into a node of a concrete syntax tree, with the given kind.
+    ///
+    /// The parser's state must be a [`cst::Builder`] (or be able to borrow one). See the [`cst`] module for more
+    /// information.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, cst::Builder};
+    /// let digits = text::digits::<_, extra::State<Builder<&str>>>(10).node("digits");
+    /// let number = digits.then(just('.').then(digits).or_not()).node("number");
+    ///
+    /// let mut builder = Builder::default();
+    /// number.parse_with_state("3.14", &mut builder).unwrap();
+    ///
+    /// let root = builder.finish("root", 4);
+    /// let number = root.child_nodes().next().unwrap();
+    /// assert_eq!(number.kind(), &"number");
+    /// assert_eq!(
+    ///     number.child_nodes().map(|digits| digits.span()).collect::<Vec<_>>(),
+    ///     [0..1, 2..4],
+    /// );
+    /// ```
+    fn node<K>(self, kind: K) -> Node<Self, K>
+    where
+        Self: Sized,
+        E::State: BorrowMut<cst::Builder<K>>,
+        K: Clone,
+    {
+        Node { parser: self, kind }
+    }
+
+    /// Give this parser a name and record its progress when parsing with [`Parser::parse_traced`].
+    ///
+    /// Each time the parser runs, the position at which it was entered, the position that it reached and whether it
+    /// succeeded are recorded in the [`debug::Trace`], along with any rewinds of the input that happen while it runs.
+    /// When profiling with a [`debug::Profile`] instead, these are summed up for each name. With the `tracing` feature enabled, each run is also reported to the `tracing` crate as a span.
+    ///
+    /// When not tracing, this parser behaves exactly like the parser it wraps. See the [`debug`] module for more
+    /// information.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, debug::Trace};
+    /// let word = text::ascii::ident::<_, extra::Err<Simple<char>>>().debug("word");
+    /// let words = word.padded().repeated().collect::<Vec<_>>().debug("words");
+    ///
+    /// let mut trace = Trace::default();
+    /// assert_eq!(words.parse_traced("hello world", &mut trace).into_result(), Ok(vec!["hello", "world"]));
+    /// // The first word stopped at the space after "hello", so backtracked to before it
+    /// assert_eq!(
+    ///     trace.to_string(),
+    ///     "words 0..11 ok\n\
+    ///      \x20 word 0..5 ok\n\
+    ///      \x20   rewind 6 -> 5\n\
+    ///      \x20 word 6..11 ok\n\
+    ///      \x20 word 11..11 failed\n",
+    /// );
+    /// ```
+    fn debug(self, name: &'static str) -> debug::Debug<Self>
+    where
+        Self: Sized,
+    {
+        debug::Debug { parser: self, name }
+    }
+
     /// Parse one thing or, on failure, another thing.
     ///
     /// The output of both parsers must be of the same type, because either output can be produced.
@@ -1376,6 +1788,52 @@ pub trait Parser<'src, I: Input<'src>, O, E: ParserExtra<'src, I> = extra::Defau
         OrNot { parser: self }
     }
 
+    /// Commit to the current parse path once this parser succeeds.
+    ///
+    /// After a cut has been passed, enclosing [`Parser::or`], [`choice`], [`Parser::or_not`], [`Parser::repeated`]
+    /// and [`Parser::separated_by`] combinators no longer backtrack when a later part of the same branch fails:
+    /// instead, the error is propagated directly. This both avoids needlessly trying alternatives that cannot match
+    /// and results in errors that point at the actual problem rather than at the start of the branch.
+    ///
+    /// A cut only applies to the branch it appears in. Once the enclosing alternative succeeds, backtracking behaves
+    /// as normal again. Lookahead combinators like [`Parser::not`] and [`Parser::and_is`] never commit.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// #[derive(Clone, Debug, PartialEq)]
+    /// enum Stmt<'src> {
+    ///     Let(&'src str, &'src str),
+    ///     Other(&'src str),
+    /// }
+    ///
+    /// let ident = text::ascii::ident::<_, extra::Err<Rich<char>>>().padded();
+    /// let stmt = text::ascii::keyword("let")
+    ///     .ignore_then(ident)
+    ///     // Once we've seen `let` and a name, this can only be a `let` statement
+    ///     .cut()
+    ///     .then_ignore(just('=').padded())
+    ///     .then(text::digits(10).to_slice())
+    ///     .map(|(name, val)| Stmt::Let(name, val))
+    ///     .or(any().repeated().to_slice().map(Stmt::Other));
+    ///
+    /// assert_eq!(stmt.parse("let x = 5").into_result(), Ok(Stmt::Let("x", "5")));
+    /// assert_eq!(stmt.parse("lettuce").into_result(), Ok(Stmt::Other("lettuce")));
+    /// // Without the cut, this would be silently accepted as `Stmt::Other`
+    /// let errs = stmt.parse("let x 5").into_errors();
+    /// assert_eq!(errs.len(), 1);
+    /// assert_eq!(errs[0].span().into_range(), 6..7);
+    /// ```
+    fn cut(self) -> Cut<Self>
+    where
+        Self: Sized,
+    {
+        Cut { parser: self }
+    }
+
     /// Invert the result of the contained parser, failing if it succeeds and succeeding if it fails.
     /// The output of this parser is always `()`, the unit type.
     ///
@@ -2174,30 +2632,190 @@ pub trait Parser<'src, I: Input<'src>, O, E: ParserExtra<'src, I> = extra::Defau
         }
     }
 
-    /// Simplify the type of the parser using Rust's `impl Trait` syntax.
+    /// Describe the grammar accepted by this parser, allowing it to be exported as EBNF or as railroad diagrams.
     ///
-    /// The only reason for using this function is to make Rust's compiler errors easier to debug: it does not change
-    /// the behaviour of the parser at all, and is in fact just a simple identity function.
-    #[cfg(feature = "nightly")]
-    fn simplify(self) -> impl Parser<'src, I, O, E>
+    /// Recursive parsers, and labelled parsers marked with [`Labelled::as_rule`], become named rules of the grammar.
+    /// See the [`grammar`] module for more information.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let digits = one_of::<_, _, extra::Err<Simple<char>>>("01").repeated().at_least(1);
+    /// let list = digits
+    ///     .labelled("digits")
+    ///     .as_rule()
+    ///     .separated_by(just(','))
+    ///     .allow_trailing()
+    ///     .collect::<Vec<_>>()
+    ///     .delimited_by(just('['), just(']'))
+    ///     .labelled("list")
+    ///     .as_rule();
+    /// assert_eq!(list.parse("[01,1,]").into_result(), Ok(vec![(), ()]));
+    ///
+    /// assert_eq!(
+    ///     list.grammar().to_ebnf(),
+    ///     "list = \"[\", [ digits, { \",\", digits } ], [ \",\" ], \"]\" ;\n\
+    ///      digits = ( '0' | '1' ), { '0' | '1' } ;\n",
+    /// );
+    /// ```
+    #[cfg(feature = "grammar")]
+    fn grammar(&self) -> grammar::Grammar
     where
-        Self: Sized + 'src,
+        Self: Sized,
+        I::Token: fmt::Debug,
     {
-        self
+        let mut scope = grammar::NodeScope::default();
+        let expr = self.node_info(&mut scope);
+        scope.into_grammar(expr)
     }
 
-    /// Use [Pratt parsing](https://en.wikipedia.org/wiki/Operator-precedence_parser#Pratt_parsing) to ergonomically
-    /// parse this pattern separated by prefix, postfix, and infix operators of various associativites and precedence.
-    ///
-    /// Pratt parsing is a powerful technique and is recommended when writing parsers for expressions.
+    /// Check the grammar of this parser for mistakes that would otherwise only be found at parse time, if at all.
     ///
-    /// # Example
+    /// This reports repetitions of parsers that can succeed without consuming input (which never end), left recursion
+    /// that isn't guarded by [`Parser::memoized`] (which overflows the stack), alternatives of a choice that are never
+    /// reached because an earlier alternative matches first, and [`Recursive`] parsers that were never defined. See
+    /// the [`lint`] module for more information.
     ///
-    /// See the documentation in [`pratt`] for more extensive examples and details.
+    /// # Examples
     ///
     /// ```
-    /// # use chumsky::prelude::*;
-    /// use chumsky::pratt::*;
+    /// # use chumsky::{prelude::*, lint::Lint};
+    /// let expr = recursive(|expr| {
+    ///     expr.then_ignore(just('-'))
+    ///         .then(text::int::<&str, extra::Err<Simple<char>>>(10))
+    ///         .ignored()
+    ///         .or(text::int(10).ignored())
+    /// })
+    /// .labelled("expr")
+    /// .as_rule();
+    /// assert_eq!(
+    ///     expr.lint(),
+    ///     vec![Lint::LeftRecursion { cycle: vec!["expr".to_string()] }],
+    /// );
+    /// assert_eq!(expr.lint()[0].to_string(), "`expr` is left-recursive (expr -> expr)");
+    ///
+    /// // Memoized parsers support left recursion
+    /// # #[cfg(feature = "memoization")]
+    /// # {
+    /// let expr = recursive(|expr| {
+    ///     expr.then_ignore(just('-'))
+    ///         .then(text::int::<&str, extra::Err<Simple<char>>>(10))
+    ///         .ignored()
+    ///         .or(text::int(10).ignored())
+    ///         .memoized()
+    /// });
+    /// assert!(expr.lint().is_empty());
+    /// # }
+    /// ```
+    #[cfg(feature = "grammar")]
+    fn lint(&self) -> Vec<lint::Lint>
+    where
+        Self: Sized,
+        I::Token: fmt::Debug,
+    {
+        self.grammar().lint()
+    }
+
+    /// Generate a random input that this parser accepts, as a sequence of tokens.
+    ///
+    /// The input follows the structure of the parser, with randomness and limits on its size provided by `generator`.
+    /// Checks on the outputs of parsers (such as [`Parser::filter`]) and lookahead are not taken into account, so some
+    /// inputs may still be rejected. See the [`generate`] module for more information.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, generate::Generator};
+    /// let list = text::int::<&str, extra::Err<Simple<char>>>(10)
+    ///     .separated_by(just(',').padded())
+    ///     .collect::<Vec<_>>()
+    ///     .delimited_by(just('['), just(']'));
+    ///
+    /// let mut generator = Generator::new(0);
+    /// let inputs = (0..10)
+    ///     .map(|_| list.generate(&mut generator).unwrap().into_iter().collect::<String>())
+    ///     .collect::<Vec<_>>();
+    /// for input in &inputs {
+    ///     assert!(input.starts_with('[') && input.ends_with(']'));
+    ///     assert!(list.parse(input.as_str()).into_result().is_ok());
+    /// }
+    /// ```
+    #[cfg(feature = "generate")]
+    fn generate(
+        &self,
+        generator: &mut generate::Generator,
+    ) -> Result<Vec<I::Token>, generate::GenerateError>
+    where
+        Self: Sized,
+        I::Token: Clone,
+    {
+        let mut scope = generate::Scope::new(generator);
+        self.gen_tokens(&mut scope)?;
+        Ok(scope.into_tokens())
+    }
+
+    /// Tell [`Parser::generate`] how to generate inputs for this parser, replacing the way it would otherwise generate
+    /// them.
+    ///
+    /// This is useful for parsers that can't be generated automatically, like [`any`] or [`select!`], and for
+    /// parsers whose outputs are checked (such as with [`Parser::filter`]).
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, generate::Generator};
+    /// let vowel = any::<&str, extra::Err<Simple<char>>>()
+    ///     .filter(|c: &char| "aeiou".contains(*c))
+    ///     .generate_with(|scope| {
+    ///         let i = scope.generator().below(5);
+    ///         scope.push("aeiou".as_bytes()[i] as char);
+    ///         Ok(())
+    ///     });
+    ///
+    /// let vowels = vowel.repeated().at_least(1).collect::<String>();
+    /// let input = vowels.generate(&mut Generator::new(7)).unwrap();
+    /// assert!(!input.is_empty() && input.iter().all(|c| "aeiou".contains(*c)));
+    /// ```
+    #[cfg(feature = "generate")]
+    fn generate_with<F>(self, f: F) -> generate::GenerateWith<Self, F>
+    where
+        Self: Sized,
+        F: Fn(&mut generate::Scope<'_, I::Token>) -> Result<(), generate::GenerateError>,
+    {
+        generate::GenerateWith {
+            parser: self,
+            generate: f,
+        }
+    }
+
+    /// Simplify the type of the parser using Rust's `impl Trait` syntax.
+    ///
+    /// The only reason for using this function is to make Rust's compiler errors easier to debug: it does not change
+    /// the behaviour of the parser at all, and is in fact just a simple identity function.
+    #[cfg(feature = "nightly")]
+    fn simplify(self) -> impl Parser<'src, I, O, E>
+    where
+        Self: Sized + 'src,
+    {
+        self
+    }
+
+    /// Use [Pratt parsing](https://en.wikipedia.org/wiki/Operator-precedence_parser#Pratt_parsing) to ergonomically
+    /// parse this pattern separated by prefix, postfix, infix, and ternary operators of various associativites and
+    /// precedence.
+    ///
+    /// Pratt parsing is a powerful technique and is recommended when writing parsers for expressions.
+    ///
+    /// # Example
+    ///
+    /// See the documentation in [`pratt`] for more extensive examples and details.
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// use chumsky::pratt::*;
     ///
     /// let int = text::int::<_, extra::Err<Rich<char>>>(10)
     ///     .from_str()
@@ -2409,6 +3027,27 @@ where
         state: &mut Self::IterState<M>,
     ) -> IPResult<M, O>;
 
+    #[cfg(feature = "grammar")]
+    #[doc(hidden)]
+    fn node_info(&self, _scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::Unknown
+    }
+
+    #[cfg(feature = "generate")]
+    #[doc(hidden)]
+    fn gen_tokens(
+        &self,
+        _scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        Err(generate::unsupported::<Self>())
+    }
+
     /// Collect this iterable parser into a [`Container`].
     ///
     /// This is commonly useful for collecting parsers that output many values into containers of various kinds:
@@ -2751,6 +3390,36 @@ where
         self
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.inner.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.inner.gen_tokens(scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.inner.prefixes()
+    }
+
+    fn skip_many(&self, inp: &mut InputRef<'src, '_, I, E>) -> usize {
+        self.inner.skip_many(inp)
+    }
+
     go_extra!(O);
 }
 
@@ -2768,6 +3437,36 @@ where
         T::go::<M>(self, inp)
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        T::node_info(self, scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        T::gen_tokens(self, scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        T::prefixes(self)
+    }
+
+    fn skip_many(&self, inp: &mut InputRef<'src, '_, I, E>) -> usize {
+        T::skip_many(self, inp)
+    }
+
     go_extra!(O);
 }
 
@@ -2785,6 +3484,36 @@ where
         T::go::<M>(self, inp)
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        T::node_info(self, scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        T::gen_tokens(self, scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        T::prefixes(self)
+    }
+
+    fn skip_many(&self, inp: &mut InputRef<'src, '_, I, E>) -> usize {
+        T::skip_many(self, inp)
+    }
+
     go_extra!(O);
 }
 
@@ -2802,6 +3531,36 @@ where
         T::go::<M>(self, inp)
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        T::node_info(self, scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        T::gen_tokens(self, scope)
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        T::prefixes(self)
+    }
+
+    fn skip_many(&self, inp: &mut InputRef<'src, '_, I, E>) -> usize {
+        T::skip_many(self, inp)
+    }
+
     go_extra!(O);
 }
 
@@ -3175,6 +3934,709 @@ mod tests {
         assert_eq!(&chars, "abcdefg");
     }
 
+    #[test]
+    #[cfg(feature = "std")]
+    fn read_input_commit() {
+        use crate::input::{InputRef, ReadInput};
+        use std::{cell::Cell, io::Read};
+
+        // A reader that can't seek and that produces its input a few bytes at a time
+        struct Trickle(Vec<u8>, usize);
+        impl Read for Trickle {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                let n = buf.len().min(3).min(self.0.len() - self.1);
+                buf[..n].copy_from_slice(&self.0[self.1..self.1 + n]);
+                self.1 += n;
+                Ok(n)
+            }
+        }
+
+        let src = "foo:1\nbar:22\n".repeat(10_000).into_bytes();
+
+        let field = |c: u8| {
+            any::<_, extra::Default>()
+                .filter(move |b: &u8| *b != c)
+                .repeated()
+                .at_least(1)
+                .collect::<Vec<_>>()
+        };
+        let max_buffered = Cell::new(0);
+        let record = field(b':')
+            .then_ignore(just(b':'))
+            .then(field(b'\n'))
+            .then_ignore(just(b'\n'))
+            .then_ignore(ReadInput::commit())
+            .then_ignore(custom(|inp| {
+                let inp: &mut InputRef<ReadInput<Trickle>, extra::Default> = inp;
+                max_buffered.set(max_buffered.get().max(inp.cache.filled));
+                Ok(())
+            }));
+        let records = record.repeated().count();
+
+        assert_eq!(
+            records.parse(ReadInput::new(Trickle(src, 0))).into_result(),
+            Ok(20_000)
+        );
+        // The buffer never holds much more than a single record
+        assert!(max_buffered.get() < 16);
+
+        // Backtracking across a commit point is forbidden
+        let bad = just::<_, _, extra::Default>(b'f')
+            .then(ReadInput::commit())
+            .then(just(b'x'))
+            .ignored()
+            .or(just(b'f').then(just(b'o')).ignored());
+        let res = std::panic::catch_unwind(|| bad.parse(ReadInput::new(&b"fo"[..])).has_errors());
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn source_set() {
+        use crate::source::{SourceSet, SourceSpan};
+
+        let mut sources = SourceSet::new();
+        let lib = sources.add("lib.foo", "let x = 1;");
+        let main = sources.add("main.foo", "let y = 2;\nlet\t z 3;");
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources.find("main.foo").map(|s| s.id()), Some(main));
+
+        let stmt = text::ascii::keyword("let")
+            .padded()
+            .ignore_then(text::ascii::ident().padded())
+            .then_ignore(just::<_, _, extra::Err<Rich<char, SourceSpan>>>('=').padded())
+            .then(text::int(10).padded())
+            .then_ignore(just(';'))
+            .labelled("statement")
+            .as_context();
+        let stmts = stmt
+            .padded()
+            .repeated()
+            .collect::<Vec<_>>()
+            .then_ignore(end())
+            .map_with(|stmts, e| (stmts, e.span()));
+
+        let parse = |input| stmts.parse(input).into_output_errors();
+
+        let (out, errs) = parse(sources[lib].input());
+        assert!(errs.is_empty());
+        let (_, span) = out.unwrap();
+        assert_eq!(span, (lib, SimpleSpan::from(0..10)));
+
+        let (_, errs) = parse(sources[main].input());
+        assert_eq!(errs.len(), 1);
+        let err = sources.locate(errs[0].span()).unwrap();
+        assert_eq!(err.source.name(), "main.foo");
+        assert_eq!(err.text(), "3");
+        assert_eq!(err.to_string(), "main.foo:2:8");
+
+        let (_, ctx_span) = errs[0].contexts().next().unwrap();
+        let ctx = sources.locate(ctx_span).unwrap();
+        assert_eq!(ctx.to_string(), "main.foo:2:1");
+    }
+
+    #[test]
+    fn splice() {
+        use crate::source::{Segment, SourceSet, Splice, SpliceSpan};
+
+        let mut sources = SourceSet::new();
+        let main = sources.add("main", "x = @inc;\ny = @mac;");
+        let inc = sources.add("inc", "1 + @mac");
+        let mac = sources.add("mac", "(2 * ?)");
+
+        // Expands to `x = 1 + (2 * ?);\ny = (2 * ?);`
+        let splice = Splice::new([
+            Segment::new(&sources[main], 0..4),
+            Segment::new(&sources[inc], 0..4).included_from(0, 4..8),
+            Segment::whole(&sources[mac]).included_from(1, 4..8),
+            Segment::new(&sources[main], 8..14),
+            Segment::whole(&sources[mac]).included_from(3, 14..18),
+            Segment::new(&sources[main], 18..19),
+        ]);
+
+        let stmt = text::ascii::ident::<_, extra::Err<Rich<char, SpliceSpan>>>()
+            .then_ignore(just('=').padded())
+            .then(
+                none_of(";")
+                    .repeated()
+                    .to_slice()
+                    .map(|s: alloc::borrow::Cow<str>| s.into_owned()),
+            )
+            .then_ignore(just(';'))
+            .map_with(|stmt, e| (stmt, e.span()))
+            .padded();
+
+        let out = stmt
+            .repeated()
+            .collect::<Vec<_>>()
+            .parse(splice.input())
+            .into_result()
+            .unwrap();
+        assert_eq!(out[0].0 .1, "1 + (2 * ?)");
+        // A span that covers an inclusion extends over the inclusion site in the original source
+        assert_eq!(sources.locate(&out[0].1).unwrap().text(), "x = @inc;");
+        assert_eq!(sources.locate(&out[1].1).unwrap().text(), "y = @mac;");
+
+        // Errors within a nested inclusion carry the whole chain of inclusion sites
+        let errs = none_of::<_, _, extra::Err<Rich<char, SpliceSpan>>>('?')
+            .repeated()
+            .parse(splice.input())
+            .into_errors();
+        let err = splice.with_include_contexts(errs[0].clone(), errs[0].span());
+        assert_eq!(err.span().0.source, mac);
+        let chain = err
+            .contexts()
+            .map(|(label, site)| (label.to_string(), sources.locate(site).unwrap().to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            chain,
+            vec![
+                ("included from inc:1:5".to_string(), "inc:1:5".to_string()),
+                ("included from main:1:5".to_string(), "main:1:5".to_string()),
+            ]
+        );
+
+        // A chain that passes through the same source twice keeps every site
+        let lib = sources.add("lib", "ab @lib cd @bad");
+        let bad = sources.add("bad", "?");
+        let splice = Splice::new([
+            Segment::new(&sources[main], 0..4),
+            Segment::new(&sources[lib], 0..3).included_from(0, 4..8),
+            Segment::new(&sources[lib], 8..11).included_from(1, 3..7),
+            Segment::whole(&sources[bad]).included_from(2, 11..15),
+        ]);
+        let errs = none_of::<_, _, extra::Err<Rich<char, SpliceSpan>>>('?')
+            .repeated()
+            .parse(splice.input())
+            .into_errors();
+        let err = splice.with_include_contexts(errs[0].clone(), errs[0].span());
+        assert_eq!(err.span().0.source, bad);
+        assert_eq!(
+            err.contexts()
+                .map(|(label, _)| label.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "included from lib:1:12",
+                "included from lib:1:4",
+                "included from main:1:5"
+            ]
+        );
+
+        // Other error types can be given contexts too
+        let errs = none_of::<_, _, extra::Err<Simple<char, SpliceSpan>>>('?')
+            .repeated()
+            .parse(splice.input())
+            .into_errors();
+        let err = splice.with_include_contexts(errs[0], errs[0].span());
+        assert_eq!(err, errs[0]);
+    }
+
+    #[test]
+    fn cut() {
+        type E<'src> = extra::Err<Rich<'src, char>>;
+
+        let item = || {
+            let ident = text::ascii::ident::<_, E>().padded();
+            let func = text::ascii::keyword("fn")
+                .ignore_then(ident)
+                .cut()
+                .then_ignore(just("()").padded())
+                .map(|name| format!("fn {name}"));
+            let call = ident
+                .then_ignore(just("();").padded())
+                .map(|name| format!("call {name}"));
+            func.or(call)
+        };
+
+        // The error is reported after the commit point rather than from the other alternative
+        let errs = item().parse("fn foo;").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span().into_range(), 6..7);
+        assert_eq!(errs[0].found(), Some(&';'));
+        assert!(item().parse("fn foo()").into_result().is_ok());
+        assert_eq!(
+            item().parse("fun();").into_result(),
+            Ok("call fun".to_string())
+        );
+
+        // `repeated` and `or_not` propagate the failure rather than stopping early
+        let items = item().repeated().collect::<Vec<_>>();
+        let errs = items.parse("foo(); fn bar;").into_errors();
+        assert_eq!(errs[0].span().into_range(), 13..14);
+        let errs = item().or_not().parse("fn bar;").into_errors();
+        assert_eq!(errs[0].span().into_range(), 6..7);
+
+        // Once the committed branch succeeds, enclosing alternatives backtrack as normal again
+        let stmt = item()
+            .then_ignore(just('!'))
+            .or(item().then_ignore(just('?')));
+        assert_eq!(
+            stmt.parse("fn foo()?").into_result(),
+            Ok("fn foo".to_string())
+        );
+
+        // A cut inside lookahead does not commit the enclosing parser
+        let word = text::ascii::ident::<_, E>();
+        let guarded = just("fn")
+            .cut()
+            .then(just('!'))
+            .not()
+            .ignore_then(word)
+            .or(just("fn!").to_slice());
+        assert_eq!(guarded.parse("fn!").into_result(), Ok("fn!"));
+    }
+
+    #[test]
+    fn permutation_any_order() {
+        use crate::error::RichPattern;
+
+        let attr = |name| {
+            just::<_, _, extra::Err<Rich<char>>>(name)
+                .ignore_then(just('='))
+                .ignore_then(text::int(10))
+                .padded()
+                .labelled(name)
+        };
+        let attrs = permutation((attr("x"), attr("y"), attr("z")));
+
+        assert_eq!(
+            attrs.parse("y=2 z=3 x=1").into_result(),
+            Ok(("1", "2", "3"))
+        );
+
+        // Missing items are named in the error
+        let errs = attrs.parse("x=1").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span().into_range(), 3..3);
+        let expected = errs[0].expected().cloned().collect::<Vec<_>>();
+        assert!(expected.contains(&RichPattern::Label("y".into())));
+        assert!(expected.contains(&RichPattern::Label("z".into())));
+        assert!(!expected.contains(&RichPattern::Label("x".into())));
+
+        // Duplicated items are reported at the repetition, whether or not other items are missing
+        let errs = attrs.parse("x=1 x=2").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span().into_range(), 4..7);
+        assert_eq!(errs[0].found(), Some(&'x'));
+        assert_eq!(
+            errs[0].expected().cloned().collect::<Vec<_>>(),
+            vec![RichPattern::Label(
+                format!("no repeat of item {}", 0).into()
+            )]
+        );
+        let errs = attrs.parse("y=1 x=2 y=3").into_errors();
+        assert_eq!(errs[0].span().into_range(), 8..11);
+        assert_eq!(
+            errs[0].expected().cloned().collect::<Vec<_>>(),
+            vec![RichPattern::Label(
+                format!("no repeat of item {}", 1).into()
+            )]
+        );
+
+        // Items that begin with known tokens are named by them
+        let errs = permutation((just::<_, _, extra::Err<Rich<char>>>("ab"), just('c')))
+            .parse("abab")
+            .into_errors();
+        assert_eq!(errs[0].span().into_range(), 2..4);
+        assert_eq!(
+            errs[0].expected().cloned().collect::<Vec<_>>(),
+            vec![RichPattern::Label("no repeat of \"ab\"".into())]
+        );
+        let errs = permutation((just::<_, _, extra::Err<Rich<u8>>>([1u8, 2]), just([3u8])))
+            .parse(&[1, 2, 1, 2])
+            .into_errors();
+        assert_eq!(
+            errs[0].expected().cloned().collect::<Vec<_>>(),
+            vec![RichPattern::Label("no repeat of 1 2".into())]
+        );
+
+        // A complete permutation doesn't look for repeats in the input that follows it
+        let then_a =
+            permutation((just::<_, _, extra::Err<Rich<char>>>('a'), just('b'))).then(just('a'));
+        assert_eq!(then_a.parse("aba").into_result(), Ok((('a', 'b'), 'a')));
+        assert_eq!(then_a.parse("baa").into_result(), Ok((('a', 'b'), 'a')));
+
+        let opt = permutation((attr("x"), attr("y"), attr("z"))).optional();
+        assert_eq!(opt.parse("z=3").into_result(), Ok((None, None, Some("3"))));
+        assert!(opt.parse("z=3 z=3").has_errors());
+
+        // A cut within an item propagates rather than trying the other items
+        let cut_attr = |name| {
+            just::<_, _, extra::Err<Rich<char>>>(name)
+                .cut()
+                .ignore_then(just('='))
+                .padded()
+        };
+        let errs = permutation((cut_attr("a"), cut_attr("ab")))
+            .parse("ab=")
+            .into_errors();
+        assert_eq!(errs[0].span().into_range(), 1..2);
+    }
+
+    #[test]
+    fn trivia() {
+        let comment = just::<_, _, extra::Err<Rich<char>>>("//")
+            .then(any().and_is(just('\n').not()).repeated())
+            .ignored();
+        let trivia = text::whitespace().at_least(1).or(comment).repeated();
+
+        let string = just('"')
+            .ignore_then(none_of('"').repeated().to_slice())
+            .then_ignore(just('"'))
+            .no_trivia();
+        let stmt = text::ascii::keyword("let")
+            .ignore_then(text::ascii::ident())
+            .then_ignore(just('='))
+            .then(text::int(10).or(string))
+            .then_ignore(just(';'));
+        let stmts = stmt
+            .clone()
+            .repeated()
+            .collect::<Vec<_>>()
+            .with_trivia(trivia);
+
+        assert_eq!(
+            stmts
+                .parse("  let x = 1 ; // one\n let  y=\" a // b \";\n")
+                .into_result(),
+            Ok(vec![("x", "1"), ("y", " a // b ")]),
+        );
+
+        // Tokens still need to be separated where they would otherwise merge
+        assert!(stmts.parse("letx=1;").has_errors());
+
+        // Trivia doesn't show up in errors
+        let errs = stmts.parse("let x = ;").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span().into_range(), 8..9);
+        assert_eq!(errs[0].found(), Some(&';'));
+        assert!(errs[0]
+            .expected()
+            .all(|e| !matches!(e, crate::error::RichPattern::Token(t) if t.is_whitespace())));
+
+        // Without trivia, tokens are parsed as normal
+        assert!(stmt.parse("let x = 1;").has_errors());
+
+        // Trivia keeps being skipped after switching context or state
+        let nested = just::<_, _, extra::Err<Rich<char>>>('a')
+            .ignore_then(just('b').with_ctx(()))
+            .ignore_then(just('c').with_state(()))
+            .with_trivia(text::whitespace());
+        assert_eq!(nested.parse(" a b c ").into_result(), Ok('c'));
+
+        // Character-level parsers skip trivia too, and spans and slices start after it
+        let spans = any::<_, extra::Err<Rich<char>>>()
+            .to_span()
+            .then(none_of('x').map_with(|_, e| e.span()))
+            .then(just("ab").to_slice())
+            .with_trivia(text::whitespace().at_least(1));
+        assert_eq!(
+            spans.parse(" a  b  ab ").into_result(),
+            Ok((((1..2).into(), (4..5).into()), "ab"))
+        );
+    }
+
+    #[test]
+    fn choice_dispatch() {
+        fn alts<'src>() -> Vec<Boxed<'src, 'src, &'src str, String, extra::Err<Rich<'src, char>>>> {
+            vec![
+                text::ascii::keyword("in").map(String::from).boxed(),
+                text::ascii::keyword("int").map(String::from).boxed(),
+                text::ascii::keyword("into").map(String::from).boxed(),
+                just("<").to("lt".to_string()).boxed(),
+                just("<=").to("le".to_string()).boxed(),
+                one_of("+-").map(String::from).boxed(),
+                just("->").to("arrow".to_string()).boxed(),
+                text::int(10).map(|s: &str| format!("#{s}")).boxed(),
+                text::ascii::ident().map(|s: &str| format!("${s}")).boxed(),
+            ]
+        }
+        let dispatch = crate::primitive::choice_dispatch(alts()).padded();
+        let linear = choice(alts()).padded();
+
+        for src in [
+            "in", "int", "into", "intone", "i", "<", "<=", "+", "-", "->", "42", "x", " into ", "",
+            "?", "<>",
+        ] {
+            let (fast, slow) = (dispatch.parse(src), linear.parse(src));
+            assert_eq!(fast.output(), slow.output(), "{src:?}");
+            assert_eq!(
+                fast.errors()
+                    .map(|e| (e.span(), e.found()))
+                    .collect::<Vec<_>>(),
+                slow.errors()
+                    .map(|e| (e.span(), e.found()))
+                    .collect::<Vec<_>>(),
+                "{src:?}",
+            );
+        }
+
+        // Skipped alternatives don't contribute to errors
+        let errs = dispatch.parse("?").into_errors();
+        assert!(errs[0]
+            .expected()
+            .all(|e| !matches!(e, crate::error::RichPattern::Token(t) if **t == '<')));
+
+        // Alternatives are tried in order, so an earlier, shorter match still wins
+        assert_eq!(dispatch.parse("<").into_result(), Ok("lt".to_string()));
+        assert!(dispatch.parse("<=").has_errors());
+
+        // Keywords are indexed by their text, so only the keywords that could match are tried
+        let keywords = ["select", "from", "where", "insert", "into", "values"];
+        let alts =
+            || keywords.map(|kw| text::ascii::keyword::<_, _, extra::Err<Rich<char>>>(kw).boxed());
+        let dispatch = crate::primitive::choice_dispatch(alts());
+        let linear = choice(alts());
+        assert_eq!(dispatch.parse("where").into_result(), Ok("where"));
+        let expected = |res: ParseResult<&str, Rich<char>>| {
+            res.into_errors()[0]
+                .expected()
+                .map(|e| e.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(expected(dispatch.parse("wherever")), vec!["where"]);
+        assert_eq!(expected(linear.parse("wherever")).len(), keywords.len());
+
+        // Leading tokens are looked up after trivia
+        let stmt = crate::primitive::choice_dispatch([
+            text::ascii::keyword::<_, _, extra::Err<Rich<char>>>("let").boxed(),
+            text::ascii::keyword("fn").boxed(),
+        ])
+        .then(text::ascii::ident())
+        .with_trivia(text::whitespace());
+        assert_eq!(stmt.parse("  fn  foo ").into_result(), Ok(("fn", "foo")));
+
+        // When no alternative can match, the unexpected token is reported
+        let errs = stmt.parse(" if x").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span().into_range(), 1..2);
+        assert_eq!(errs[0].found(), Some(&'i'));
+
+        // A cut in a matching alternative stops other alternatives being tried
+        let cut = crate::primitive::choice_dispatch([
+            just::<_, _, extra::Err<Rich<char>>>("a")
+                .cut()
+                .then(just("b"))
+                .to(1)
+                .boxed(),
+            just("ac").to(2).boxed(),
+        ]);
+        assert!(cut.parse("ac").has_errors());
+        assert_eq!(cut.parse("ab").into_result(), Ok(1));
+    }
+
+    #[test]
+    fn take_while_until() {
+        use crate::inspector::Inspector;
+
+        // An inspector that needs to see every token, which disables bulk skipping
+        #[derive(Default)]
+        struct Count(usize);
+        impl<'src, I: Input<'src>> Inspector<'src, I> for Count {
+            type Checkpoint = usize;
+            fn on_token(&mut self, _: &I::Token) {
+                self.0 += 1;
+            }
+            fn on_save<'parse>(&self, _: &crate::input::Cursor<'src, 'parse, I>) -> usize {
+                self.0
+            }
+            fn on_rewind<'parse>(
+                &mut self,
+                marker: &crate::input::Checkpoint<'src, 'parse, I, usize>,
+            ) {
+                self.0 = *marker.inspector();
+            }
+        }
+
+        fn body<'src, E: extra::ParserExtra<'src, &'src str>>(
+        ) -> impl Parser<'src, &'src str, &'src str, E> {
+            take_until(just("*/"))
+        }
+
+        // Place the terminator at every offset relative to the chunks that the input is scanned in
+        for pad in 0..20 {
+            for fill in ["x", "é", "→ab"] {
+                let text = fill.repeat(pad);
+                let src = format!("{text}*/rest");
+                let fast = body::<extra::Default>().lazy().parse(&src);
+                assert_eq!(fast.into_result(), Ok(text.as_str()));
+                let mut count = Count::default();
+                let slow = body::<extra::State<Count>>()
+                    .lazy()
+                    .parse_with_state(&src, &mut count);
+                assert_eq!(slow.into_result(), Ok(text.as_str()));
+                assert_eq!(count.0, src.chars().count());
+            }
+        }
+
+        // Tokens that begin the stop parser without it matching are taken
+        assert_eq!(
+            body::<extra::Default>()
+                .lazy()
+                .parse("a * b / c */")
+                .into_result(),
+            Ok("a * b / c ")
+        );
+        // Without a stop, everything is taken
+        assert_eq!(
+            body::<extra::Default>().parse("no end").into_result(),
+            Ok("no end")
+        );
+        // Stop parsers with many (or non-ASCII, or unknown) leading tokens work too
+        for (stop, expected) in [
+            (one_of("0123456789").ignored().boxed(), "abc→def "),
+            (just('→').ignored().boxed(), "abc"),
+            (
+                any().filter(char::is_ascii_digit).ignored().boxed(),
+                "abc→def ",
+            ),
+        ] {
+            let p = take_until::<_, _, extra::Default, _>(stop).lazy();
+            assert_eq!(p.parse("abc→def 42").into_result(), Ok(expected));
+        }
+        // Token slices are supported
+        assert_eq!(
+            take_until::<_, _, extra::Default, _>(just(0u8))
+                .lazy()
+                .parse(&[1u8, 2, 3, 0, 4][..])
+                .into_result(),
+            Ok(&[1u8, 2, 3][..]),
+        );
+
+        let ident =
+            take_while::<_, _, extra::Err<Rich<char>>>(|c: &char| c.is_alphanumeric()).at_least(1);
+        assert_eq!(ident.lazy().parse("héllo wörld").into_result(), Ok("héllo"));
+        let errs = ident.parse("+").into_errors();
+        assert_eq!(errs[0].span().into_range(), 0..1);
+        assert_eq!(errs[0].found(), Some(&'+'));
+
+        let field = take_till1::<_, _, extra::Err<Rich<char>>>(|c: &char| *c == ';');
+        assert_eq!(field.lazy().parse("ab;c").into_result(), Ok("ab"));
+        assert!(field.parse(";").has_errors());
+
+        // Sets of tokens are scanned for in bulk, taking the same input as when each token is checked
+        for src in ["ab,c\nd", "→é,x", "abc", ",", ""] {
+            for set in [",\n", ",→", ","] {
+                let fast = take_till1::<_, _, extra::Default>(set).lazy().parse(src);
+                let mut count = Count::default();
+                let slow = take_till1::<_, _, extra::State<Count>>(set)
+                    .lazy()
+                    .parse_with_state(src, &mut count);
+                let pred = take_till1::<_, _, extra::Default>(|c: &char| set.contains(*c))
+                    .lazy()
+                    .parse(src);
+                assert_eq!(fast.output(), slow.output(), "{src:?} {set:?}");
+                assert_eq!(fast.output(), pred.output(), "{src:?} {set:?}");
+            }
+        }
+        let digits =
+            take_while::<_, _, extra::Err<Rich<char>>>(crate::class::char_class("0-9")).at_least(2);
+        assert_eq!(
+            digits.clone().lazy().parse("123abc").into_result(),
+            Ok("123")
+        );
+        assert!(digits.parse("1").has_errors());
+        assert_eq!(
+            take_while::<_, _, extra::Default>([b' ', b'\t'])
+                .lazy()
+                .parse(&b" \t x"[..])
+                .into_result(),
+            Ok(&b" \t "[..]),
+        );
+
+        // Literals can be used as the stop
+        assert_eq!(
+            take_until_literal::<_, extra::Default, _>("*/")
+                .lazy()
+                .parse("a * b */")
+                .into_result(),
+            Ok("a * b "),
+        );
+
+        // Trivia is skipped before the input taken, but not within it
+        let words = take_while::<_, _, extra::Default>(|c: &char| c.is_alphabetic())
+            .then(take_until_literal(";"))
+            .then_ignore(just(';'))
+            .with_trivia(text::whitespace());
+        assert_eq!(words.parse("  ab  c d ;").into_result(), Ok(("ab", "c d ")));
+
+        // Whitespace skipped in bulk consumes just as much as it would otherwise
+        assert_eq!(
+            text::whitespace::<_, extra::Default>()
+                .to_slice()
+                .lazy()
+                .parse(" \t\n x")
+                .into_result(),
+            Ok(" \t\n "),
+        );
+        for src in [" \t\n x", "\u{a0} \u{2003}\tx", "   \r\n", ""] {
+            let mut count = Count::default();
+            let slow = text::whitespace::<_, extra::State<Count>>()
+                .to_slice()
+                .lazy()
+                .parse_with_state(src, &mut count);
+            let fast = text::whitespace::<_, extra::Default>()
+                .to_slice()
+                .lazy()
+                .parse(src);
+            assert_eq!(fast.into_result(), slow.into_result());
+            let mut count = Count::default();
+            let slow = text::inline_whitespace::<_, extra::State<Count>>()
+                .to_slice()
+                .lazy()
+                .parse_with_state(src, &mut count);
+            let fast = text::inline_whitespace::<_, extra::Default>()
+                .to_slice()
+                .lazy()
+                .parse(src);
+            assert_eq!(fast.into_result(), slow.into_result());
+        }
+    }
+
+    #[test]
+    fn bit_input() {
+        use crate::input::{BitInput, BitOrder};
+
+        type E = extra::Err<Simple<'static, bool>>;
+
+        // A 5-bit count followed by a byte-aligned payload of that many bytes
+        let packet = BitInput::bits::<E>(5)
+            .then(BitInput::bits(3))
+            .then(BitInput::aligned_bytes(2))
+            .then(BitInput::rest_bytes());
+
+        // Count `00010`, followed by `101`
+        let bytes = &[0b0001_0101, 0xAB, 0xCD, 0xEF];
+        assert_eq!(
+            packet.parse(BitInput::new(bytes)).into_result(),
+            Ok((((2, 5), &[0xAB, 0xCD][..]), &[0xEF][..])),
+        );
+        // Reading the same bytes least significant bit first
+        assert_eq!(
+            packet
+                .parse(BitInput::new(bytes).with_order(BitOrder::LsbFirst))
+                .into_result(),
+            Ok((((0b10101, 0b000), &[0xAB, 0xCD][..]), &[0xEF][..])),
+        );
+
+        // Spans are measured in bits
+        let field = BitInput::bits::<E>(3).map_with(|n, e| (n, e.span()));
+        assert_eq!(
+            field
+                .then(BitInput::align().ignore_then(field))
+                .then_ignore(BitInput::align())
+                .parse(BitInput::new(&[0xFF, 0x00]))
+                .into_result(),
+            Ok(((7, (0..3).into()), (0, (8..11).into()))),
+        );
+
+        // Running out of input is an error at the end of the input
+        let errs = BitInput::aligned_bytes::<E>(3)
+            .parse(BitInput::new(&[1, 2]))
+            .into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::from(16..16));
+    }
+
     #[test]
     #[cfg(feature = "memoization")]
     fn exponential() {
@@ -3233,6 +4695,242 @@ mod tests {
         assert_eq!(parser().parse("a+b+c").into_result().unwrap(), "abc");
     }
 
+    #[test]
+    #[cfg(feature = "memoization")]
+    fn left_recursive_seed_growing() {
+        use crate::prelude::*;
+
+        fn int<'src>() -> impl Parser<'src, &'src str, String> + Clone {
+            text::int(10).map(String::from)
+        }
+
+        // Direct left recursion: expr = expr '-' int | int
+        fn direct<'src>() -> impl Parser<'src, &'src str, String> {
+            recursive(|expr| {
+                expr.then_ignore(just('-'))
+                    .then(int())
+                    .map(|(a, b)| format!("({a}-{b})"))
+                    .or(int())
+                    .memoized()
+            })
+        }
+
+        assert_eq!(direct().parse("1").into_result().unwrap(), "1");
+        assert_eq!(direct().parse("1-2-3").into_result().unwrap(), "((1-2)-3)");
+        // Parsing without producing outputs grows the seed too
+        assert_eq!(
+            direct().to_slice().parse("1-2-3").into_result(),
+            Ok("1-2-3")
+        );
+        assert!(direct().parse("1-2-").has_errors());
+
+        // Long chains don't take quadratic time or overflow the stack
+        let long = (0..2000)
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join("-");
+        assert!(direct().parse(&long).into_result().is_ok());
+
+        // Outputs don't need to implement `Clone`
+        #[derive(Debug, PartialEq)]
+        struct Terms(Vec<String>);
+        let terms = recursive(|terms| {
+            terms
+                .then_ignore(just('-'))
+                .then(int())
+                .map(|(Terms(mut terms), term)| {
+                    terms.push(term);
+                    Terms(terms)
+                })
+                .or(int().map(|term| Terms(vec![term])))
+                .memoized()
+        });
+        assert_eq!(
+            terms.parse("1-2-3").into_result(),
+            Ok(Terms(vec!["1".into(), "2".into(), "3".into()]))
+        );
+
+        // Indirect left recursion through another memoized rule: sum = prod '+' int | prod, prod = sum '*' int | int
+        fn indirect<'src>() -> impl Parser<'src, &'src str, String> {
+            recursive(|sum| {
+                let prod = sum
+                    .clone()
+                    .then_ignore(just('*'))
+                    .then(int())
+                    .map(|(a, b)| format!("({a}*{b})"))
+                    .or(int())
+                    .memoized();
+                prod.clone()
+                    .then_ignore(just('+'))
+                    .then(int())
+                    .map(|(a, b)| format!("({a}+{b})"))
+                    .or(prod)
+                    .memoized()
+            })
+        }
+
+        assert_eq!(
+            indirect().parse("1+2*3+4").into_result().unwrap(),
+            "(((1+2)*3)+4)"
+        );
+
+        // Secondary errors within the seed are kept
+        let expr = recursive(|expr| {
+            let int = text::int::<_, extra::Err<Rich<char>>>(10)
+                .to_slice()
+                .recover_with(via_parser(just('?').to("0")));
+            expr.then_ignore(just('-'))
+                .then(int)
+                .map(|(a, b): (String, &str)| format!("({a}-{b})"))
+                .or(int.map(String::from))
+                .memoized()
+        });
+        let (out, errs) = expr.parse("?-2-?").into_output_errors();
+        assert_eq!(out.as_deref(), Some("((0-2)-0)"));
+        assert_eq!(errs.len(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "memoization")]
+    fn memo_stats() {
+        use crate::prelude::*;
+
+        let word = text::ascii::ident::<_, extra::Err<Simple<char>>>()
+            .to_slice()
+            .memoized()
+            .named("word");
+        let id = word.id();
+
+        // Clones and boxed copies share the memo table entries of the original
+        let boxed = word.boxed();
+        assert_ne!(word.memoized().id(), id);
+        let item = word
+            .then_ignore(just('!'))
+            .or(boxed.then_ignore(just('?')))
+            .or(just("1"));
+
+        let result = item.parse("1");
+        let counts = result.memo_stats().get(id).unwrap();
+        assert_eq!((counts.hits, counts.misses), (1, 1));
+        assert_eq!(counts.hit_rate(), 0.5);
+        assert_eq!(result.memo_stats().by_name("word"), Some(counts));
+        assert_eq!(result.memo_stats().total(), counts);
+        assert_eq!(result.memo_stats().peak_entries(), 1);
+        // Statistics aren't considered when comparing results
+        assert_eq!(
+            result,
+            just::<_, _, extra::Err<Simple<char>>>("1").parse("1")
+        );
+
+        // Successes aren't memoized
+        let counts = item.parse("foo?").memo_stats().get(id).unwrap();
+        assert_eq!((counts.hits, counts.misses), (0, 2));
+
+        // Left recursion is counted
+        let expr = recursive(|expr| {
+            expr.then_ignore(just::<_, _, extra::Err<Simple<char>>>('+'))
+                .then(text::int(10))
+                .to(())
+                .or(text::int(10).to(()))
+                .memoized()
+                .named("expr")
+        });
+        let result = expr.parse("1+2+3");
+        assert_eq!(
+            result.memo_stats().by_name("expr").unwrap().left_recursions,
+            1
+        );
+
+        // Bounded tables evict the oldest entries
+        let digit = one_of::<_, _, extra::Err<Simple<char>>>('0'..='9')
+            .memoized()
+            .max_entries(1);
+        let id = digit.id();
+        let digits = digit.to_slice().or(just("x")).repeated();
+        let result = digits.parse("xxx");
+        let counts = result.memo_stats().get(id).unwrap();
+        assert_eq!((counts.misses, counts.evictions), (4, 3));
+        // One cached failure, plus the entry for the invocation in progress
+        assert_eq!(result.memo_stats().peak_entries(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "memoization")]
+    fn incremental() {
+        use crate::incremental::{Cache, Edit};
+        use core::cell::Cell;
+
+        let runs = Cell::new(0);
+        let item = text::ascii::ident::<_, extra::Err<Simple<char>>>()
+            .map_with(|ident: &str, e| {
+                runs.set(runs.get() + 1);
+                (ident.to_string(), e.span())
+            })
+            .incremental();
+        let items = item.separated_by(just(',').padded()).collect::<Vec<_>>();
+
+        let mut cache = Cache::new();
+        let old = "alpha, beta, gamma, delta";
+        assert_eq!(
+            items.parse_incremental(old, &mut cache).into_result(),
+            items.parse(old).into_result(),
+        );
+        assert_eq!(cache.len(), 4);
+        runs.set(0);
+
+        // Reparsing identical input reuses everything
+        items
+            .parse_incremental(old, &mut cache)
+            .into_result()
+            .unwrap();
+        assert_eq!(runs.get(), 0);
+
+        // Replace `beta` with `b`
+        cache.edit(Edit::new(7..11, 1));
+        let new = "alpha, b, gamma, delta";
+        assert_eq!(
+            items.parse_incremental(new, &mut cache).into_result(),
+            items.parse(new).into_result(),
+        );
+        // Only `b` was parsed again (plus the fresh, non-incremental parse above)
+        assert_eq!(runs.get(), 1 + 4);
+
+        // An input that doesn't match the cache still parses correctly
+        let other = "x, y";
+        assert_eq!(
+            items.parse_incremental(other, &mut cache).into_result(),
+            items.parse(other).into_result(),
+        );
+
+        // Results are keyed on the parser's id rather than its address, so a rebuilt parser only reuses the results
+        // of the parser it replaces when given its id
+        let build = || {
+            text::ascii::ident::<_, extra::Err<Simple<char>>>()
+                .map_with(|ident: &str, e| {
+                    runs.set(runs.get() + 1);
+                    (ident.to_string(), e.span())
+                })
+                .incremental()
+        };
+        let old = build();
+        let mut cache = Cache::new();
+        old.parse_incremental("foo", &mut cache)
+            .into_result()
+            .unwrap();
+        runs.set(0);
+        build()
+            .parse_incremental("foo", &mut cache)
+            .into_result()
+            .unwrap();
+        assert_eq!(runs.get(), 1);
+        build()
+            .with_id(old.id())
+            .parse_incremental("foo", &mut cache)
+            .into_result()
+            .unwrap();
+        assert_eq!(runs.get(), 1);
+    }
+
     #[cfg(debug_assertions)]
     mod debug_asserts {
         use crate::prelude::*;
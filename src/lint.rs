@@ -0,0 +1,683 @@
+//! Analysis of the grammars described by parsers, finding mistakes before any input is parsed.
+//!
+//! *"Would it save you a lot of time if I just gave up and went mad now?"*
+//!
+//! Some mistakes in a parser only show themselves when it's given the wrong input: a repetition of a parser that can
+//! succeed without consuming anything never ends (and is only caught by debug assertions), and an alternative that can
+//! never be reached because an earlier one always matches first is never caught at all. [`Parser::lint`] examines the
+//! [`Grammar`] of a parser and reports these mistakes as [`Lint`]s, so that they can be checked for in a test.
+//!
+//! The analysis is conservative: parsers that can't describe themselves (see the [`grammar`] module) are assumed to
+//! consume input and to be able to fail, so mistakes involving them may be missed. Checks on the outputs of parsers,
+//! such as [`Parser::filter`], are not taken into account, so an alternative reported as shadowed may still be reached
+//! when the output of an earlier one is rejected.
+//!
+//! # Examples
+//!
+//! ```
+//! # use chumsky::{prelude::*, lint::Lint};
+//! let op = just::<_, &str, extra::Err<Simple<char>>>("=").or(just("=="));
+//! let spaces = just(' ').or_not().repeated();
+//!
+//! assert_eq!(
+//!     op.then_ignore(spaces).lint(),
+//!     vec![
+//!         Lint::ShadowedAlternative {
+//!             rule: "start".into(),
+//!             alternative: "\"==\"".into(),
+//!             shadowed_by: "\"=\"".into(),
+//!         },
+//!         Lint::NullableRepetition {
+//!             rule: "start".into(),
+//!             repetition: "{ [ \" \" ] }".into(),
+//!         },
+//!     ],
+//! );
+//! ```
+
+use super::*;
+use grammar::{Expr, Grammar};
+
+/// A mistake found in the grammar of a parser by [`Parser::lint`].
+///
+/// Rules are referred to by their names (see [`grammar::Rule::name`]) and expressions are written as EBNF, as by
+/// [`Grammar::to_ebnf`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Lint {
+    /// A repetition with no upper bound of a parser that can succeed without consuming input, which never ends.
+    NullableRepetition {
+        /// The rule containing the repetition.
+        rule: String,
+        /// The repetition.
+        repetition: String,
+    },
+    /// Rules that can invoke themselves without first consuming input, which recurse until the stack overflows.
+    ///
+    /// Left recursion through a parser created with [`Parser::memoized`] is supported, and not reported.
+    LeftRecursion {
+        /// The rules involved, in the order that they invoke one another. The last invokes the first.
+        cycle: Vec<String>,
+    },
+    /// An alternative of a choice that can never be reached, because an earlier alternative always matches first.
+    ///
+    /// For example, in `just("a").or(just("ab"))` the second alternative is never reached: whenever the input begins
+    /// with `ab`, the first alternative matches the `a`.
+    ShadowedAlternative {
+        /// The rule containing the choice.
+        rule: String,
+        /// The alternative that can never be reached.
+        alternative: String,
+        /// The earlier alternative that matches first.
+        shadowed_by: String,
+    },
+    /// A [`Recursive`] parser that was declared but never defined, which panics when used.
+    UndefinedRecursive {
+        /// The rule of the parser.
+        rule: String,
+    },
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NullableRepetition { rule, repetition } => write!(
+                f,
+                "in `{rule}`, `{repetition}` repeats a pattern that can match without consuming input, and never ends",
+            ),
+            Self::LeftRecursion { cycle } => write!(
+                f,
+                "`{}` is left-recursive ({} -> {})",
+                cycle[0],
+                cycle.join(" -> "),
+                cycle[0],
+            ),
+            Self::ShadowedAlternative {
+                rule,
+                alternative,
+                shadowed_by,
+            } => write!(
+                f,
+                "in `{rule}`, the alternative `{alternative}` is never reached because `{shadowed_by}` matches first",
+            ),
+            Self::UndefinedRecursive { rule } => {
+                write!(f, "`{rule}` was declared but never defined")
+            }
+        }
+    }
+}
+
+impl Grammar {
+    /// Check the grammar for mistakes. See [`Parser::lint`].
+    pub fn lint(&self) -> Vec<Lint> {
+        let analysis = Analysis::new(self);
+        let mut lints = self
+            .undefined
+            .iter()
+            .map(|id| Lint::UndefinedRecursive {
+                rule: self.rules()[*id].name().into(),
+            })
+            .collect::<Vec<_>>();
+        lints.extend(analysis.left_recursion());
+        for rule in self.ordered() {
+            analysis.walk(rule.name(), rule.expr(), &mut lints);
+        }
+        lints
+    }
+}
+
+/// The most token sequences that a set of literals is allowed to grow to before giving up on it.
+const MAX_LITERALS: usize = 64;
+
+type Literals = Vec<Vec<String>>;
+
+/// Facts about each rule of a grammar, found by iterating until they stop changing.
+struct Analysis<'a> {
+    grammar: &'a Grammar,
+    /// Whether each rule can succeed without consuming input.
+    nullable: Vec<bool>,
+    /// Whether each rule always succeeds.
+    infallible: Vec<bool>,
+    /// The literals and prefixes of each rule, found when first needed.
+    literals: RefCell<Vec<Option<Option<Literals>>>>,
+    prefixes: RefCell<Vec<Option<Literals>>>,
+}
+
+impl<'a> Analysis<'a> {
+    fn new(grammar: &'a Grammar) -> Self {
+        let rules = grammar.rules().len();
+        let mut this = Self {
+            grammar,
+            nullable: vec![false; rules],
+            infallible: vec![false; rules],
+            literals: RefCell::new(vec![None; rules]),
+            prefixes: RefCell::new(vec![None; rules]),
+        };
+        loop {
+            let mut changed = false;
+            for (id, rule) in grammar.rules().iter().enumerate() {
+                let (nullable, infallible) =
+                    (this.nullable(rule.expr()), this.infallible(rule.expr()));
+                changed |= nullable != this.nullable[id] || infallible != this.infallible[id];
+                this.nullable[id] = nullable;
+                this.infallible[id] = infallible;
+            }
+            if !changed {
+                break this;
+            }
+        }
+    }
+
+    fn ebnf(&self, expr: &Expr) -> String {
+        self.grammar.ebnf(expr, 0)
+    }
+
+    /// Whether the expression can succeed without consuming input.
+    fn nullable(&self, expr: &Expr) -> bool {
+        match expr {
+            Expr::End | Expr::Empty | Expr::Optional(_) | Expr::Lookahead { .. } => true,
+            Expr::Seq(items) => items.iter().all(|item| self.nullable(item)),
+            Expr::Choice(items) => items.iter().any(|item| self.nullable(item)),
+            Expr::Repeat {
+                item,
+                separator,
+                min,
+                ..
+            } => {
+                *min == 0
+                    || (self.nullable(item)
+                        && (*min == 1 || separator.as_ref().map_or(true, |sep| self.nullable(sep))))
+            }
+            Expr::Permutation { items, optional } => {
+                *optional || items.iter().all(|item| self.nullable(item))
+            }
+            Expr::Rule(id) => self.nullable[*id],
+            Expr::Memoized(expr) => self.nullable(expr),
+            Expr::Literal(_)
+            | Expr::OneOf(_)
+            | Expr::NoneOf(_)
+            | Expr::Any
+            | Expr::AnyExcept(_)
+            | Expr::Special(_)
+            | Expr::Undefined
+            | Expr::Unknown => false,
+        }
+    }
+
+    /// Whether the expression always succeeds.
+    fn infallible(&self, expr: &Expr) -> bool {
+        match expr {
+            Expr::Empty | Expr::Optional(_) => true,
+            Expr::Seq(items) => items.iter().all(|item| self.infallible(item)),
+            Expr::Choice(items) => items.iter().any(|item| self.infallible(item)),
+            Expr::Repeat {
+                item,
+                separator,
+                min,
+                ..
+            } => {
+                *min == 0
+                    || (self.infallible(item)
+                        && separator.as_ref().map_or(true, |sep| self.infallible(sep)))
+            }
+            Expr::Permutation { items, optional } => {
+                *optional || items.iter().all(|item| self.infallible(item))
+            }
+            Expr::Rule(id) => self.infallible[*id],
+            Expr::Memoized(expr) => self.infallible(expr),
+            _ => false,
+        }
+    }
+
+    /// Find the rules that the expression can invoke before consuming any input. Memoized parsers guard against left
+    /// recursion, so rules within them are not included.
+    fn left_rules(&self, expr: &Expr, rules: &mut Vec<usize>) {
+        match expr {
+            Expr::Rule(id) => rules.push(*id),
+            Expr::Seq(items) => {
+                for item in items {
+                    self.left_rules(item, rules);
+                    if !self.nullable(item) {
+                        break;
+                    }
+                }
+            }
+            // Any item of a permutation may come first
+            Expr::Choice(items) | Expr::Permutation { items, .. } => {
+                for item in items {
+                    self.left_rules(item, rules);
+                }
+            }
+            Expr::Repeat {
+                item,
+                separator,
+                leading,
+                ..
+            } => {
+                if let (true, Some(sep)) = (leading, separator) {
+                    self.left_rules(sep, rules);
+                }
+                self.left_rules(item, rules);
+            }
+            Expr::Optional(expr) | Expr::AnyExcept(expr) | Expr::Lookahead { expr, .. } => {
+                self.left_rules(expr, rules)
+            }
+            _ => {}
+        }
+    }
+
+    /// Find cycles of rules that invoke one another without consuming input, reporting each rule at most once.
+    fn left_recursion(&self) -> Vec<Lint> {
+        let edges = self
+            .grammar
+            .rules()
+            .iter()
+            .map(|rule| {
+                let mut rules = Vec::new();
+                self.left_rules(rule.expr(), &mut rules);
+                rules
+            })
+            .collect::<Vec<_>>();
+
+        let mut reported = vec![false; edges.len()];
+        let mut lints = Vec::new();
+        let first = self.grammar.start;
+        for start in core::iter::once(first).chain((0..edges.len()).filter(|id| *id != first)) {
+            if reported[start] {
+                continue;
+            }
+            // Search for the shortest path leading back to the rule
+            let mut parents = vec![None; edges.len()];
+            let mut queue = alloc::collections::VecDeque::from([start]);
+            while let Some(id) = queue.pop_front() {
+                if parents[start].is_some() {
+                    break;
+                }
+                for &next in &edges[id] {
+                    if parents[next].is_none() {
+                        parents[next] = Some(id);
+                        queue.push_back(next);
+                    }
+                }
+            }
+            let Some(mut id) = parents[start] else {
+                continue;
+            };
+            let mut cycle = vec![start];
+            while id != start {
+                cycle.push(id);
+                id = parents[id].unwrap();
+            }
+            cycle[1..].reverse();
+            for &id in &cycle {
+                reported[id] = true;
+            }
+            lints.push(Lint::LeftRecursion {
+                cycle: cycle
+                    .into_iter()
+                    .map(|id| self.grammar.rules()[id].name().into())
+                    .collect(),
+            });
+        }
+        lints
+    }
+
+    /// Check an expression, and those within it, for nullable repetitions and shadowed alternatives.
+    fn walk(&self, rule: &str, expr: &Expr, lints: &mut Vec<Lint>) {
+        match expr {
+            Expr::Repeat {
+                item,
+                separator,
+                max: None,
+                ..
+            } if self.nullable(item)
+                && separator.as_ref().map_or(true, |sep| self.nullable(sep)) =>
+            {
+                lints.push(Lint::NullableRepetition {
+                    rule: rule.into(),
+                    repetition: self.ebnf(expr),
+                })
+            }
+            Expr::Choice(items) => {
+                for (i, alternative) in items.iter().enumerate() {
+                    if let Some(earlier) = items[..i]
+                        .iter()
+                        .find(|earlier| self.shadows(earlier, alternative))
+                    {
+                        lints.push(Lint::ShadowedAlternative {
+                            rule: rule.into(),
+                            alternative: self.ebnf(alternative),
+                            shadowed_by: self.ebnf(earlier),
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        match expr {
+            Expr::Seq(items) | Expr::Choice(items) | Expr::Permutation { items, .. } => {
+                for item in items {
+                    self.walk(rule, item, lints);
+                }
+            }
+            Expr::Repeat {
+                item, separator, ..
+            } => {
+                self.walk(rule, item, lints);
+                if let Some(sep) = separator {
+                    self.walk(rule, sep, lints);
+                }
+            }
+            Expr::Optional(expr)
+            | Expr::AnyExcept(expr)
+            | Expr::Lookahead { expr, .. }
+            | Expr::Memoized(expr) => self.walk(rule, expr, lints),
+            _ => {}
+        }
+    }
+
+    /// Whether `earlier` matches whenever `later` would, when tried first.
+    fn shadows(&self, earlier: &Expr, later: &Expr) -> bool {
+        if self.infallible(earlier) {
+            return true;
+        }
+        let Some(literals) = self.literals(earlier) else {
+            return false;
+        };
+        let prefixes = self.prefixes(later);
+        !prefixes.is_empty()
+            && prefixes
+                .iter()
+                .all(|prefix| literals.iter().any(|lit| prefix.starts_with(lit)))
+    }
+
+    /// Find every sequence of tokens that the expression matches, if there are few enough of them.
+    fn literals(&self, expr: &Expr) -> Option<Literals> {
+        let literals = match expr {
+            Expr::Literal(toks) => vec![toks.clone()],
+            Expr::OneOf(toks) => toks.iter().map(|tok| vec![tok.clone()]).collect(),
+            Expr::Empty => vec![Vec::new()],
+            Expr::Seq(items) => {
+                let mut literals = vec![Vec::new()];
+                for item in items {
+                    literals = concat(&literals, &self.literals(item)?);
+                    if literals.len() > MAX_LITERALS {
+                        return None;
+                    }
+                }
+                literals
+            }
+            Expr::Choice(items) => {
+                let mut literals = Vec::new();
+                for item in items {
+                    literals.extend(self.literals(item)?);
+                }
+                literals
+            }
+            Expr::Optional(expr) => {
+                let mut literals = self.literals(expr)?;
+                literals.push(Vec::new());
+                literals
+            }
+            Expr::Rule(id) => {
+                if let Some(literals) = &self.literals.borrow()[*id] {
+                    return literals.clone();
+                }
+                // Recursive rules match too many sequences
+                self.literals.borrow_mut()[*id] = Some(None);
+                let literals = self.literals(self.grammar.rules()[*id].expr());
+                self.literals.borrow_mut()[*id] = Some(literals.clone());
+                literals?
+            }
+            Expr::Memoized(expr) => self.literals(expr)?,
+            _ => return None,
+        };
+        (literals.len() <= MAX_LITERALS).then_some(literals)
+    }
+
+    /// Find sequences of tokens such that everything the expression matches begins with one of them.
+    fn prefixes(&self, expr: &Expr) -> Literals {
+        if let Some(literals) = self.literals(expr) {
+            return literals;
+        }
+        let prefixes = match expr {
+            Expr::Seq(items) => {
+                let mut prefixes = vec![Vec::new()];
+                for item in items {
+                    let (next, complete) = match self.literals(item) {
+                        Some(literals) => (literals, true),
+                        None => (self.prefixes(item), false),
+                    };
+                    let next = concat(&prefixes, &next);
+                    if next.len() > MAX_LITERALS {
+                        break;
+                    }
+                    prefixes = next;
+                    if !complete {
+                        break;
+                    }
+                }
+                prefixes
+            }
+            Expr::Choice(items)
+            | Expr::Permutation {
+                items,
+                optional: false,
+            } => items.iter().flat_map(|item| self.prefixes(item)).collect(),
+            Expr::Repeat {
+                item,
+                min,
+                leading: false,
+                ..
+            } if *min > 0 => self.prefixes(item),
+            Expr::Rule(id) => {
+                if let Some(prefixes) = &self.prefixes.borrow()[*id] {
+                    return prefixes.clone();
+                }
+                // Recursive rules begin with anything while they're being found
+                self.prefixes.borrow_mut()[*id] = Some(vec![Vec::new()]);
+                let prefixes = self.prefixes(self.grammar.rules()[*id].expr());
+                self.prefixes.borrow_mut()[*id] = Some(prefixes.clone());
+                prefixes
+            }
+            Expr::Memoized(expr) => self.prefixes(expr),
+            _ => vec![Vec::new()],
+        };
+        if prefixes.len() > MAX_LITERALS {
+            vec![Vec::new()]
+        } else {
+            prefixes
+        }
+    }
+}
+
+/// Every sequence from `a` followed by every sequence from `b`.
+fn concat(a: &[Vec<String>], b: &[Vec<String>]) -> Literals {
+    a.iter()
+        .flat_map(|a| b.iter().map(move |b| a.iter().chain(b).cloned().collect()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recursive::Indirect;
+
+    type Extra = extra::Err<EmptyErr>;
+
+    #[test]
+    fn repetitions() {
+        let a = just::<_, &str, Extra>('a');
+        assert_eq!(a.repeated().lint(), vec![]);
+        assert_eq!(a.or_not().repeated().exactly(3).lint(), vec![]);
+        assert_eq!(
+            a.or_not().repeated().at_least(1).lint(),
+            vec![Lint::NullableRepetition {
+                rule: "start".into(),
+                repetition: "[ \"a\" ], { [ \"a\" ] }".into(),
+            }],
+        );
+        // A separator that consumes input ensures progress
+        assert_eq!(a.or_not().separated_by(just(',')).lint(), vec![]);
+        assert_eq!(
+            text::whitespace::<&str, Extra>()
+                .separated_by(empty())
+                .lint(),
+            vec![Lint::NullableRepetition {
+                rule: "start".into(),
+                repetition: "[ { ? any ? }, { , { ? any ? } } ]".into(),
+            }],
+        );
+    }
+
+    #[test]
+    fn left_recursion() {
+        let expr = recursive(|expr| {
+            let term = recursive(|term| {
+                term.clone()
+                    .then(one_of("*/"))
+                    .then(text::int::<&str, Extra>(10))
+                    .ignored()
+                    .or(expr.clone().delimited_by(just('('), just(')')))
+                    .or(text::int(10).ignored())
+            });
+            expr.then(one_of("+-"))
+                .then(term.clone())
+                .ignored()
+                .or(term)
+        })
+        .labelled("expr")
+        .as_rule();
+        assert_eq!(
+            expr.lint(),
+            vec![
+                Lint::LeftRecursion {
+                    cycle: vec!["expr".into()],
+                },
+                Lint::LeftRecursion {
+                    cycle: vec!["rule_1".into()],
+                },
+            ],
+        );
+
+        // Indirect recursion, through a nullable prefix
+        let a = Recursive::<Indirect<&str, (), Extra>>::declare();
+        let b = just('b')
+            .or_not()
+            .ignore_then(a.clone())
+            .labelled("b")
+            .as_rule();
+        let mut a_def = a.clone();
+        a_def.define(b.then(just('a')).ignored().or(just('a').ignored()));
+        let lints = a.lint();
+        assert_eq!(
+            lints,
+            vec![Lint::LeftRecursion {
+                cycle: vec!["start".into(), "b".into()],
+            }],
+        );
+        assert_eq!(
+            lints[0].to_string(),
+            "`start` is left-recursive (start -> b -> start)"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "memoization")]
+    fn memoized_left_recursion() {
+        let expr = recursive(|expr| {
+            expr.then_ignore(just('-'))
+                .then(text::int::<&str, Extra>(10))
+                .ignored()
+                .or(text::int(10).ignored())
+                .memoized()
+        });
+        assert_eq!(expr.lint(), vec![]);
+    }
+
+    #[test]
+    fn shadowed() {
+        let lint = |alternative: &str, shadowed_by: &str| Lint::ShadowedAlternative {
+            rule: "start".into(),
+            alternative: alternative.into(),
+            shadowed_by: shadowed_by.into(),
+        };
+
+        assert_eq!(
+            just::<_, &str, Extra>("a").or(just("ab")).lint(),
+            vec![lint("\"ab\"", "\"a\"")],
+        );
+        assert_eq!(just::<_, &str, Extra>("ab").or(just("a")).lint(), vec![]);
+        assert_eq!(
+            choice((
+                just::<_, &str, Extra>("a").then(one_of("xy")).to_slice(),
+                just("ay"),
+                just("b").or_not().to_slice(),
+                just("c"),
+            ))
+            .lint(),
+            vec![
+                lint("\"ay\"", "\"a\", ( 'x' | 'y' )"),
+                lint("\"c\"", "[ \"b\" ]")
+            ],
+        );
+        // Later alternatives that only begin with a literal are shadowed too
+        assert_eq!(
+            just::<_, &str, Extra>('-')
+                .to(())
+                .or(just("->").then(text::ascii::ident()).to(()))
+                .lint(),
+            vec![lint("\"->\", ? identifier ?", "\"-\"")],
+        );
+        // Quotes and escapes are compared correctly
+        assert_eq!(
+            one_of::<_, &str, Extra>("'\"\\")
+                .to(())
+                .or(just("\\n").to(()))
+                .or(just("\n").to(()))
+                .lint(),
+            vec![lint("\"\\\\n\"", "'\\'' | '\"' | '\\\\'")],
+        );
+        // Tokens are compared whole, even when their debug representations contain spaces or quotes
+        assert_eq!(
+            just::<_, &[&str], Extra>(["a b"])
+                .ignored()
+                .or(just(["a", "b"]).ignored())
+                .lint(),
+            vec![],
+        );
+        assert_eq!(
+            just::<_, &[&str], Extra>(["a b"])
+                .ignored()
+                .or(just(["a b", "c"]).ignored())
+                .lint(),
+            vec![lint("\"a b\" \"c\"", "\"a b\"")],
+        );
+        // Keywords don't describe the tokens they match, so they're never reported as shadowing anything
+        assert_eq!(
+            text::ascii::keyword::<&str, _, Extra>("if")
+                .or(text::ascii::keyword("iffy"))
+                .or(just("if"))
+                .lint(),
+            vec![],
+        );
+    }
+
+    #[test]
+    fn undefined() {
+        let undefined = Recursive::<Indirect<&str, (), Extra>>::declare();
+        let parser = just('a').ignored().or(undefined).labelled("item").as_rule();
+        let lints = parser.lint();
+        assert_eq!(
+            lints,
+            vec![Lint::UndefinedRecursive {
+                rule: "rule_0".into()
+            }],
+        );
+        assert_eq!(
+            lints[0].to_string(),
+            "`rule_0` was declared but never defined"
+        );
+    }
+}
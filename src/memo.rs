@@ -0,0 +1,243 @@
+//! Types for identifying memoized parsers and inspecting how effective memoization was during a parse.
+//!
+//! *"Ford, you're turning into a penguin. Stop it."*
+//!
+//! Each parser created with [`Parser::memoized`] is given a [`MemoId`] when it is constructed. The id is a property of
+//! the parser value rather than of its location in memory, so it's shared by clones and survives the parser being
+//! moved or boxed. Results of memoized parsers are keyed on this id and the location within the input.
+//!
+//! After a parse, [`ParseResult::memo_stats`] reports how many invocations of each memoized parser were answered from
+//! the memo table (hits) and how many required the parser to run (misses). If a memoized parser rarely hits, it is
+//! probably not worth memoizing. If the table grows too large, [`Memoized::max_entries`] bounds the number of results
+//! that a parser may keep.
+//!
+//! # Example
+//!
+//! ```
+//! # use chumsky::prelude::*;
+//! let atom = text::ascii::ident::<_, extra::Err<Simple<char>>>()
+//!     .to_slice()
+//!     .memoized()
+//!     .named("atom");
+//! let atom_id = atom.id();
+//!
+//! // Both alternatives start with an atom, so a failing atom is only parsed once at each location
+//! let item = atom.then_ignore(just('!')).or(atom.then_ignore(just('?'))).or(just("0"));
+//!
+//! let result = item.parse("0");
+//! let stats = result.memo_stats();
+//! assert_eq!(stats.get(atom_id), stats.by_name("atom"));
+//! assert_eq!(stats.by_name("atom").unwrap().misses, 1);
+//! assert_eq!(stats.by_name("atom").unwrap().hits, 1);
+//! ```
+
+use super::*;
+use alloc::collections::{BTreeMap, VecDeque};
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// A unique identity given to each [`Memoized`] parser when it is created.
+///
+/// Clones of a memoized parser share its id.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MemoId(usize);
+
+impl MemoId {
+    /// Generate an id that has not been given to any other parser.
+    pub(crate) fn fresh() -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        Self(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Get the index of this id. Ids are allocated in increasing order of parser construction.
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+/// Counts of how memoized parsers were used during a parse.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MemoCounts {
+    /// The number of invocations that were answered from the memo table, without running the parser.
+    pub hits: usize,
+    /// The number of invocations that required the parser to run.
+    pub misses: usize,
+    /// The number of times that a parser was found to be left-recursive, requiring a seed to be grown.
+    pub left_recursions: usize,
+    /// The number of results that were discarded because of [`Memoized::max_entries`].
+    pub evictions: usize,
+}
+
+impl MemoCounts {
+    /// The proportion of invocations that were answered from the memo table, between `0.0` and `1.0`.
+    ///
+    /// Returns `0.0` if there were no invocations.
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            total => self.hits as f64 / total as f64,
+        }
+    }
+
+    fn add(&mut self, other: &Self) {
+        self.hits += other.hits;
+        self.misses += other.misses;
+        self.left_recursions += other.left_recursions;
+        self.evictions += other.evictions;
+    }
+}
+
+/// Statistics about memoization during a parse, obtained with [`ParseResult::memo_stats`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemoStats {
+    parsers: BTreeMap<MemoId, (Option<&'static str>, MemoCounts)>,
+    peak_entries: usize,
+}
+
+impl MemoStats {
+    /// The counts for all memoized parsers combined.
+    pub fn total(&self) -> MemoCounts {
+        self.parsers
+            .values()
+            .fold(MemoCounts::default(), |mut total, (_, counts)| {
+                total.add(counts);
+                total
+            })
+    }
+
+    /// The counts for the memoized parser with the given id, if it was invoked.
+    pub fn get(&self, id: MemoId) -> Option<MemoCounts> {
+        self.parsers.get(&id).map(|(_, counts)| *counts)
+    }
+
+    /// The combined counts for memoized parsers given the name with [`Memoized::named`], if any were invoked.
+    pub fn by_name(&self, name: &str) -> Option<MemoCounts> {
+        self.parsers
+            .values()
+            .filter(|(n, _)| *n == Some(name))
+            .fold(None, |total, (_, counts)| {
+                let mut total = total.unwrap_or_default();
+                total.add(counts);
+                Some(total)
+            })
+    }
+
+    /// Iterate over the id, name and counts of each memoized parser that was invoked, in order of id.
+    pub fn iter(&self) -> impl Iterator<Item = (MemoId, Option<&'static str>, MemoCounts)> + '_ {
+        self.parsers
+            .iter()
+            .map(|(id, (name, counts))| (*id, *name, *counts))
+    }
+
+    /// The largest number of entries that the memo table held at once.
+    pub fn peak_entries(&self) -> usize {
+        self.peak_entries
+    }
+
+    /// Add the statistics of a parse of a nested input.
+    pub(crate) fn merge(&mut self, other: Self) {
+        for (id, (name, counts)) in other.parsers {
+            self.counts(id, name).add(&counts);
+        }
+        self.peak_entries = self.peak_entries.max(other.peak_entries);
+    }
+
+    pub(crate) fn counts(&mut self, id: MemoId, name: Option<&'static str>) -> &mut MemoCounts {
+        let (n, counts) = self.parsers.entry(id).or_default();
+        if name.is_some() {
+            *n = name;
+        }
+        counts
+    }
+}
+
+/// The state of a memoized parser at a particular input location.
+pub(crate) enum Memo<T, E> {
+    /// The parser is currently running at this location. If it gets invoked here again before finishing, it's left
+    /// recursive.
+    InProgress { left_recursive: bool },
+    /// The parser is left recursive at this location and is growing a seed. Invocations of the parser at this location
+    /// produce the result of the seed at `level` (or fail, if `level` is `0`) rather than running the parser.
+    Growing {
+        /// The results found so far, shortest first.
+        seeds: Vec<Seed<T, E>>,
+        /// The number of seeds that invocations of the parser at this location may use.
+        level: usize,
+    },
+    /// The parser failed at this location with the given error.
+    Failed(Option<Located<T, E>>),
+}
+
+/// A result found while growing a left-recursive parser.
+///
+/// Outputs are not kept: when one is needed, it is produced by parsing again with the previous seed.
+#[derive(Clone)]
+pub(crate) struct Seed<T, E> {
+    /// The cursor at the end of the seed.
+    pub(crate) end: T,
+    /// Secondary errors emitted while parsing the seed.
+    pub(crate) errors: Vec<Located<T, E>>,
+}
+
+pub(crate) struct Memos<T, E> {
+    table: HashMap<(usize, MemoId), Memo<T, E>>,
+    /// The locations of cached failures of parsers with a bounded number of entries, oldest first.
+    bounded: HashMap<MemoId, VecDeque<usize>>,
+    /// The number of times that a parser has been invoked at a location at which it was already running (or growing a
+    /// seed). Failures that happen while this changes depend on a left-recursive parser's partial result, so they
+    /// can't be cached.
+    pub(crate) recursions: usize,
+    pub(crate) stats: MemoStats,
+}
+
+impl<T, E> Default for Memos<T, E> {
+    fn default() -> Self {
+        Self {
+            table: HashMap::default(),
+            bounded: HashMap::default(),
+            recursions: 0,
+            stats: MemoStats::default(),
+        }
+    }
+}
+
+impl<T, E> Memos<T, E> {
+    pub(crate) fn get_mut(&mut self, key: &(usize, MemoId)) -> Option<&mut Memo<T, E>> {
+        self.table.get_mut(key)
+    }
+
+    pub(crate) fn remove(&mut self, key: &(usize, MemoId)) -> Option<Memo<T, E>> {
+        self.table.remove(key)
+    }
+
+    pub(crate) fn insert(&mut self, key: (usize, MemoId), memo: Memo<T, E>) {
+        self.table.insert(key, memo);
+        self.stats.peak_entries = self.stats.peak_entries.max(self.table.len());
+    }
+
+    /// Cache a failure, evicting the parser's oldest cached failure if it has more than `max_entries` of them.
+    pub(crate) fn insert_failed(
+        &mut self,
+        key: (usize, MemoId),
+        err: Option<Located<T, E>>,
+        max_entries: Option<usize>,
+        name: Option<&'static str>,
+    ) {
+        if let Some(max_entries) = max_entries {
+            let locations = self.bounded.entry(key.1).or_default();
+            locations.push_back(key.0);
+            while locations.len() > max_entries {
+                let Some(loc) = locations.pop_front() else {
+                    break;
+                };
+                if let Some(Memo::Failed(_)) = self.table.get(&(loc, key.1)) {
+                    self.table.remove(&(loc, key.1));
+                    self.stats.counts(key.1, name).evictions += 1;
+                }
+            }
+            if max_entries == 0 {
+                return;
+            }
+        }
+        self.insert(key, Memo::Failed(err));
+    }
+}
@@ -21,7 +21,13 @@
 //!
 //! Because operators bind atoms together, pratt parsers require you to specify, for each operator, a function that
 //! combines its operands together into a syntax tree. These functions are given as the last arguments of [`infix`],
-//! [`prefix`], and [`postfix`].
+//! [`prefix`], [`postfix`], [`ternary`], and [`prefix_ternary`].
+//!
+//! # Declared operators
+//!
+//! Some languages let programs declare operators of their own. [`declared_in_state`] and [`declared_in_ctx`] look up
+//! the fixity and binding power of operators at parse time, in an [`Operators`] table held by the parser state or
+//! context, so that declarations parsed earlier in the input affect how later expressions are parsed.
 //!
 //! # Examples
 //!
@@ -747,6 +753,690 @@ where
     op_check_and_emit!();
 }
 
+/// See [`ternary`].
+pub struct Ternary<'src, A, B, F, Atom, OpA, OpB, I, E> {
+    first_parser: A,
+    second_parser: B,
+    fold: F,
+    associativity: Associativity,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<&'src (Atom, OpA, OpB, I, E)>,
+}
+
+impl<A: Copy, B: Copy, F: Copy, Atom, OpA, OpB, I, E> Copy
+    for Ternary<'_, A, B, F, Atom, OpA, OpB, I, E>
+{
+}
+impl<A: Clone, B: Clone, F: Clone, Atom, OpA, OpB, I, E> Clone
+    for Ternary<'_, A, B, F, Atom, OpA, OpB, I, E>
+{
+    fn clone(&self) -> Self {
+        Self {
+            first_parser: self.first_parser.clone(),
+            second_parser: self.second_parser.clone(),
+            fold: self.fold.clone(),
+            associativity: self.associativity,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+/// Specify a ternary operator, made of two parts that sit between three operands, for a pratt parser with the given
+/// associativity, binding power, and [fold function](crate::pratt#fold-functions).
+///
+/// C's conditional operator, `a ? b : c`, and Python's conditional expression, `x if c else y`, are ternary operators.
+///
+/// The operand between the two parts is delimited by them, so it may be any expression at all, no matter the binding
+/// power of its operators. The binding power and associativity only determine how the operator binds to the operands
+/// on either side of it, just as with [`infix`]. For example, the conditional operator in C is right-associative so
+/// that `a ? b : c ? d : e` parses as `a ? b : (c ? d : e)`.
+///
+/// The fold function (the last argument) tells the parser how to combine the operator parts and operands into a new
+/// expression. It must have the following signature:
+///
+/// ```ignore
+/// impl Fn(Atom, OpA, Atom, OpB, Atom, &mut MapExtra<'src, '_, I, E>) -> O
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, pratt::*};
+/// let atom = text::ascii::ident::<_, extra::Err<Simple<char>>>().map(|s: &str| s.to_string()).padded();
+///
+/// let expr = atom.pratt((
+///     ternary(right(1), just('?'), just(':'), |c, _, a, _, b, _| format!("({c} ? {a} : {b})")),
+///     infix(left(2), just('+'), |l, _, r, _| format!("({l} + {r})")),
+/// ));
+///
+/// assert_eq!(
+///     expr.parse("a ? b + c : d ? e : f + g").into_result(),
+///     Ok("(a ? (b + c) : (d ? e : (f + g)))".to_string()),
+/// );
+/// ```
+pub const fn ternary<'src, A, B, F, Atom, OpA, OpB, I, E>(
+    associativity: Associativity,
+    first_parser: A,
+    second_parser: B,
+    fold: F,
+) -> Ternary<'src, A, B, F, Atom, OpA, OpB, I, E>
+where
+    F: Fn(Atom, OpA, Atom, OpB, Atom, &mut MapExtra<'src, '_, I, E>) -> Atom,
+{
+    Ternary {
+        first_parser,
+        second_parser,
+        fold,
+        associativity,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+impl<'src, I, O, E, A, B, F, OpA, OpB> Operator<'src, I, O, E>
+    for Ternary<'src, A, B, F, O, OpA, OpB, I, E>
+where
+    I: Input<'src>,
+    E: ParserExtra<'src, I>,
+    A: Parser<'src, I, OpA, E>,
+    B: Parser<'src, I, OpB, E>,
+    F: Fn(O, OpA, O, OpB, O, &mut MapExtra<'src, '_, I, E>) -> O,
+{
+    #[inline]
+    fn do_parse_infix<'parse, M: Mode>(
+        &self,
+        inp: &mut InputRef<'src, 'parse, I, E>,
+        pre_expr: &input::Cursor<'src, 'parse, I>,
+        pre_op: &input::Checkpoint<'src, 'parse, I, <E::State as Inspector<'src, I>>::Checkpoint>,
+        lhs: M::Output<O>,
+        min_power: u32,
+        f: &impl Fn(&mut InputRef<'src, 'parse, I, E>, u32) -> PResult<M, O>,
+    ) -> Result<M::Output<O>, M::Output<O>>
+    where
+        Self: Sized,
+    {
+        if self.associativity.left_power() < min_power {
+            return Err(lhs);
+        }
+        let parts = self.first_parser.go::<M>(inp).and_then(|first| {
+            let mid = f(inp, 0)?;
+            let second = self.second_parser.go::<M>(inp)?;
+            let rhs = f(inp, self.associativity.right_power())?;
+            Ok(M::combine(
+                M::combine(first, mid, |first, mid| (first, mid)),
+                M::combine(second, rhs, |second, rhs| (second, rhs)),
+                |first, second| (first, second),
+            ))
+        });
+        match parts {
+            Ok(parts) => Ok(M::combine(
+                lhs,
+                parts,
+                |lhs, ((first, mid), (second, rhs))| {
+                    (self.fold)(
+                        lhs,
+                        first,
+                        mid,
+                        second,
+                        rhs,
+                        &mut MapExtra::new(pre_expr, inp),
+                    )
+                },
+            )),
+            Err(()) => {
+                inp.rewind(pre_op.clone());
+                Err(lhs)
+            }
+        }
+    }
+
+    op_check_and_emit!();
+}
+
+/// See [`prefix_ternary`].
+pub struct PrefixTernary<'src, A, B, C, F, Atom, OpA, OpB, OpC, I, E> {
+    first_parser: A,
+    second_parser: B,
+    third_parser: C,
+    fold: F,
+    binding_power: u16,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<&'src (Atom, OpA, OpB, OpC, I, E)>,
+}
+
+impl<A: Copy, B: Copy, C: Copy, F: Copy, Atom, OpA, OpB, OpC, I, E> Copy
+    for PrefixTernary<'_, A, B, C, F, Atom, OpA, OpB, OpC, I, E>
+{
+}
+impl<A: Clone, B: Clone, C: Clone, F: Clone, Atom, OpA, OpB, OpC, I, E> Clone
+    for PrefixTernary<'_, A, B, C, F, Atom, OpA, OpB, OpC, I, E>
+{
+    fn clone(&self) -> Self {
+        Self {
+            first_parser: self.first_parser.clone(),
+            second_parser: self.second_parser.clone(),
+            third_parser: self.third_parser.clone(),
+            fold: self.fold.clone(),
+            binding_power: self.binding_power,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+/// Specify a ternary operator that begins with the first of its three parts, each of which is followed by an operand,
+/// for a pratt parser with the given binding power and [fold function](crate::pratt#fold-functions).
+///
+/// Conditional expressions like `if a then b else c` are prefix ternary operators in many languages.
+///
+/// The first two operands are delimited by the operator parts that follow them, so they may be any expression at all.
+/// The binding power only determines how the operator binds to the last operand, just as with [`prefix`]. For
+/// example, if `+` binds more tightly than `if`, `if a then b else c + d` parses as `if a then b else (c + d)`.
+///
+/// The fold function (the last argument) tells the parser how to combine the operator parts and operands into a new
+/// expression. It must have the following signature:
+///
+/// ```ignore
+/// impl Fn(OpA, Atom, OpB, Atom, OpC, Atom, &mut MapExtra<'src, '_, I, E>) -> O
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, pratt::*};
+/// let atom = text::int::<_, extra::Err<Simple<char>>>(10).from_str::<i64>().unwrapped().padded();
+/// let kw = |kw| text::ascii::keyword(kw).padded();
+///
+/// let expr = atom.pratt((
+///     prefix_ternary(1, kw("if"), kw("then"), kw("else"), |_, c, _, a, _, b, _| {
+///         if c != 0 { a } else { b }
+///     }),
+///     infix(left(2), just('+'), |l, _, r, _| l + r),
+/// ));
+///
+/// assert_eq!(expr.parse("if 0 then 1 else 2 + 3").into_result(), Ok(5));
+/// assert_eq!(expr.parse("if if 1 then 0 else 1 then 1 else 2").into_result(), Ok(2));
+/// ```
+pub const fn prefix_ternary<'src, A, B, C, F, Atom, OpA, OpB, OpC, I, E>(
+    binding_power: u16,
+    first_parser: A,
+    second_parser: B,
+    third_parser: C,
+    fold: F,
+) -> PrefixTernary<'src, A, B, C, F, Atom, OpA, OpB, OpC, I, E>
+where
+    F: Fn(OpA, Atom, OpB, Atom, OpC, Atom, &mut MapExtra<'src, '_, I, E>) -> Atom,
+{
+    PrefixTernary {
+        first_parser,
+        second_parser,
+        third_parser,
+        fold,
+        binding_power,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+impl<'src, I, O, E, A, B, C, F, OpA, OpB, OpC> Operator<'src, I, O, E>
+    for PrefixTernary<'src, A, B, C, F, O, OpA, OpB, OpC, I, E>
+where
+    I: Input<'src>,
+    E: ParserExtra<'src, I>,
+    A: Parser<'src, I, OpA, E>,
+    B: Parser<'src, I, OpB, E>,
+    C: Parser<'src, I, OpC, E>,
+    F: Fn(OpA, O, OpB, O, OpC, O, &mut MapExtra<'src, '_, I, E>) -> O,
+{
+    #[inline]
+    fn do_parse_prefix<'parse, M: Mode>(
+        &self,
+        inp: &mut InputRef<'src, 'parse, I, E>,
+        pre_expr: &input::Checkpoint<'src, 'parse, I, <E::State as Inspector<'src, I>>::Checkpoint>,
+        f: &impl Fn(&mut InputRef<'src, 'parse, I, E>, u32) -> PResult<M, O>,
+    ) -> PResult<M, O>
+    where
+        Self: Sized,
+    {
+        let parts = self.first_parser.go::<M>(inp).and_then(|first| {
+            let a = f(inp, 0)?;
+            let second = self.second_parser.go::<M>(inp)?;
+            let b = f(inp, 0)?;
+            let third = self.third_parser.go::<M>(inp)?;
+            let c = f(inp, Associativity::Left(self.binding_power).left_power())?;
+            Ok(M::combine(
+                M::combine(
+                    M::combine(first, a, |first, a| (first, a)),
+                    M::combine(second, b, |second, b| (second, b)),
+                    |first, second| (first, second),
+                ),
+                M::combine(third, c, |third, c| (third, c)),
+                |(first, second), third| (first, second, third),
+            ))
+        });
+        match parts {
+            Ok(parts) => Ok(M::map(parts, |((first, a), (second, b), (third, c))| {
+                (self.fold)(
+                    first,
+                    a,
+                    second,
+                    b,
+                    third,
+                    c,
+                    &mut MapExtra::new(pre_expr.cursor(), inp),
+                )
+            })),
+            Err(()) => {
+                inp.rewind(pre_expr.clone());
+                Err(())
+            }
+        }
+    }
+
+    op_check_and_emit!();
+}
+
+/// The fixity of an operator in an [`Operators`] table: the position it appears in relative to its operands, and how
+/// tightly it binds to them.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Fixity {
+    /// A unary prefix operator with the given binding power, like those created by [`prefix`].
+    Prefix(u16),
+    /// A binary infix operator with the given associativity and binding power, like those created by [`infix`].
+    Infix(Associativity),
+    /// A unary postfix operator with the given binding power, like those created by [`postfix`].
+    Postfix(u16),
+}
+
+/// A table of operators, keyed by the token (or other output of an operator parser) that denotes them, for use with
+/// [`declared_in_state`] and [`declared_in_ctx`].
+///
+/// Each operator may have at most one fixity of each kind, so `-` might be declared as both a prefix operator and an
+/// infix operator, but not as two different infix operators.
+///
+/// `Operators` implements [`Inspector`] without doing anything on rewind, so it may be used as parser state directly
+/// (for example, `extra::State<Operators<&str>>`). If operator declarations can be backtracked over, embed the table
+/// in a state type that restores it on rewind, such as [`RollbackState`](crate::inspector::RollbackState), and
+/// implement [`Borrow<Operators<T>>`](core::borrow::Borrow) for that type.
+#[derive(Clone, Debug)]
+pub struct Operators<T> {
+    prefix: HashMap<T, u16>,
+    infix: HashMap<T, Associativity>,
+    postfix: HashMap<T, u16>,
+}
+
+impl<T> Default for Operators<T> {
+    fn default() -> Self {
+        Self {
+            prefix: HashMap::default(),
+            infix: HashMap::default(),
+            postfix: HashMap::default(),
+        }
+    }
+}
+
+impl<T: Hash + Eq> Operators<T> {
+    /// Create an empty table of operators.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declare an operator with the given fixity, returning its previous fixity of the same kind, if any.
+    pub fn declare(&mut self, op: T, fixity: Fixity) -> Option<Fixity> {
+        match fixity {
+            Fixity::Prefix(power) => self.prefix.insert(op, power).map(Fixity::Prefix),
+            Fixity::Infix(assoc) => self.infix.insert(op, assoc).map(Fixity::Infix),
+            Fixity::Postfix(power) => self.postfix.insert(op, power).map(Fixity::Postfix),
+        }
+    }
+
+    /// Get the binding power of the given operator when it is used as a prefix operator, if declared.
+    pub fn prefix(&self, op: &T) -> Option<u16> {
+        self.prefix.get(op).copied()
+    }
+
+    /// Get the associativity and binding power of the given operator when it is used as an infix operator, if
+    /// declared.
+    pub fn infix(&self, op: &T) -> Option<Associativity> {
+        self.infix.get(op).copied()
+    }
+
+    /// Get the binding power of the given operator when it is used as a postfix operator, if declared.
+    pub fn postfix(&self, op: &T) -> Option<u16> {
+        self.postfix.get(op).copied()
+    }
+}
+
+impl<T: Hash + Eq> Extend<(T, Fixity)> for Operators<T> {
+    fn extend<Iter: IntoIterator<Item = (T, Fixity)>>(&mut self, iter: Iter) {
+        for (op, fixity) in iter {
+            self.declare(op, fixity);
+        }
+    }
+}
+
+impl<T: Hash + Eq> FromIterator<(T, Fixity)> for Operators<T> {
+    fn from_iter<Iter: IntoIterator<Item = (T, Fixity)>>(iter: Iter) -> Self {
+        let mut ops = Self::new();
+        ops.extend(iter);
+        ops
+    }
+}
+
+impl<'src, T, I: Input<'src>> Inspector<'src, I> for Operators<T> {
+    type Checkpoint = ();
+    const ON_TOKEN: bool = false;
+    #[inline(always)]
+    fn on_token(&mut self, _: &I::Token) {}
+    #[inline(always)]
+    fn on_save<'parse>(&self, _: &input::Cursor<'src, 'parse, I>) -> Self::Checkpoint {}
+    #[inline(always)]
+    fn on_rewind<'parse>(&mut self, _: &input::Checkpoint<'src, 'parse, I, Self::Checkpoint>) {}
+}
+
+/// An operator, applied to its operands, as passed to the fold function of [`declared_in_state`] and
+/// [`declared_in_ctx`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Operation<O, T> {
+    /// A prefix operator and its operand.
+    Prefix(T, O),
+    /// An infix operator and its operands.
+    Infix(O, T, O),
+    /// A postfix operator and its operand.
+    Postfix(O, T),
+}
+
+/// Marks a [`Declared`] operator as finding its [`Operators`] in the parser state. See [`declared_in_state`].
+#[derive(Copy, Clone, Debug)]
+pub struct InState;
+
+/// Marks a [`Declared`] operator as finding its [`Operators`] in the parser context. See [`declared_in_ctx`].
+#[derive(Copy, Clone, Debug)]
+pub struct InCtx;
+
+mod sealed {
+    use super::*;
+
+    pub trait OperatorSource<'src, I: Input<'src>, E: ParserExtra<'src, I>, T> {
+        fn operators<'a>(inp: &'a InputRef<'src, '_, I, E>) -> &'a Operators<T>;
+    }
+
+    impl<'src, I, E, T> OperatorSource<'src, I, E, T> for InState
+    where
+        I: Input<'src>,
+        E: ParserExtra<'src, I>,
+        E::State: Borrow<Operators<T>>,
+    {
+        fn operators<'a>(inp: &'a InputRef<'src, '_, I, E>) -> &'a Operators<T> {
+            (*inp.state).borrow()
+        }
+    }
+
+    impl<'src, I, E, T> OperatorSource<'src, I, E, T> for InCtx
+    where
+        I: Input<'src>,
+        E: ParserExtra<'src, I>,
+        E::Context: Borrow<Operators<T>>,
+    {
+        fn operators<'a>(inp: &'a InputRef<'src, '_, I, E>) -> &'a Operators<T> {
+            inp.ctx().borrow()
+        }
+    }
+}
+
+/// See [`declared_in_state`] and [`declared_in_ctx`].
+pub struct Declared<'src, A, F, S, Atom, Op, I, E> {
+    op_parser: A,
+    fold: F,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<&'src (S, Atom, Op, I, E)>,
+}
+
+impl<A: Copy, F: Copy, S, Atom, Op, I, E> Copy for Declared<'_, A, F, S, Atom, Op, I, E> {}
+impl<A: Clone, F: Clone, S, Atom, Op, I, E> Clone for Declared<'_, A, F, S, Atom, Op, I, E> {
+    fn clone(&self) -> Self {
+        Self {
+            op_parser: self.op_parser.clone(),
+            fold: self.fold.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+/// Specify operators for a pratt parser that are declared at parse time, in an [`Operators`] table found in the
+/// parser state, along with a [fold function](crate::pratt#fold-functions) for all of them.
+///
+/// This allows languages in which programs declare their own operators (such as Haskell's `infixl 6 <+>`) to be
+/// parsed: a parser for the declaration adds the operator to the table (via [`MapExtra::state`]), and every expression
+/// parsed afterwards makes use of it.
+///
+/// The operator parser (the first argument) should accept any token that could be an operator. Whenever it succeeds,
+/// its output is looked up in the table to find whether it's declared as an operator of the kind that is valid at
+/// that point in the expression and, if so, how tightly it binds. Undeclared operators are left unparsed.
+///
+/// The state must implement [`Borrow<Operators<Op>>`](core::borrow::Borrow). [`Operators`] itself may be used as the
+/// state.
+///
+/// The fold function (the last argument) tells the parser how to combine an [`Operation`] (an operator and its
+/// operands) into a new expression. It must have the following signature:
+///
+/// ```ignore
+/// impl Fn(Operation<Atom, Op>, &mut MapExtra<'src, '_, I, E>) -> O
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, pratt::*};
+/// type Extra<'src> = extra::Full<Simple<'src, char>, Operators<&'src str>, ()>;
+///
+/// let op = one_of::<_, _, Extra>("+-*/<>^|&").repeated().at_least(1).to_slice().padded();
+/// let atom = text::int(10).padded();
+///
+/// let expr = atom.map(str::to_string).pratt(declared_in_state(op, |operation, _| match operation {
+///     Operation::Prefix(op, x) => format!("({op}{x})"),
+///     Operation::Infix(l, op, r) => format!("({l} {op} {r})"),
+///     Operation::Postfix(x, op) => format!("({x}{op})"),
+/// }));
+///
+/// let power = text::int(10).padded().from_str::<u16>().unwrapped();
+/// let fixity = choice((
+///     text::ascii::keyword("infixl").ignore_then(power).map(|p| Fixity::Infix(left(p))),
+///     text::ascii::keyword("infixr").ignore_then(power).map(|p| Fixity::Infix(right(p))),
+///     text::ascii::keyword("prefix").ignore_then(power).map(Fixity::Prefix),
+/// ));
+/// let decl = fixity
+///     .padded()
+///     .then(op)
+///     .map_with(|(fixity, op), e| { e.state().declare(op, fixity); });
+///
+/// // Declarations take effect for every expression that follows them
+/// let stmt = choice((decl.map(|()| None), expr.map(Some))).then_ignore(just(';')).padded();
+/// let program = stmt.repeated().collect::<Vec<_>>();
+///
+/// let src = "
+///     infixl 6 <+>;
+///     infixr 7 <^>;
+///     prefix 8 -;
+///     1 <+> -2 <^> 3 <^> 4 <+> 5;
+/// ";
+/// assert_eq!(
+///     program.parse_with_state(src, &mut Operators::new()).into_result(),
+///     Ok(vec![None, None, None, Some("((1 <+> ((-2) <^> (3 <^> 4))) <+> 5)".to_string())]),
+/// );
+///
+/// // Without declarations, operators aren't recognised
+/// assert!(program.parse_with_state("1 <+> 2;", &mut Operators::new()).has_errors());
+/// ```
+pub const fn declared_in_state<'src, A, F, Atom, Op, I, E>(
+    op_parser: A,
+    fold: F,
+) -> Declared<'src, A, F, InState, Atom, Op, I, E>
+where
+    F: Fn(Operation<Atom, Op>, &mut MapExtra<'src, '_, I, E>) -> Atom,
+{
+    Declared {
+        op_parser,
+        fold,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+/// Specify operators for a pratt parser that are declared at parse time, in an [`Operators`] table found in the
+/// parser context, along with a [fold function](crate::pratt#fold-functions) for all of them.
+///
+/// This is the same as [`declared_in_state`], except that the context must implement
+/// [`Borrow<Operators<Op>>`](core::borrow::Borrow). The context can't be changed by the parser that it is passed to, so
+/// the table is either provided up front with [`Parser::with_ctx`], or built by parsing a block of declarations that
+/// is passed to the expression parser with [`Parser::ignore_with_ctx`].
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, pratt::*};
+/// let op = one_of("+*").padded();
+/// let expr = text::int(10)
+///     .padded()
+///     .map(str::to_string)
+///     .pratt(declared_in_ctx(op, |operation, _| match operation {
+///         Operation::Infix(l, op, r) => format!("({l} {op} {r})"),
+///         _ => unreachable!(),
+///     }));
+///
+/// // Declarations like `2+ 1*` come first, and are passed to the expression as context
+/// let decls = text::int::<_, extra::Default>(10)
+///     .from_str()
+///     .unwrapped()
+///     .then(one_of("+*"))
+///     .map(|(power, op)| (op, Fixity::Infix(left(power))))
+///     .padded()
+///     .repeated()
+///     .collect::<Vec<_>>()
+///     .map(|decls| decls.into_iter().collect::<Operators<char>>());
+/// let program = decls.then_ignore(just(';')).ignore_with_ctx(expr);
+///
+/// assert_eq!(program.parse("1+ 2*; 1 + 2 * 3").into_result(), Ok("(1 + (2 * 3))".to_string()));
+/// assert_eq!(program.parse("2+ 1*; 1 + 2 * 3").into_result(), Ok("((1 + 2) * 3)".to_string()));
+/// ```
+pub const fn declared_in_ctx<'src, A, F, Atom, Op, I, E>(
+    op_parser: A,
+    fold: F,
+) -> Declared<'src, A, F, InCtx, Atom, Op, I, E>
+where
+    F: Fn(Operation<Atom, Op>, &mut MapExtra<'src, '_, I, E>) -> Atom,
+{
+    Declared {
+        op_parser,
+        fold,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+impl<'src, I, O, E, A, F, S, Op> Operator<'src, I, O, E> for Declared<'src, A, F, S, O, Op, I, E>
+where
+    I: Input<'src>,
+    E: ParserExtra<'src, I>,
+    A: Parser<'src, I, Op, E>,
+    F: Fn(Operation<O, Op>, &mut MapExtra<'src, '_, I, E>) -> O,
+    S: sealed::OperatorSource<'src, I, E, Op>,
+    Op: Hash + Eq,
+{
+    #[inline]
+    fn do_parse_prefix<'parse, M: Mode>(
+        &self,
+        inp: &mut InputRef<'src, 'parse, I, E>,
+        pre_expr: &input::Checkpoint<'src, 'parse, I, <E::State as Inspector<'src, I>>::Checkpoint>,
+        f: &impl Fn(&mut InputRef<'src, 'parse, I, E>, u32) -> PResult<M, O>,
+    ) -> PResult<M, O>
+    where
+        Self: Sized,
+    {
+        // The operator must always be produced, since it determines the binding power
+        let parsed = self.op_parser.go::<Emit>(inp).and_then(|op| {
+            let power = S::operators(inp).prefix(&op).ok_or(())?;
+            let rhs = f(inp, Associativity::Left(power).left_power())?;
+            Ok((op, rhs))
+        });
+        match parsed {
+            Ok((op, rhs)) => Ok(M::map(rhs, |rhs| {
+                (self.fold)(
+                    Operation::Prefix(op, rhs),
+                    &mut MapExtra::new(pre_expr.cursor(), inp),
+                )
+            })),
+            Err(()) => {
+                inp.rewind(pre_expr.clone());
+                Err(())
+            }
+        }
+    }
+
+    #[inline]
+    fn do_parse_postfix<'parse, M: Mode>(
+        &self,
+        inp: &mut InputRef<'src, 'parse, I, E>,
+        pre_expr: &input::Cursor<'src, 'parse, I>,
+        pre_op: &input::Checkpoint<'src, 'parse, I, <E::State as Inspector<'src, I>>::Checkpoint>,
+        lhs: M::Output<O>,
+        min_power: u32,
+    ) -> Result<M::Output<O>, M::Output<O>>
+    where
+        Self: Sized,
+    {
+        let op = self.op_parser.go::<Emit>(inp).ok().filter(|op| {
+            S::operators(inp).postfix(op).map_or(false, |power| {
+                Associativity::Left(power).right_power() >= min_power
+            })
+        });
+        match op {
+            Some(op) => Ok(M::map(lhs, |lhs| {
+                (self.fold)(
+                    Operation::Postfix(lhs, op),
+                    &mut MapExtra::new(pre_expr, inp),
+                )
+            })),
+            None => {
+                inp.rewind(pre_op.clone());
+                Err(lhs)
+            }
+        }
+    }
+
+    #[inline]
+    fn do_parse_infix<'parse, M: Mode>(
+        &self,
+        inp: &mut InputRef<'src, 'parse, I, E>,
+        pre_expr: &input::Cursor<'src, 'parse, I>,
+        pre_op: &input::Checkpoint<'src, 'parse, I, <E::State as Inspector<'src, I>>::Checkpoint>,
+        lhs: M::Output<O>,
+        min_power: u32,
+        f: &impl Fn(&mut InputRef<'src, 'parse, I, E>, u32) -> PResult<M, O>,
+    ) -> Result<M::Output<O>, M::Output<O>>
+    where
+        Self: Sized,
+    {
+        let parsed = self.op_parser.go::<Emit>(inp).and_then(|op| {
+            let assoc = S::operators(inp)
+                .infix(&op)
+                .filter(|assoc| assoc.left_power() >= min_power)
+                .ok_or(())?;
+            let rhs = f(inp, assoc.right_power())?;
+            Ok((op, rhs))
+        });
+        match parsed {
+            Ok((op, rhs)) => Ok(M::combine(lhs, rhs, |lhs, rhs| {
+                (self.fold)(
+                    Operation::Infix(lhs, op, rhs),
+                    &mut MapExtra::new(pre_expr, inp),
+                )
+            })),
+            Err(()) => {
+                inp.rewind(pre_op.clone());
+                Err(lhs)
+            }
+        }
+    }
+
+    op_check_and_emit!();
+}
+
 /// See [`Parser::pratt`].
 #[derive(Copy, Clone)]
 pub struct Pratt<Atom, Ops> {
@@ -925,6 +1615,7 @@ impl<'src, Atom, Ops> Pratt<Atom, Ops> {
         Atom: Parser<'src, I, O, E>,
         Ops: Operator<'src, I, O, E>,
     {
+        inp.skip_trivia();
         let pre_expr = inp.save();
         // Prefix unary operators
         let mut lhs = match self
@@ -1254,4 +1945,103 @@ mod tests {
             Ok("(((§(1 + (-(~(2!)))))$) * 3)".to_string()),
         )
     }
+
+    #[test]
+    fn with_ternary_ops() {
+        let atom = text::int::<_, Err<Simple<char>>>(10).map(|s: &str| s.to_string());
+
+        let parser = atom.pratt((
+            infix(left(2), just('+'), |l, _, r, _| format!("({l} + {r})")),
+            infix(left(3), just('*'), |l, _, r, _| format!("({l} * {r})")),
+            ternary(right(1), just('?'), just(':'), |c, _, a, _, b, _| {
+                format!("({c} ? {a} : {b})")
+            }),
+            prefix_ternary(3, just('['), just('|'), just(']'), |_, c, _, a, _, b, _| {
+                format!("[{c} | {a}] {b}")
+            }),
+        ));
+
+        // Tighter operators bind within the outer operands, and anything goes between the parts
+        assert_eq!(
+            parser.parse("1+2?3*4?5:6:7*8").into_result(),
+            Ok("((1 + 2) ? ((3 * 4) ? 5 : 6) : (7 * 8))".to_string()),
+        );
+        // Right-associative, so a trailing ternary nests on the right
+        assert_eq!(
+            parser.parse("1?2:3?4:5").into_result(),
+            Ok("(1 ? 2 : (3 ? 4 : 5))".to_string()),
+        );
+        // The last operand of a prefix ternary takes operators that bind more tightly than it, but no others
+        assert_eq!(
+            parser.parse("[1?2:3|4+5]6*7+8").into_result(),
+            Ok("([(1 ? 2 : 3) | (4 + 5)] (6 * 7) + 8)".to_string()),
+        );
+        assert_eq!(
+            parser.parse("1?[2|3]4:5").into_result(),
+            Ok("(1 ? [2 | 3] 4 : 5)".to_string()),
+        );
+        // Incomplete operators are not parsed
+        assert_eq!(
+            parser.lazy().parse("1+2?3").into_result(),
+            Ok("(1 + 2)".to_string()),
+        );
+        assert!(parser.parse("1?2").has_errors());
+        assert!(parser.parse("[1|2").has_errors());
+    }
+
+    #[test]
+    fn with_declared_ops() {
+        type Extra<'src> = extra::Full<Simple<'src, char>, Operators<char>, ()>;
+
+        let atom = text::int::<_, Extra>(10).map(|s: &str| s.to_string());
+        let parser = atom.pratt((
+            // Declared operators can be mixed with static ones
+            infix(left(5), just('+'), |l, _, r, _| format!("({l} + {r})")),
+            declared_in_state(one_of("-*!^"), |operation, _| match operation {
+                Operation::Prefix(op, r) => format!("({op}{r})"),
+                Operation::Infix(l, op, r) => format!("({l} {op} {r})"),
+                Operation::Postfix(l, op) => format!("({l}{op})"),
+            }),
+        ));
+
+        let mut ops = Operators::new();
+        assert_eq!(ops.declare('-', Fixity::Prefix(8)), None);
+        ops.declare('-', Fixity::Infix(left(5)));
+        ops.declare('*', Fixity::Infix(left(6)));
+        ops.declare('!', Fixity::Postfix(9));
+        assert_eq!(ops.prefix(&'-'), Some(8));
+        assert_eq!(ops.infix(&'-'), Some(left(5)));
+        assert_eq!(ops.postfix(&'-'), None);
+
+        assert_eq!(
+            parser.parse_with_state("-1+2*3!-4", &mut ops).into_result(),
+            Ok("(((-1) + (2 * (3!))) - 4)".to_string()),
+        );
+        // Parsing without producing output still consults the table
+        assert!(!parser
+            .ignored()
+            .parse_with_state("-1+2*3!-4", &mut ops)
+            .has_errors());
+
+        // Redeclaring an operator changes how it binds
+        assert_eq!(
+            ops.declare('*', Fixity::Infix(right(4))),
+            Some(Fixity::Infix(left(6)))
+        );
+        assert_eq!(
+            parser.parse_with_state("1*2+3*4", &mut ops).into_result(),
+            Ok("(1 * ((2 + 3) * 4))".to_string()),
+        );
+
+        // Undeclared operators, and declared operators in the wrong position, aren't parsed
+        assert!(parser.parse_with_state("1^2", &mut ops).has_errors());
+        assert!(parser.parse_with_state("!1", &mut ops).has_errors());
+        assert_eq!(
+            parser
+                .lazy()
+                .parse_with_state("1^2", &mut ops)
+                .into_result(),
+            Ok("1".to_string()),
+        );
+    }
 }
@@ -41,6 +41,7 @@ where
 {
     #[inline]
     fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, ()> {
+        inp.skip_trivia();
         let before = inp.save();
         match inp.next_maybe_inner() {
             None => Ok(M::bind(|| ())),
@@ -53,6 +54,25 @@ where
         }
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, _scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::End
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        _scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        Ok(())
+    }
+
     go_extra!(());
 }
 
@@ -83,6 +103,25 @@ where
         Ok(M::bind(|| ()))
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, _scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::Empty
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        _scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        Ok(())
+    }
+
     go_extra!(());
 }
 
@@ -126,6 +165,8 @@ impl<T: Clone, I, E> Clone for Just<T, I, E> {
 
 /// A parser that accepts only the given input.
 ///
+/// Any trivia declared with [`Parser::with_trivia`] is skipped before the input.
+///
 /// The output type of this parser is `C`, the input or sequence that was provided.
 ///
 /// # Examples
@@ -165,6 +206,49 @@ where
         Self::go_cfg::<M>(self, inp, JustCfg::default())
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, _scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        use alloc::format;
+
+        grammar::Expr::literal(
+            self.seq
+                .seq_iter()
+                .map(|tok| format!("{:?}", Borrow::<I::Token>::borrow(&tok))),
+        )
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        scope.extend(
+            self.seq
+                .seq_iter()
+                .map(|tok| Borrow::<I::Token>::borrow(&tok).clone()),
+        );
+        Ok(())
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        let prefix = self
+            .seq
+            .seq_iter()
+            .map(|tok| Borrow::<I::Token>::borrow(&tok).clone())
+            .collect::<Vec<_>>();
+        // An empty sequence matches anywhere
+        (!prefix.is_empty()).then(|| vec![prefix])
+    }
+
     go_extra!(T);
 }
 
@@ -184,6 +268,7 @@ where
         cfg: Self::Config,
     ) -> PResult<M, T> {
         let seq = cfg.seq.as_ref().unwrap_or(&self.seq);
+        inp.skip_trivia();
         for next in seq.seq_iter() {
             let before = inp.save();
             match inp.next_maybe_inner() {
@@ -224,6 +309,8 @@ impl<T: Clone, I, E> Clone for OneOf<T, I, E> {
 
 /// A parser that accepts one of a sequence of specific inputs.
 ///
+/// Any trivia declared with [`Parser::with_trivia`] is skipped before the input.
+///
 /// The output type of this parser is `I`, the input that was found.
 ///
 /// # Examples
@@ -260,6 +347,7 @@ where
 {
     #[inline]
     fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, I::Token> {
+        inp.skip_trivia();
         let before = inp.save();
         match inp.next_inner() {
             #[allow(suspicious_double_ref_op)] // Is this a clippy bug?
@@ -267,18 +355,78 @@ where
             found => {
                 let err_span = inp.span_since(before.cursor());
                 inp.rewind(before);
-                inp.add_alt(
-                    self.seq
-                        .seq_iter()
-                        .map(|e| DefaultExpected::Token(T::to_maybe_ref(e))),
-                    found.map(|f| f.into()),
-                    err_span,
-                );
+                let found = found.map(|f| f.into());
+                match self.seq.describe(false) {
+                    Some(pattern) => {
+                        inp.add_alt([DefaultExpected::Class(pattern)], found, err_span)
+                    }
+                    None => inp.add_alt(
+                        self.seq
+                            .seq_iter()
+                            .map(|e| DefaultExpected::Token(T::to_maybe_ref(e))),
+                        found,
+                        err_span,
+                    ),
+                }
                 Err(())
             }
         }
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, _scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        use alloc::format;
+
+        if let Some(pattern) = self.seq.describe(false) {
+            return grammar::Expr::Special(pattern.as_ref().into());
+        }
+        grammar::Expr::one_of(
+            self.seq
+                .seq_iter()
+                .map(|tok| format!("{:?}", Borrow::<I::Token>::borrow(&tok))),
+            false,
+        )
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        let n = self.seq.seq_iter().count();
+        let i = scope.generator().below(n);
+        match self.seq.seq_iter().nth(i) {
+            Some(tok) => {
+                scope.push(Borrow::<I::Token>::borrow(&tok).clone());
+                Ok(())
+            }
+            // An empty set of tokens never matches
+            None => Err(generate::GenerateError::Exhausted),
+        }
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        // Large sets, such as ranges of characters, aren't worth listing
+        if self.seq.seq_iter().nth(256).is_some() {
+            return None;
+        }
+        Some(
+            self.seq
+                .seq_iter()
+                .map(|tok| vec![Borrow::<I::Token>::borrow(&tok).clone()])
+                .collect(),
+        )
+    }
+
     go_extra!(I::Token);
 }
 
@@ -337,6 +485,7 @@ where
 {
     #[inline]
     fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, I::Token> {
+        inp.skip_trivia();
         let before = inp.save();
         match inp.next_inner() {
             // #[allow(suspicious_double_ref_op)] // Is this a clippy bug?
@@ -344,16 +493,46 @@ where
             found => {
                 let err_span = inp.span_since(before.cursor());
                 inp.rewind(before);
-                inp.add_alt(
-                    [DefaultExpected::SomethingElse],
-                    found.map(|f| f.into()),
-                    err_span,
-                );
+                let expected = match self.seq.describe(true) {
+                    Some(pattern) => DefaultExpected::Class(pattern),
+                    None => DefaultExpected::SomethingElse,
+                };
+                inp.add_alt([expected], found.map(|f| f.into()), err_span);
                 Err(())
             }
         }
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, _scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        use alloc::format;
+
+        if let Some(pattern) = self.seq.describe(true) {
+            return grammar::Expr::Special(pattern.as_ref().into());
+        }
+        grammar::Expr::one_of(
+            self.seq
+                .seq_iter()
+                .map(|tok| format!("{:?}", Borrow::<I::Token>::borrow(&tok))),
+            true,
+        )
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        _scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        // There's no way to know which tokens aren't in the set
+        Err(generate::unsupported::<Self>())
+    }
+
     go_extra!(I::Token);
 }
 
@@ -473,6 +652,7 @@ where
 {
     #[inline]
     fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, O> {
+        inp.skip_trivia();
         let before = inp.save();
         let next = inp.next_inner();
         let found = match next {
@@ -490,6 +670,25 @@ where
         Err(())
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, _scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::Special("select".into())
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        _scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        Err(generate::unsupported::<Self>())
+    }
+
     go_extra!(O);
 }
 
@@ -533,6 +732,7 @@ where
 {
     #[inline]
     fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, O> {
+        inp.skip_trivia();
         let before = inp.save();
         let next = inp.next_ref_inner();
         let found = match next {
@@ -548,6 +748,25 @@ where
         Err(())
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, _scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::Special("select".into())
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        _scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        Err(generate::unsupported::<Self>())
+    }
+
     go_extra!(O);
 }
 
@@ -571,6 +790,7 @@ where
 {
     #[inline]
     fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, I::Token> {
+        inp.skip_trivia();
         let before = inp.save();
         match inp.next_inner() {
             Some(tok) => Ok(M::bind(|| tok)),
@@ -583,6 +803,25 @@ where
         }
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, _scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::Any
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        _scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        Err(generate::unsupported::<Self>())
+    }
+
     go_extra!(I::Token);
 }
 
@@ -627,6 +866,7 @@ where
 {
     #[inline]
     fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, &'src I::Token> {
+        inp.skip_trivia();
         let before = inp.save();
         match inp.next_ref_inner() {
             Some(tok) => Ok(M::bind(|| tok)),
@@ -639,6 +879,25 @@ where
         }
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, _scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::Any
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        _scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        Err(generate::unsupported::<Self>())
+    }
+
     go_extra!(&'src I::Token);
 }
 
@@ -666,171 +925,710 @@ pub const fn any_ref<'src, I: BorrowInput<'src>, E: ParserExtra<'src, I>>() -> A
     }
 }
 
-/// See [`map_ctx`].
-pub struct MapCtx<A, AE, F, E> {
-    pub(crate) parser: A,
-    pub(crate) mapper: F,
-    #[allow(dead_code)]
-    pub(crate) phantom: EmptyPhantom<(AE, E)>,
-}
+/// The tokens taken by [`take_while`] or, for [`take_till1`], the tokens that end the input taken: either a predicate,
+/// such as `|c: &char| c.is_ascii_digit()`, or a set of tokens, such as `",\n"` or `[b' ', b'\t']`.
+///
+/// Inputs like `&str` and `&[T]` can scan for a small set of tokens in bulk, which is much faster than checking each
+/// token in turn. For `&str`, this requires every token in the set to be ASCII.
+pub trait TokenSet<T> {
+    /// Returns true if the token is in the set.
+    fn contains(&self, tok: &T) -> bool;
+
+    /// Every token in the set, if the set is known ahead of time.
+    fn tokens(&self) -> Option<Vec<T>> {
+        None
+    }
 
-impl<A: Copy, AE, F: Copy, E> Copy for MapCtx<A, AE, F, E> {}
-impl<A: Clone, AE, F: Clone, E> Clone for MapCtx<A, AE, F, E> {
-    fn clone(&self) -> Self {
-        MapCtx {
-            parser: self.parser.clone(),
-            mapper: self.mapper.clone(),
-            phantom: EmptyPhantom::new(),
-        }
+    // Skip the tokens that are in the set (or, if `until`, that aren't) in bulk, returning the number skipped, or
+    // `None` if the input can't do so.
+    #[doc(hidden)]
+    fn skip<'src, I, E>(&self, inp: &mut InputRef<'src, '_, I, E>, until: bool) -> Option<usize>
+    where
+        I: Input<'src, Token = T>,
+        E: ParserExtra<'src, I>,
+    {
+        let _ = (inp, until);
+        None
     }
 }
 
-impl<'src, I, O, E, EI, A, F> Parser<'src, I, O, E> for MapCtx<A, EI, F, E>
+// Scan for a set of characters without allocating, if there are few enough of them.
+fn skip_chars<'src, I, E>(
+    inp: &mut InputRef<'src, '_, I, E>,
+    chars: impl IntoIterator<Item = char>,
+    until: bool,
+) -> Option<usize>
 where
-    I: Input<'src>,
+    I: Input<'src, Token = char>,
     E: ParserExtra<'src, I>,
-    EI: ParserExtra<'src, I, Error = E::Error, State = E::State>,
-    A: Parser<'src, I, O, EI>,
-    F: Fn(&E::Context) -> EI::Context,
-    EI::Context: 'src,
 {
+    let mut buf = ['\0'; 16];
+    let mut len = 0;
+    for c in chars {
+        *buf.get_mut(len)? = c;
+        len += 1;
+    }
+    inp.skip_tokens(&buf[..len], until)
+}
+
+impl<T, F: Fn(&T) -> bool> TokenSet<T> for F {
+    #[inline(always)]
+    fn contains(&self, tok: &T) -> bool {
+        self(tok)
+    }
+}
+
+impl TokenSet<char> for &str {
     #[inline]
-    fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, O> {
-        inp.with_ctx(&(self.mapper)(inp.ctx()), |inp| self.parser.go::<M>(inp))
+    fn contains(&self, tok: &char) -> bool {
+        str::contains(self, *tok)
     }
 
-    go_extra!(O);
+    fn tokens(&self) -> Option<Vec<char>> {
+        Some(self.chars().collect())
+    }
+
+    #[inline]
+    fn skip<'src, I, E>(&self, inp: &mut InputRef<'src, '_, I, E>, until: bool) -> Option<usize>
+    where
+        I: Input<'src, Token = char>,
+        E: ParserExtra<'src, I>,
+    {
+        skip_chars(inp, self.chars(), until)
+    }
 }
 
-/// Apply a mapping function to the context of this parser.
-///
-/// Note that this combinator will behave differently from all other maps, in terms of which
-/// parsers it effects - while other maps apply to the output of the parser, and thus read left-to-right, this one
-/// applies to the _input_ of the parser, and as such applies right-to-left.
+impl TokenSet<char> for String {
+    #[inline]
+    fn contains(&self, tok: &char) -> bool {
+        str::contains(self, *tok)
+    }
+
+    fn tokens(&self) -> Option<Vec<char>> {
+        Some(self.chars().collect())
+    }
+
+    #[inline]
+    fn skip<'src, I, E>(&self, inp: &mut InputRef<'src, '_, I, E>, until: bool) -> Option<usize>
+    where
+        I: Input<'src, Token = char>,
+        E: ParserExtra<'src, I>,
+    {
+        skip_chars(inp, self.chars(), until)
+    }
+}
+
+impl<T: PartialEq + Clone> TokenSet<T> for &[T] {
+    #[inline]
+    fn contains(&self, tok: &T) -> bool {
+        <[T]>::contains(self, tok)
+    }
+
+    fn tokens(&self) -> Option<Vec<T>> {
+        Some(self.to_vec())
+    }
+
+    #[inline]
+    fn skip<'src, I, E>(&self, inp: &mut InputRef<'src, '_, I, E>, until: bool) -> Option<usize>
+    where
+        I: Input<'src, Token = T>,
+        E: ParserExtra<'src, I>,
+    {
+        inp.skip_tokens(self, until)
+    }
+}
+
+impl<T: PartialEq + Clone, const N: usize> TokenSet<T> for [T; N] {
+    #[inline]
+    fn contains(&self, tok: &T) -> bool {
+        <[T]>::contains(self, tok)
+    }
+
+    fn tokens(&self) -> Option<Vec<T>> {
+        Some(self.to_vec())
+    }
+
+    #[inline]
+    fn skip<'src, I, E>(&self, inp: &mut InputRef<'src, '_, I, E>, until: bool) -> Option<usize>
+    where
+        I: Input<'src, Token = T>,
+        E: ParserExtra<'src, I>,
+    {
+        inp.skip_tokens(self, until)
+    }
+}
+
+impl TokenSet<char> for class::CharClass {
+    #[inline(always)]
+    fn contains(&self, tok: &char) -> bool {
+        class::CharClass::contains(self, *tok)
+    }
+
+    fn tokens(&self) -> Option<Vec<char>> {
+        // Large classes, such as those built from Unicode properties, aren't worth listing
+        let mut tokens = self.ranges().flatten();
+        let listed = (&mut tokens).take(256).collect();
+        tokens.next().is_none().then_some(listed)
+    }
+
+    #[inline]
+    fn skip<'src, I, E>(&self, inp: &mut InputRef<'src, '_, I, E>, until: bool) -> Option<usize>
+    where
+        I: Input<'src, Token = char>,
+        E: ParserExtra<'src, I>,
+    {
+        skip_chars(inp, self.ranges().flatten(), until)
+    }
+}
+
+/// See [`take_while`] and [`take_till1`].
+pub struct TakeWhile<F, I, E> {
+    set: F,
+    negate: bool,
+    at_least: usize,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(E, I)>,
+}
+
+impl<F: Copy, I, E> Copy for TakeWhile<F, I, E> {}
+impl<F: Clone, I, E> Clone for TakeWhile<F, I, E> {
+    fn clone(&self) -> Self {
+        Self {
+            set: self.set.clone(),
+            negate: self.negate,
+            at_least: self.at_least,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<F, I, E> TakeWhile<F, I, E> {
+    /// Require that at least the given number of tokens be taken for the parser to succeed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let hex = take_while::<_, _, extra::Err<Simple<char>>>(|c: &char| c.is_ascii_hexdigit()).at_least(2);
+    ///
+    /// assert_eq!(hex.parse("c0ffee").into_result(), Ok("c0ffee"));
+    /// assert!(hex.parse("c").has_errors());
+    /// ```
+    pub fn at_least(self, at_least: usize) -> Self {
+        Self { at_least, ..self }
+    }
+}
+
+/// A parser that takes tokens for as long as they are in the given [`TokenSet`] (either a predicate or a set of
+/// tokens), producing the slice of input that they span.
 ///
-/// More technically, if all combinators form a 'tree' of parsers, where each node executes
-/// its children in turn, normal maps apply up the tree. This means a parent mapper gets the
-/// result of its children, applies the map, then passes the new result to its parent. This map,
-/// however, applies down the tree. Context is provided from the parent,
-/// such as [`Parser::ignore_with_ctx`] and [`Parser::then_with_ctx`],
-/// and gets altered before being provided to the children.
+/// This is equivalent to `any().filter(pred).repeated().to_slice()`, but is considerably faster. In particular, no
+/// error is generated for the token that ends the run. When given a set of tokens rather than a predicate, inputs like
+/// `&str` scan for the end of the run in bulk. Use [`TakeWhile::at_least`] to require a minimum number of tokens.
 ///
-/// ```
-/// # use chumsky::{prelude::*, error::Simple};
+/// Any trivia declared with [`Parser::with_trivia`] is skipped before the input.
 ///
-/// let upper = just::<u8, &[u8], extra::Context<u8>>(b'0').configure(|cfg, ctx: &u8| cfg.seq(*ctx));
+/// The output type of this parser is `I::Slice`.
 ///
-/// let inc = one_of::<_, _, extra::Default>(b'a'..=b'z')
-///     .ignore_with_ctx(map_ctx::<_, u8, &[u8], extra::Context<u8>, extra::Context<u8>, _>(|c: &u8| c.to_ascii_uppercase(), upper))
-///     .to_slice()
-///     .repeated()
-///     .at_least(1)
-///     .collect::<Vec<_>>();
+/// # Examples
 ///
-/// assert_eq!(inc.parse(b"aAbB" as &[_]).into_result(), Ok(vec![b"aA" as &[_], b"bB"]));
-/// assert!(inc.parse(b"aB").has_errors());
-/// ```
-/// You can not only change the value of the context but also change the type entirely:
 /// ```
-/// # use chumsky::{
-///         extra,
-///         primitive::{just, map_ctx},
-///         ConfigParser, Parser,
-/// };
-///
-/// fn string_ctx<'src>() -> impl Parser<'src, &'src str, (), extra::Context<String>> {
-///     just("".to_owned())
-///         .configure(|cfg, s: &String| cfg.seq(s.clone()))
-///         .ignored()
-/// }
+/// # use chumsky::{prelude::*, error::Simple};
+/// let digits = take_while::<_, _, extra::Err<Simple<char>>>(|c: &char| c.is_ascii_digit());
 ///
-/// fn usize_ctx<'src>() -> impl Parser<'src, &'src str, (), extra::Context<usize>> {
-///     map_ctx::<_, _, _, extra::Context<usize>, extra::Context<String>, _>(
-///        |num: &usize| num.to_string(),
-///        string_ctx(),
-///     )
-/// }
+/// assert_eq!(digits.parse("48791").into_result(), Ok("48791"));
+/// assert_eq!(digits.lazy().parse("42!").into_result(), Ok("42"));
+/// assert_eq!(digits.parse("").into_result(), Ok(""));
 ///
-/// fn specific_usize<'src>(num: usize) -> impl Parser<'src, &'src str, ()> {
-///     usize_ctx().with_ctx(num)
-/// }
-/// assert!(!specific_usize(10).parse("10").has_errors());
+/// // A set of tokens is scanned for in bulk
+/// let spaces = take_while::<_, _, extra::Err<Simple<char>>>(" \t");
+/// assert_eq!(spaces.lazy().parse(" \t x").into_result(), Ok(" \t "));
 /// ```
-pub const fn map_ctx<'src, P, OP, I, E, EP, F>(mapper: F, parser: P) -> MapCtx<P, EP, F, E>
+pub const fn take_while<'src, F, I, E>(set: F) -> TakeWhile<F, I, E>
 where
-    F: Fn(&E::Context) -> EP::Context,
-    I: Input<'src>,
-    P: Parser<'src, I, OP, EP>,
+    I: SliceInput<'src>,
     E: ParserExtra<'src, I>,
-    EP: ParserExtra<'src, I>,
-    EP::Context: 'src,
+    F: TokenSet<I::Token>,
 {
-    MapCtx {
-        parser,
-        mapper,
+    TakeWhile {
+        set,
+        negate: false,
+        at_least: 0,
         phantom: EmptyPhantom::new(),
     }
 }
 
-/// See [`fn@todo`].
-pub struct Todo<I, O, E> {
-    location: Location<'static>,
-    #[allow(dead_code)]
-    phantom: EmptyPhantom<(O, E, I)>,
-}
-
-impl<I, O, E> Copy for Todo<I, O, E> {}
-impl<I, O, E> Clone for Todo<I, O, E> {
-    fn clone(&self) -> Self {
-        *self
-    }
-}
-
-/// A parser that can be used wherever you need to implement a parser later.
+/// A parser that takes one or more tokens, stopping before the first one in the given [`TokenSet`] (either a predicate
+/// or a set of tokens), and produces the slice of input that they span.
 ///
-/// This parser is analogous to the [`todo!`] and [`unimplemented!`] macros, but will produce a panic when used to
-/// parse input, not immediately when invoked.
+/// This is the inverse of [`take_while`], except that it requires at least one token to be taken.
 ///
-/// This function is useful when developing your parser, allowing you to prototype and run parts of your parser without
-/// committing to implementing the entire thing immediately.
+/// Any trivia declared with [`Parser::with_trivia`] is skipped before the input.
 ///
-/// The output type of this parser is whatever you want it to be: it'll never produce output!
+/// The output type of this parser is `I::Slice`.
 ///
 /// # Examples
 ///
-/// ```should_panic
-/// # use chumsky::prelude::*;
-/// let int = just::<_, _, extra::Err<Simple<char>>>("0x").ignore_then(todo())
-///     .or(just("0b").ignore_then(text::digits(2).to_slice()))
-///     .or(text::int(10).to_slice());
+/// ```
+/// # use chumsky::{prelude::*, error::Simple};
+/// let field = take_till1::<_, _, extra::Err<Simple<char>>>(",\n");
+/// let row = field.separated_by(just(',')).collect::<Vec<_>>();
+/// let csv = row.separated_by(just('\n')).collect::<Vec<_>>();
 ///
-/// // Decimal numbers are parsed
-/// assert_eq!(int.parse("12").into_result(), Ok("12"));
-/// // Binary numbers are parsed
-/// assert_eq!(int.parse("0b00101").into_result(), Ok("00101"));
-/// // Parsing hexidecimal numbers results in a panic because the parser is unimplemented
-/// int.parse("0xd4");
+/// assert_eq!(csv.parse("ab,c\ndef").into_result(), Ok(vec![vec!["ab", "c"], vec!["def"]]));
+/// assert!(csv.parse("ab,,def").has_errors());
+///
+/// // Predicates work too
+/// let word = take_till1::<_, _, extra::Err<Simple<char>>>(|c: &char| c.is_whitespace());
+/// assert_eq!(word.lazy().parse("hello world").into_result(), Ok("hello"));
 /// ```
-#[track_caller]
-pub fn todo<'src, I: Input<'src>, O, E: ParserExtra<'src, I>>() -> Todo<I, O, E> {
-    Todo {
-        location: *Location::caller(),
+pub const fn take_till1<'src, F, I, E>(set: F) -> TakeWhile<F, I, E>
+where
+    I: SliceInput<'src>,
+    E: ParserExtra<'src, I>,
+    F: TokenSet<I::Token>,
+{
+    TakeWhile {
+        set,
+        negate: true,
+        at_least: 1,
         phantom: EmptyPhantom::new(),
     }
 }
 
-impl<'src, I, O, E> Parser<'src, I, O, E> for Todo<I, O, E>
+impl<'src, I, E, F> Parser<'src, I, I::Slice, E> for TakeWhile<F, I, E>
 where
-    I: Input<'src>,
+    I: SliceInput<'src>,
     E: ParserExtra<'src, I>,
+    F: TokenSet<I::Token>,
 {
     #[inline]
-    fn go<M: Mode>(&self, _inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, O> {
-        todo!(
-            "Attempted to use an unimplemented parser at {}",
-            self.location
-        )
-    }
+    fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, I::Slice> {
+        inp.skip_trivia();
+        let before = inp.save();
+        let count = self.set.skip(inp, self.negate).unwrap_or_else(|| {
+            let mut count = 0;
+            inp.skip_while(|tok| {
+                let taken = self.set.contains(tok) != self.negate;
+                count += taken as usize;
+                taken
+            });
+            count
+        });
+
+        if count < self.at_least {
+            let at = inp.save();
+            let found = inp.next_maybe_inner();
+            let err_span = inp.span_since(at.cursor());
+            inp.rewind(at);
+            inp.add_alt(
+                [DefaultExpected::SomethingElse],
+                found.map(|f| f.into()),
+                err_span,
+            );
+            return Err(());
+        }
+
+        Ok(M::bind(|| inp.slice_since(before.cursor()..)))
+    }
+
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, _scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        use alloc::format;
+
+        let item = match self.set.tokens() {
+            Some(tokens) => {
+                grammar::Expr::one_of(tokens.iter().map(|tok| format!("{tok:?}")), self.negate)
+            }
+            None => grammar::Expr::Any,
+        };
+        grammar::Expr::Repeat {
+            item: Box::new(item),
+            separator: None,
+            min: self.at_least,
+            max: None,
+            leading: false,
+            trailing: false,
+        }
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        // There's no way to know which tokens satisfy a predicate, or aren't in a set
+        let Some(tokens) = self
+            .set
+            .tokens()
+            .filter(|tokens| !self.negate && !tokens.is_empty())
+        else {
+            return Err(generate::unsupported::<Self>());
+        };
+        scope.repeat(self.at_least, None, |_, scope| {
+            let i = scope.generator().below(tokens.len());
+            scope.push(tokens[i].clone());
+            Ok(())
+        })
+    }
+
+    go_extra!(I::Slice);
+}
+
+/// See [`take_until`].
+pub struct TakeUntil<P, OP, T> {
+    stop: P,
+    // The tokens that the stop parser can begin with, if known
+    first: Option<Vec<T>>,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<OP>,
+}
+
+impl<P: Clone, OP, T: Clone> Clone for TakeUntil<P, OP, T> {
+    fn clone(&self) -> Self {
+        Self {
+            stop: self.stop.clone(),
+            first: self.first.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+/// A parser that takes tokens up until (but not including) the point at which the given parser would succeed, or the
+/// end of the input, producing the slice of input that they span.
+///
+/// This is equivalent to `any().and_is(stop.not()).repeated().to_slice()`, but is considerably faster when the tokens
+/// that `stop` can begin with are known (such as when it is a [`just`] or [`one_of`]). In that case, the input is
+/// scanned for those tokens directly. For `&str` inputs with only a few ASCII tokens to look for, the scan works on
+/// many bytes at once.
+///
+/// Any trivia declared with [`Parser::with_trivia`] is skipped before the input, but not within the input that is taken
+/// nor while checking for `stop`.
+///
+/// To stop at a literal sequence of tokens, [`take_until_literal`] can be used in place of `take_until(just(...))`.
+///
+/// The output type of this parser is `I::Slice`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, error::Simple};
+/// let comment = just::<_, _, extra::Err<Simple<char>>>("/*")
+///     .ignore_then(take_until(just("*/")))
+///     .then_ignore(just("*/"));
+///
+/// assert_eq!(comment.parse("/* a * b / c */").into_result(), Ok(" a * b / c "));
+/// assert!(comment.parse("/* unterminated").has_errors());
+/// ```
+pub fn take_until<'src, I, OP, E, P>(stop: P) -> TakeUntil<P, OP, I::Token>
+where
+    I: SliceInput<'src>,
+    I::Token: Clone + PartialEq,
+    E: ParserExtra<'src, I>,
+    P: Parser<'src, I, OP, E>,
+{
+    let first = stop.prefixes().and_then(|prefixes| {
+        let mut first = Vec::new();
+        for prefix in prefixes {
+            // An empty prefix means that the stop parser can match anywhere
+            let tok = prefix.into_iter().next()?;
+            if !<[_]>::contains(&first, &tok) {
+                first.push(tok);
+            }
+        }
+        Some(first)
+    });
+    TakeUntil {
+        stop,
+        first,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+/// A parser that takes tokens up until (but not including) the given literal sequence of tokens, or the end of the
+/// input, producing the slice of input that they span.
+///
+/// This is equivalent to `take_until(just(literal))`. See [`take_until`].
+///
+/// The output type of this parser is `I::Slice`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, error::Simple};
+/// let line_comment = just::<_, _, extra::Err<Simple<char>>>("//")
+///     .ignore_then(take_until_literal("\n"))
+///     .padded();
+///
+/// assert_eq!(line_comment.parse("// hello\n").into_result(), Ok(" hello"));
+/// assert_eq!(line_comment.parse("// no newline").into_result(), Ok(" no newline"));
+/// ```
+pub fn take_until_literal<'src, I, E, T>(literal: T) -> TakeUntil<Just<T, I, E>, T, I::Token>
+where
+    I: SliceInput<'src>,
+    I::Token: Clone + PartialEq,
+    E: ParserExtra<'src, I>,
+    T: OrderedSeq<'src, I::Token> + Clone,
+{
+    take_until(just(literal))
+}
+
+impl<'src, I, OP, E, P> Parser<'src, I, I::Slice, E> for TakeUntil<P, OP, I::Token>
+where
+    I: SliceInput<'src>,
+    I::Token: PartialEq,
+    E: ParserExtra<'src, I>,
+    P: Parser<'src, I, OP, E>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, I::Slice> {
+        inp.skip_trivia();
+        let before = inp.save();
+        inp.with_trivia(None, |inp| loop {
+            if let Some(first) = &self.first {
+                if inp.skip_tokens(first, true).is_none() {
+                    inp.skip_while(|tok| !<[_]>::contains(first, tok));
+                }
+            }
+
+            let at = inp.save();
+            let alt = inp.errors.alt.take();
+            let outer_cut = inp.errors.cut;
+            let stopped = self.stop.go::<Check>(inp).is_ok();
+            inp.rewind(at);
+            // Failing to find the stop parser isn't an error, so leave no trace of it
+            inp.errors.alt = alt;
+            inp.errors.cut = outer_cut;
+
+            if stopped || inp.next_maybe_inner().is_none() {
+                break;
+            }
+        });
+        Ok(M::bind(|| inp.slice_since(before.cursor()..)))
+    }
+
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::Repeat {
+            item: Box::new(grammar::Expr::AnyExcept(Box::new(
+                self.stop.node_info(scope),
+            ))),
+            separator: None,
+            min: 0,
+            max: None,
+            leading: false,
+            trailing: false,
+        }
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        _scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        // There's no way to know which tokens aren't matched by the stop parser
+        Err(generate::unsupported::<Self>())
+    }
+
+    go_extra!(I::Slice);
+}
+
+/// See [`map_ctx`].
+pub struct MapCtx<A, AE, F, E> {
+    pub(crate) parser: A,
+    pub(crate) mapper: F,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<(AE, E)>,
+}
+
+impl<A: Copy, AE, F: Copy, E> Copy for MapCtx<A, AE, F, E> {}
+impl<A: Clone, AE, F: Clone, E> Clone for MapCtx<A, AE, F, E> {
+    fn clone(&self) -> Self {
+        MapCtx {
+            parser: self.parser.clone(),
+            mapper: self.mapper.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'src, I, O, E, EI, A, F> Parser<'src, I, O, E> for MapCtx<A, EI, F, E>
+where
+    I: Input<'src>,
+    E: ParserExtra<'src, I>,
+    EI: ParserExtra<'src, I, Error = E::Error, State = E::State>,
+    A: Parser<'src, I, O, EI>,
+    F: Fn(&E::Context) -> EI::Context,
+    EI::Context: 'src,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, O> {
+        inp.with_ctx(&(self.mapper)(inp.ctx()), |inp| self.parser.go::<M>(inp))
+    }
+
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
+    go_extra!(O);
+}
+
+/// Apply a mapping function to the context of this parser.
+///
+/// Note that this combinator will behave differently from all other maps, in terms of which
+/// parsers it effects - while other maps apply to the output of the parser, and thus read left-to-right, this one
+/// applies to the _input_ of the parser, and as such applies right-to-left.
+///
+/// More technically, if all combinators form a 'tree' of parsers, where each node executes
+/// its children in turn, normal maps apply up the tree. This means a parent mapper gets the
+/// result of its children, applies the map, then passes the new result to its parent. This map,
+/// however, applies down the tree. Context is provided from the parent,
+/// such as [`Parser::ignore_with_ctx`] and [`Parser::then_with_ctx`],
+/// and gets altered before being provided to the children.
+///
+/// ```
+/// # use chumsky::{prelude::*, error::Simple};
+///
+/// let upper = just::<u8, &[u8], extra::Context<u8>>(b'0').configure(|cfg, ctx: &u8| cfg.seq(*ctx));
+///
+/// let inc = one_of::<_, _, extra::Default>(b'a'..=b'z')
+///     .ignore_with_ctx(map_ctx::<_, u8, &[u8], extra::Context<u8>, extra::Context<u8>, _>(|c: &u8| c.to_ascii_uppercase(), upper))
+///     .to_slice()
+///     .repeated()
+///     .at_least(1)
+///     .collect::<Vec<_>>();
+///
+/// assert_eq!(inc.parse(b"aAbB" as &[_]).into_result(), Ok(vec![b"aA" as &[_], b"bB"]));
+/// assert!(inc.parse(b"aB").has_errors());
+/// ```
+/// You can not only change the value of the context but also change the type entirely:
+/// ```
+/// # use chumsky::{
+///         extra,
+///         primitive::{just, map_ctx},
+///         ConfigParser, Parser,
+/// };
+///
+/// fn string_ctx<'src>() -> impl Parser<'src, &'src str, (), extra::Context<String>> {
+///     just("".to_owned())
+///         .configure(|cfg, s: &String| cfg.seq(s.clone()))
+///         .ignored()
+/// }
+///
+/// fn usize_ctx<'src>() -> impl Parser<'src, &'src str, (), extra::Context<usize>> {
+///     map_ctx::<_, _, _, extra::Context<usize>, extra::Context<String>, _>(
+///        |num: &usize| num.to_string(),
+///        string_ctx(),
+///     )
+/// }
+///
+/// fn specific_usize<'src>(num: usize) -> impl Parser<'src, &'src str, ()> {
+///     usize_ctx().with_ctx(num)
+/// }
+/// assert!(!specific_usize(10).parse("10").has_errors());
+/// ```
+pub const fn map_ctx<'src, P, OP, I, E, EP, F>(mapper: F, parser: P) -> MapCtx<P, EP, F, E>
+where
+    F: Fn(&E::Context) -> EP::Context,
+    I: Input<'src>,
+    P: Parser<'src, I, OP, EP>,
+    E: ParserExtra<'src, I>,
+    EP: ParserExtra<'src, I>,
+    EP::Context: 'src,
+{
+    MapCtx {
+        parser,
+        mapper,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+/// See [`fn@todo`].
+pub struct Todo<I, O, E> {
+    location: Location<'static>,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(O, E, I)>,
+}
+
+impl<I, O, E> Copy for Todo<I, O, E> {}
+impl<I, O, E> Clone for Todo<I, O, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+/// A parser that can be used wherever you need to implement a parser later.
+///
+/// This parser is analogous to the [`todo!`] and [`unimplemented!`] macros, but will produce a panic when used to
+/// parse input, not immediately when invoked.
+///
+/// This function is useful when developing your parser, allowing you to prototype and run parts of your parser without
+/// committing to implementing the entire thing immediately.
+///
+/// The output type of this parser is whatever you want it to be: it'll never produce output!
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use chumsky::prelude::*;
+/// let int = just::<_, _, extra::Err<Simple<char>>>("0x").ignore_then(todo())
+///     .or(just("0b").ignore_then(text::digits(2).to_slice()))
+///     .or(text::int(10).to_slice());
+///
+/// // Decimal numbers are parsed
+/// assert_eq!(int.parse("12").into_result(), Ok("12"));
+/// // Binary numbers are parsed
+/// assert_eq!(int.parse("0b00101").into_result(), Ok("00101"));
+/// // Parsing hexidecimal numbers results in a panic because the parser is unimplemented
+/// int.parse("0xd4");
+/// ```
+#[track_caller]
+pub fn todo<'src, I: Input<'src>, O, E: ParserExtra<'src, I>>() -> Todo<I, O, E> {
+    Todo {
+        location: *Location::caller(),
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+impl<'src, I, O, E> Parser<'src, I, O, E> for Todo<I, O, E>
+where
+    I: Input<'src>,
+    E: ParserExtra<'src, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, _inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, O> {
+        todo!(
+            "Attempted to use an unimplemented parser at {}",
+            self.location
+        )
+    }
 
     go_extra!(O);
 }
@@ -909,24 +1707,80 @@ macro_rules! impl_choice_for_tuple {
             #[inline]
             fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, O> {
                 let before = inp.save();
+                // Each choice is its own cut scope: a cut from an earlier sibling must not stop us backtracking here
+                let outer_cut = core::mem::replace(&mut inp.errors.cut, false);
 
                 let Choice { parsers: ($Head, $($X,)*), .. } = self;
 
                 match $Head.go::<M>(inp) {
-                    Ok(out) => return Ok(out),
+                    Ok(out) => {
+                        inp.errors.cut = outer_cut;
+                        return Ok(out);
+                    }
+                    // A cut was passed, so other alternatives must not be tried
+                    Err(()) if inp.errors.cut => return Err(()),
                     Err(()) => inp.rewind(before.clone()),
                 }
 
                 $(
                     match $X.go::<M>(inp) {
-                        Ok(out) => return Ok(out),
+                        Ok(out) => {
+                            inp.errors.cut = outer_cut;
+                            return Ok(out);
+                        }
+                        Err(()) if inp.errors.cut => return Err(()),
                         Err(()) => inp.rewind(before.clone()),
                     }
                 )*
 
+                inp.errors.cut = outer_cut;
                 Err(())
             }
 
+            #[cfg(feature = "grammar")]
+            fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+            where
+                I::Token: fmt::Debug,
+            {
+                let Choice { parsers: ($Head, $($X,)*), .. } = self;
+                grammar::Expr::choice([$Head.node_info(scope), $($X.node_info(scope)),*])
+            }
+
+            #[cfg(feature = "generate")]
+            fn gen_tokens(
+                &self,
+                scope: &mut generate::Scope<'_, I::Token>,
+            ) -> Result<(), generate::GenerateError>
+            where
+                I::Token: Clone,
+            {
+                let Choice { parsers: ($Head, $($X,)*), .. } = self;
+                let n = [stringify!($Head), $(stringify!($X)),*].len();
+                scope.choice(n, |i, scope| {
+                    let mut j = 0;
+                    if i == j {
+                        return $Head.gen_tokens(scope);
+                    }
+                    $(
+                        j += 1;
+                        if i == j {
+                            return $X.gen_tokens(scope);
+                        }
+                    )*
+                    unreachable!()
+                })
+            }
+
+            fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+            where
+                I::Token: Clone,
+            {
+                let Choice { parsers: ($Head, $($X,)*), .. } = self;
+                let mut prefixes = $Head.prefixes()?;
+                $(prefixes.extend($X.prefixes()?);)*
+                Some(prefixes)
+            }
+
             go_extra!(O);
         }
     };
@@ -942,6 +1796,32 @@ macro_rules! impl_choice_for_tuple {
                 self.parsers.0.go::<M>(inp)
             }
 
+            #[cfg(feature = "grammar")]
+            fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+            where
+                I::Token: fmt::Debug,
+            {
+                self.parsers.0.node_info(scope)
+            }
+
+            #[cfg(feature = "generate")]
+            fn gen_tokens(
+                &self,
+                scope: &mut generate::Scope<'_, I::Token>,
+            ) -> Result<(), generate::GenerateError>
+            where
+                I::Token: Clone,
+            {
+                self.parsers.0.gen_tokens(scope)
+            }
+
+            fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+            where
+                I::Token: Clone,
+            {
+                self.parsers.0.prefixes()
+            }
+
             go_extra!(O);
         }
     };
@@ -964,16 +1844,55 @@ where
             Err(())
         } else {
             let before = inp.save();
+            let outer_cut = core::mem::replace(&mut inp.errors.cut, false);
             for parser in self.parsers.iter() {
                 inp.rewind(before.clone());
-                if let Ok(out) = parser.go::<M>(inp) {
-                    return Ok(out);
+                match parser.go::<M>(inp) {
+                    Ok(out) => {
+                        inp.errors.cut = outer_cut;
+                        return Ok(out);
+                    }
+                    Err(()) if inp.errors.cut => return Err(()),
+                    Err(()) => {}
                 }
             }
+            inp.errors.cut = outer_cut;
             Err(())
         }
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::choice(self.parsers.iter().map(|parser| parser.node_info(scope)))
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        scope.choice(self.parsers.len(), |i, scope| {
+            self.parsers[i].gen_tokens(scope)
+        })
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.parsers
+            .iter()
+            .map(|parser| parser.prefixes())
+            .collect::<Option<Vec<_>>>()
+            .map(|prefixes| prefixes.into_iter().flatten().collect())
+    }
+
     go_extra!(O);
 }
 
@@ -987,6 +1906,38 @@ where
     fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, O> {
         choice(&self.parsers[..]).go::<M>(inp)
     }
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::choice(self.parsers.iter().map(|parser| parser.node_info(scope)))
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        scope.choice(self.parsers.len(), |i, scope| {
+            self.parsers[i].gen_tokens(scope)
+        })
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.parsers
+            .iter()
+            .map(|parser| parser.prefixes())
+            .collect::<Option<Vec<_>>>()
+            .map(|prefixes| prefixes.into_iter().flatten().collect())
+    }
+
     go_extra!(O);
 }
 
@@ -1000,6 +1951,252 @@ where
     fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, O> {
         choice(&self.parsers[..]).go::<M>(inp)
     }
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::choice(self.parsers.iter().map(|parser| parser.node_info(scope)))
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        scope.choice(self.parsers.len(), |i, scope| {
+            self.parsers[i].gen_tokens(scope)
+        })
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        self.parsers
+            .iter()
+            .map(|parser| parser.prefixes())
+            .collect::<Option<Vec<_>>>()
+            .map(|prefixes| prefixes.into_iter().flatten().collect())
+    }
+
+    go_extra!(O);
+}
+
+/// See [`choice_dispatch`].
+#[derive(Clone)]
+pub struct ChoiceDispatch<A, T> {
+    parsers: Vec<A>,
+    nodes: Vec<DispatchNode<T>>,
+}
+
+// A node of the trie that `ChoiceDispatch` walks to decide which alternatives to try.
+#[derive(Clone)]
+struct DispatchNode<T> {
+    next: HashMap<T, usize>,
+    // Indices of the alternatives that might match when the walk ends here, in order
+    candidates: Vec<usize>,
+}
+
+impl<T> DispatchNode<T> {
+    fn new(candidates: Vec<usize>) -> Self {
+        Self {
+            next: HashMap::new(),
+            candidates,
+        }
+    }
+}
+
+/// Parse using one of many parsers, like [`choice`], but only trying the alternatives that could match the upcoming
+/// tokens.
+///
+/// Alternatives that can only begin with a known sequence of tokens, such as [`just`], [`one_of`] and
+/// [`text::keyword`] (and parsers that start with them, like `just("let").then(ident)`), are indexed in a trie by
+/// those tokens. When parsing, the upcoming tokens are looked up in the trie and only the alternatives that could
+/// match them are tried, in their original order. Alternatives that don't start with a known sequence of tokens, such
+/// as [`text::ident`], are always tried. This makes choosing between many keywords or symbols much faster than
+/// [`choice`], which tries every alternative in turn.
+///
+/// Keywords are indexed by their text when it's ASCII and given as a `&str`, `String`, `&[u8]` or `Vec<u8>`.
+///
+/// The output is the same as that of [`choice`]. However, because alternatives that can't match are skipped, the
+/// errors they would have produced are too: a failed parse only reports what the alternatives that were tried
+/// expected.
+///
+/// The output type of this parser is the output type of the inner parsers.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// #[derive(Clone, Debug, PartialEq)]
+/// enum Token<'src> {
+///     Select,
+///     From,
+///     Where,
+///     Star,
+///     Comma,
+///     Ident(&'src str),
+/// }
+///
+/// let token = choice_dispatch([
+///     text::ascii::keyword::<_, _, extra::Err<Simple<char>>>("select").to(Token::Select).boxed(),
+///     text::ascii::keyword("from").to(Token::From).boxed(),
+///     text::ascii::keyword("where").to(Token::Where).boxed(),
+///     just('*').to(Token::Star).boxed(),
+///     just(',').to(Token::Comma).boxed(),
+///     // Identifiers don't start with a known token, so this is tried whenever the keywords above don't match
+///     text::ascii::ident().map(Token::Ident).boxed(),
+/// ]);
+///
+/// let tokens = token.padded().repeated().collect::<Vec<_>>();
+///
+/// use Token::*;
+/// assert_eq!(
+///     tokens.parse("select a, b from foo where fromage").into_result(),
+///     Ok(vec![Select, Ident("a"), Comma, Ident("b"), From, Ident("foo"), Where, Ident("fromage")]),
+/// );
+/// ```
+pub fn choice_dispatch<'src, I, O, E, A>(
+    parsers: impl IntoIterator<Item = A>,
+) -> ChoiceDispatch<A, I::Token>
+where
+    I: Input<'src>,
+    E: ParserExtra<'src, I>,
+    A: Parser<'src, I, O, E>,
+    I::Token: Clone + Hash + Eq,
+{
+    let parsers = parsers.into_iter().collect::<Vec<_>>();
+
+    let mut nodes = vec![DispatchNode::new(Vec::new())];
+    let mut parents = vec![0];
+    for (i, parser) in parsers.iter().enumerate() {
+        let prefixes = parser
+            .prefixes()
+            .filter(|prefixes| !prefixes.is_empty() && prefixes.iter().all(|p| !p.is_empty()));
+        let Some(prefixes) = prefixes else {
+            // Nothing is known about how this alternative starts, so it must always be tried
+            nodes[0].candidates.push(i);
+            continue;
+        };
+        for prefix in prefixes {
+            let mut node = 0;
+            for tok in prefix {
+                node = match nodes[node].next.get(&tok) {
+                    Some(next) => *next,
+                    None => {
+                        nodes.push(DispatchNode::new(Vec::new()));
+                        parents.push(node);
+                        let next = nodes.len() - 1;
+                        nodes[node].next.insert(tok, next);
+                        next
+                    }
+                };
+            }
+            nodes[node].candidates.push(i);
+        }
+    }
+
+    // Alternatives that match at a node might also match at any of its descendants. Parents are always created
+    // before their children, so a single pass is enough.
+    for node in 1..nodes.len() {
+        let mut candidates = nodes[parents[node]].candidates.clone();
+        candidates.append(&mut nodes[node].candidates);
+        candidates.sort_unstable();
+        candidates.dedup();
+        nodes[node].candidates = candidates;
+    }
+
+    ChoiceDispatch { parsers, nodes }
+}
+
+impl<'src, A, I, O, E> Parser<'src, I, O, E> for ChoiceDispatch<A, I::Token>
+where
+    A: Parser<'src, I, O, E>,
+    I: Input<'src>,
+    E: ParserExtra<'src, I>,
+    I::Token: Hash + Eq,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, O> {
+        let before = inp.save();
+        let outer_alt = inp.take_alt();
+
+        // Find the deepest node of the trie that matches the upcoming tokens
+        inp.skip_trivia();
+        let mut node = 0;
+        inp.peek_while(|tok| match self.nodes[node].next.get(tok) {
+            Some(next) => {
+                node = *next;
+                true
+            }
+            None => false,
+        });
+        let candidates = &self.nodes[node].candidates;
+
+        if candidates.len() == self.parsers.len() {
+            inp.rewind(before);
+            inp.errors.alt = outer_alt;
+            return choice(&self.parsers[..]).go::<M>(inp);
+        } else if candidates.is_empty() {
+            // No alternative can match, so report the token that we found instead
+            let start = inp.save();
+            let found = inp.next_maybe_inner();
+            let span = inp.span_since(start.cursor());
+            inp.rewind(start);
+            inp.errors.alt = outer_alt;
+            inp.add_alt([], found.map(|f| f.into()), span);
+            return Err(());
+        }
+
+        inp.rewind(before.clone());
+        inp.errors.alt = outer_alt;
+        let outer_cut = core::mem::replace(&mut inp.errors.cut, false);
+        for &i in candidates {
+            match self.parsers[i].go::<M>(inp) {
+                Ok(out) => {
+                    inp.errors.cut = outer_cut;
+                    return Ok(out);
+                }
+                Err(()) if inp.errors.cut => return Err(()),
+                Err(()) => inp.rewind(before.clone()),
+            }
+        }
+        inp.errors.cut = outer_cut;
+        Err(())
+    }
+
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::choice(self.parsers.iter().map(|parser| parser.node_info(scope)))
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        scope.choice(self.parsers.len(), |i, scope| {
+            self.parsers[i].gen_tokens(scope)
+        })
+    }
+
+    fn prefixes(&self) -> Option<Vec<Vec<I::Token>>>
+    where
+        I::Token: Clone,
+    {
+        choice(&self.parsers[..]).prefixes()
+    }
+
     go_extra!(O);
 }
 
@@ -1038,6 +2235,28 @@ where
         Ok(M::array(unsafe { MaybeUninitExt::array_assume_init(arr) }))
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        grammar::Expr::seq(self.parsers.iter().map(|parser| parser.node_info(scope)))
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        for parser in self.parsers.iter() {
+            parser.gen_tokens(scope)?;
+        }
+        Ok(())
+    }
+
     go_extra!([O; N]);
 }
 
@@ -1095,6 +2314,28 @@ macro_rules! impl_group_for_tuple {
                 Ok(flatten_map!(<M> $($X)*))
             }
 
+            #[cfg(feature = "grammar")]
+            fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+            where
+                I::Token: fmt::Debug,
+            {
+                let Group { parsers: ($($X,)*) } = self;
+                grammar::Expr::seq([$($X.node_info(scope)),*])
+            }
+
+            #[cfg(feature = "generate")]
+            fn gen_tokens(
+                &self,
+                scope: &mut generate::Scope<'_, I::Token>,
+            ) -> Result<(), generate::GenerateError>
+            where
+                I::Token: Clone,
+            {
+                let Group { parsers: ($($X,)*) } = self;
+                $($X.gen_tokens(scope)?;)*
+                Ok(())
+            }
+
             go_extra!(($($O,)*));
         }
     };
@@ -1128,3 +2369,309 @@ impl_group_for_tuple! {
     Y_ OY
     Z_ OZ
 }
+
+/// See [`permutation`].
+#[derive(Copy, Clone)]
+pub struct Permutation<T> {
+    parsers: T,
+}
+
+/// Parse using a tuple of many parsers in any order, producing a tuple of outputs in the order of the parsers
+/// (rather than the order in which they appeared in the input).
+///
+/// Each parser must succeed exactly once. This is useful for things like configuration blocks, attribute lists or
+/// CSS-like declarations, where the same set of items may be given in any order.
+///
+/// If some items are missing, the error lists what each of the missing items expected at the point where parsing
+/// stopped (consider using [`Parser::labelled`] on each item to give them useful names). If an item appears more than
+/// once, the error points at the repeated occurrence and is labelled with a [`DuplicateItem`] naming the item by the
+/// tokens it begins with (or, if those aren't known, by its index).
+///
+/// See [`Permutation::optional`] for a variant that allows items to be absent.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let attr = |name| just::<_, _, extra::Err<Rich<char>>>(name)
+///     .ignore_then(just('='))
+///     .ignore_then(text::int(10))
+///     .padded();
+///
+/// let attrs = permutation((attr("x"), attr("y"), attr("z")));
+///
+/// assert_eq!(attrs.parse("x=1 y=2 z=3").into_result(), Ok(("1", "2", "3")));
+/// assert_eq!(attrs.parse("z=3 x=1 y=2").into_result(), Ok(("1", "2", "3")));
+/// // Missing item
+/// assert!(attrs.parse("z=3 x=1").has_errors());
+/// // Duplicated item
+/// let errs = attrs.parse("z=3 x=1 z=4 y=2").into_errors();
+/// assert_eq!(errs[0].span().into_range(), 8..12);
+/// ```
+pub const fn permutation<T>(parsers: T) -> Permutation<T> {
+    Permutation { parsers }
+}
+
+impl<T> Permutation<T> {
+    /// Allow any of the items to be absent, producing a tuple of [`Option`]s.
+    ///
+    /// Items may still appear at most once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let flag = |name| text::ascii::keyword::<_, _, extra::Err<Rich<char>>>(name).padded();
+    ///
+    /// let flags = permutation((flag("pub"), flag("const"), flag("unsafe"))).optional();
+    ///
+    /// assert_eq!(flags.parse("unsafe pub").into_result(), Ok((Some("pub"), None, Some("unsafe"))));
+    /// assert_eq!(flags.parse("").into_result(), Ok((None, None, None)));
+    /// assert!(flags.parse("pub pub").has_errors());
+    /// ```
+    pub fn optional(self) -> OptionalPermutation<T> {
+        OptionalPermutation {
+            parsers: self.parsers,
+        }
+    }
+}
+
+/// See [`Permutation::optional`].
+#[derive(Copy, Clone)]
+pub struct OptionalPermutation<T> {
+    parsers: T,
+}
+
+/// The label of the error produced when an item of a [`permutation`] appears more than once.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DuplicateItem<T> {
+    /// The index of the repeated item within the permutation.
+    pub index: usize,
+    /// The tokens that the repeated item always begins with, if they are known (such as the name of a
+    /// `just("x=").then(...)` item).
+    pub prefix: Option<Vec<T>>,
+}
+
+// Parses the items of a permutation in any order, producing a tuple of `Option`s. Repeated items produce an error.
+macro_rules! permutation_go {
+    ($inp:ident, $M:ident, $($X:ident $O:ident)*) => {{
+        $(let mut $O = None;)*
+
+        // Each attempt is a cut scope, just like an alternative of `choice`
+        let outer_cut = core::mem::replace(&mut $inp.errors.cut, false);
+        #[allow(unused_assignments)]
+        'items: loop {
+            // Once every item has been parsed, stop: the input that follows belongs to whatever comes next
+            if $($O.is_some())&&* {
+                break;
+            }
+
+            let before = $inp.save();
+            $(
+                if $O.is_none() {
+                    match $X.go::<$M>($inp) {
+                        Ok(out) => {
+                            $O = Some(out);
+                            continue 'items;
+                        }
+                        Err(()) if $inp.errors.cut => return Err(()),
+                        Err(()) => $inp.rewind(before.clone()),
+                    }
+                }
+            )*
+
+            // None of the remaining items matched: if an item that we've already seen appears again, report it
+            let alt = $inp.errors.alt.take();
+            let mut index = 0;
+            $(
+                if $O.is_some() {
+                    let result = $X.go::<Check>($inp);
+                    $inp.errors.cut = false;
+                    if result.is_ok() && *before.cursor() != $inp.cursor() {
+                        let span = $inp.span_since(before.cursor());
+                        $inp.rewind(before);
+                        let found = $inp.peek_maybe();
+                        $inp.errors.alt = None;
+                        let prefix = $X
+                            .prefixes()
+                            .and_then(|prefixes| <[_; 1]>::try_from(prefixes).ok())
+                            .map(|[prefix]| prefix)
+                            .filter(|prefix| !prefix.is_empty());
+                        $inp.add_alt([DuplicateItem { index, prefix }], found, span);
+                        $inp.errors.cut = outer_cut;
+                        return Err(());
+                    }
+                    $inp.rewind(before.clone());
+                }
+                index += 1;
+            )*
+            $inp.errors.alt = alt;
+            break;
+        }
+        $inp.errors.cut = outer_cut;
+
+        ($($O,)*)
+    }};
+}
+
+macro_rules! impl_permutation_for_tuple {
+    () => {};
+    ($head:ident $ohead:ident $($X:ident $O:ident)*) => {
+        impl_permutation_for_tuple!($($X $O)*);
+        impl_permutation_for_tuple!(~ $head $ohead $($X $O)*);
+    };
+    (~ $($X:ident $O:ident)*) => {
+        #[allow(unused_variables, non_snake_case)]
+        impl<'src, I, E, $($X),*, $($O),*> Parser<'src, I, ($($O,)*), E> for Permutation<($($X,)*)>
+        where
+            I: Input<'src>,
+            E: ParserExtra<'src, I>,
+            I::Token: Clone,
+            E::Error: LabelError<'src, I, DuplicateItem<I::Token>>,
+            $($X: Parser<'src, I, $O, E>),*
+        {
+            #[inline]
+            fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, ($($O,)*)> {
+                let Permutation { parsers: ($($X,)*) } = self;
+
+                let ($($O,)*) = permutation_go!(inp, M, $($X $O)*);
+
+                $(
+                    // At least one required item is missing: the alternative errors describe what was expected
+                    let Some($O) = $O else { return Err(()) };
+                )*
+
+                Ok(flatten_map!(<M> $($O)*))
+            }
+
+            #[cfg(feature = "grammar")]
+            fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+            where
+                I::Token: fmt::Debug,
+            {
+                let Permutation { parsers: ($($X,)*) } = self;
+                grammar::Expr::Permutation {
+                    items: vec![$($X.node_info(scope)),*],
+                    optional: false,
+                }
+            }
+
+            #[cfg(feature = "generate")]
+            #[allow(unused_assignments)]
+            fn gen_tokens(
+                &self,
+                scope: &mut generate::Scope<'_, I::Token>,
+            ) -> Result<(), generate::GenerateError>
+            where
+                I::Token: Clone,
+            {
+                let Permutation { parsers: ($($X,)*) } = self;
+                let n = [$(stringify!($X)),*].len();
+                for i in scope.shuffled(n) {
+                    let mut j = 0;
+                    $(
+                        if i == j {
+                            $X.gen_tokens(scope)?;
+                        }
+                        j += 1;
+                    )*
+                }
+                Ok(())
+            }
+
+            go_extra!(($($O,)*));
+        }
+
+        #[allow(unused_variables, non_snake_case)]
+        impl<'src, I, E, $($X),*, $($O),*> Parser<'src, I, ($(Option<$O>,)*), E> for OptionalPermutation<($($X,)*)>
+        where
+            I: Input<'src>,
+            E: ParserExtra<'src, I>,
+            I::Token: Clone,
+            E::Error: LabelError<'src, I, DuplicateItem<I::Token>>,
+            $($X: Parser<'src, I, $O, E>),*
+        {
+            #[inline]
+            fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, ($(Option<$O>,)*)> {
+                let OptionalPermutation { parsers: ($($X,)*) } = self;
+
+                let ($($O,)*) = permutation_go!(inp, M, $($X $O)*);
+
+                $(
+                    let $O = match $O {
+                        Some(out) => M::map(out, Some),
+                        None => M::bind(|| None),
+                    };
+                )*
+
+                Ok(flatten_map!(<M> $($O)*))
+            }
+
+            #[cfg(feature = "grammar")]
+            fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+            where
+                I::Token: fmt::Debug,
+            {
+                let OptionalPermutation { parsers: ($($X,)*) } = self;
+                grammar::Expr::Permutation {
+                    items: vec![$($X.node_info(scope)),*],
+                    optional: true,
+                }
+            }
+
+            #[cfg(feature = "generate")]
+            #[allow(unused_assignments)]
+            fn gen_tokens(
+                &self,
+                scope: &mut generate::Scope<'_, I::Token>,
+            ) -> Result<(), generate::GenerateError>
+            where
+                I::Token: Clone,
+            {
+                let OptionalPermutation { parsers: ($($X,)*) } = self;
+                let n = [$(stringify!($X)),*].len();
+                for i in scope.shuffled(n) {
+                    let mut j = 0;
+                    $(
+                        if i == j {
+                            scope.optional(|scope| $X.gen_tokens(scope))?;
+                        }
+                        j += 1;
+                    )*
+                }
+                Ok(())
+            }
+
+            go_extra!(($(Option<$O>,)*));
+        }
+    };
+}
+
+impl_permutation_for_tuple! {
+    A_ OA
+    B_ OB
+    C_ OC
+    D_ OD
+    E_ OE
+    F_ OF
+    G_ OG
+    H_ OH
+    I_ OI
+    J_ OJ
+    K_ OK
+    L_ OL
+    M_ OM
+    N_ ON
+    O_ OO
+    P_ OP
+    Q_ OQ
+    R_ OR
+    S_ OS
+    T_ OT
+    U_ OU
+    V_ OV
+    W_ OW
+    X_ OX
+    Y_ OY
+    Z_ OZ
+}
@@ -130,6 +130,12 @@ impl<P: ?Sized> Recursive<P> {
                 .expect("Recursive parser used before being defined"),
         }
     }
+
+    /// An identity shared by all clones of this parser, used to name it when describing a grammar.
+    #[cfg(feature = "grammar")]
+    fn key(&self) -> usize {
+        Rc::as_ptr(&self.parser()) as *const () as usize
+    }
 }
 
 impl<P: ?Sized> Clone for Recursive<P> {
@@ -173,6 +179,33 @@ where
         })
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        scope.recursive(self.key(), |scope| {
+            self.parser()
+                .inner
+                .get()
+                .map(|parser| parser.node_info(scope))
+        })
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        scope.recurse(|scope| match self.parser().inner.get() {
+            Some(parser) => parser.gen_tokens(scope),
+            None => Err(generate::unsupported::<Self>()),
+        })
+    }
+
     go_extra!(O);
 }
 
@@ -186,6 +219,25 @@ where
         recurse(move || M::invoke(&*self.parser(), inp))
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        scope.recursive(self.key(), |scope| Some(self.parser().node_info(scope)))
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        scope.recurse(|scope| self.parser().gen_tokens(scope))
+    }
+
     go_extra!(O);
 }
 
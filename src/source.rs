@@ -0,0 +1,549 @@
+//! Types for managing many named sources, and for resolving spans within them.
+//!
+//! *"The ships hung in the sky in much the same way that bricks don't."*
+//!
+//! Compilers rarely deal with just one file. A [`SourceSet`] owns the name and text of every source that takes part in
+//! a compilation, handing out a [`SourceId`] for each one. Parsing a [`Source`] through [`Source::input`] produces
+//! spans of type [`SourceSpan`] (that is, `(SourceId, SimpleSpan)`), which [`SourceSet::locate`] can later resolve
+//! back to a file name, the text that was spanned, and the line and column at which it lies.
+//!
+//! Preprocessors that implement `#include` or macro expansion can stitch pieces of several sources into one logical
+//! input with a [`Splice`]. Spans produced while parsing a splice still point at the source that each piece came from,
+//! and [`Splice::with_include_contexts`] attaches the chain of inclusion sites to an error of any type that accepts
+//! [`IncludedFrom`] labels.
+//!
+//! # Examples
+//!
+//! ```
+//! # use chumsky::{prelude::*, source::{SourceSet, SourceSpan}};
+//! let mut sources = SourceSet::new();
+//! let a = sources.add("a.txt", "foo bar");
+//! let b = sources.add("b.txt", "foo\n  bar 42");
+//!
+//! let words = text::ascii::ident::<_, extra::Err<Rich<char, SourceSpan>>>()
+//!     .padded()
+//!     .repeated()
+//!     .then_ignore(end());
+//!
+//! assert!(words.parse(sources[a].input()).into_result().is_ok());
+//!
+//! let errs = words.parse(sources[b].input()).into_errors();
+//! let loc = sources.locate(errs[0].span()).unwrap();
+//! assert_eq!(loc.source.name(), "b.txt");
+//! assert_eq!(loc.text(), "4");
+//! assert_eq!(loc.to_string(), "b.txt:2:7");
+//! ```
+
+use super::*;
+use alloc::{borrow::Cow, string::ToString};
+use input::{ChunkedStr, WithContext};
+use span::{LineCol, LineIndex};
+
+/// An identifier for a [`Source`] held by a [`SourceSet`].
+///
+/// Identifiers are only meaningful to the [`SourceSet`] that created them.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SourceId(usize);
+
+impl SourceId {
+    /// Get the index of the source within its [`SourceSet`], in the order in which sources were added.
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// A span within one of the sources of a [`SourceSet`].
+pub type SourceSpan = (SourceId, SimpleSpan);
+
+/// A named source, held by a [`SourceSet`].
+#[derive(Clone, Debug)]
+pub struct Source {
+    id: SourceId,
+    name: String,
+    text: String,
+    line_starts: Vec<usize>,
+}
+
+impl Source {
+    /// Get the identifier of this source.
+    pub fn id(&self) -> SourceId {
+        self.id
+    }
+
+    /// Get the name of this source (typically, its file path).
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the text of this source.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Get a [`LineIndex`] for this source.
+    ///
+    /// Line boundaries are found once, when the source is added, so this is cheap to call.
+    pub fn line_index(&self) -> LineIndex<'_> {
+        LineIndex::from_line_starts(&self.text, Cow::Borrowed(&self.line_starts))
+    }
+
+    /// Get an input for this source that produces [`SourceSpan`]s.
+    pub fn input(&self) -> WithContext<SourceSpan, &str> {
+        self.text.as_str().with_context(self.id)
+    }
+}
+
+/// A registry of named sources, each of which is given a [`SourceId`].
+///
+/// See the [module-level documentation](self) for more information.
+#[derive(Clone, Debug, Default)]
+pub struct SourceSet {
+    sources: Vec<Source>,
+}
+
+impl SourceSet {
+    /// Create a new, empty source set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a source with the given name and text, returning its identifier.
+    pub fn add(&mut self, name: impl Into<String>, text: impl Into<String>) -> SourceId {
+        let id = SourceId(self.sources.len());
+        let text = text.into();
+        self.sources.push(Source {
+            id,
+            name: name.into(),
+            line_starts: LineIndex::line_starts(&text),
+            text,
+        });
+        id
+    }
+
+    /// Get the source with the given identifier, if it exists.
+    pub fn get(&self, id: SourceId) -> Option<&Source> {
+        self.sources.get(id.0)
+    }
+
+    /// Find the first source with the given name.
+    pub fn find(&self, name: &str) -> Option<&Source> {
+        self.sources.iter().find(|source| source.name == name)
+    }
+
+    /// Iterate over the sources in this set, in the order in which they were added.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &Source> + '_ {
+        self.sources.iter()
+    }
+
+    /// The number of sources in this set.
+    pub fn len(&self) -> usize {
+        self.sources.len()
+    }
+
+    /// Returns `true` if this set holds no sources.
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    /// Resolve a span (such as that of a [`Rich`] error, or of one of its contexts) to a [`Location`].
+    ///
+    /// Both [`SourceSpan`]s and [`SpliceSpan`]s can be resolved.
+    ///
+    /// Returns `None` if the span's source is not part of this set. Offsets beyond the end of the source are treated
+    /// as pointing at its end.
+    pub fn locate<S>(&self, span: &S) -> Option<Location<'_>>
+    where
+        S: Span<Offset = usize>,
+        S::Context: Into<SourceId>,
+    {
+        let source = self.get(span.context().into())?;
+        let index = source.line_index();
+        let end = span.end().min(source.text.len());
+        let start = span.start().min(end);
+        Some(Location {
+            source,
+            span: SimpleSpan::from(start..end),
+            start: index.line_col(start),
+            end: index.line_col(end),
+        })
+    }
+}
+
+impl core::ops::Index<SourceId> for SourceSet {
+    type Output = Source;
+
+    fn index(&self, id: SourceId) -> &Self::Output {
+        self.get(id).expect("source is not part of this source set")
+    }
+}
+
+/// A span that has been resolved by [`SourceSet::locate`].
+///
+/// When displayed, this produces a one-based `name:line:col` string, as used by most compilers and editors.
+#[derive(Copy, Clone, Debug)]
+pub struct Location<'a> {
+    /// The source that the span lies within.
+    pub source: &'a Source,
+    /// The byte range of the span within the source.
+    pub span: SimpleSpan,
+    /// The zero-based line and column at which the span starts.
+    pub start: LineCol,
+    /// The zero-based line and column at which the span ends.
+    pub end: LineCol,
+}
+
+impl<'a> Location<'a> {
+    /// Get the text covered by the span.
+    pub fn text(&self) -> &'a str {
+        &self.source.text[self.span.into_range()]
+    }
+}
+
+impl fmt::Display for Location<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}",
+            self.source.name,
+            self.start.line + 1,
+            self.start.col + 1
+        )
+    }
+}
+
+/// The origin of a [`SpliceSpan`]: the segment of a [`Splice`] that the span starts in, and the source it came from.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Origin {
+    /// The source that the span lies within.
+    pub source: SourceId,
+    /// The index of the segment within the [`Splice`].
+    pub segment: usize,
+}
+
+impl From<Origin> for SourceId {
+    fn from(origin: Origin) -> Self {
+        origin.source
+    }
+}
+
+/// The span type produced when parsing a [`Splice`].
+///
+/// Offsets are byte offsets into the source given by the [`Origin`], not into the spliced input.
+pub type SpliceSpan = (Origin, SimpleSpan);
+
+/// A piece of a source that forms part of a [`Splice`].
+#[derive(Clone, Debug)]
+pub struct Segment<'src> {
+    source: &'src Source,
+    range: Range<usize>,
+    included_from: Option<(usize, SimpleSpan)>,
+}
+
+impl<'src> Segment<'src> {
+    /// Create a segment covering the given byte range of a source.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range does not lie on character boundaries within the source.
+    pub fn new(source: &'src Source, range: Range<usize>) -> Self {
+        assert!(
+            source.text.get(range.clone()).is_some(),
+            "segment range does not lie on character boundaries within the source",
+        );
+        Self {
+            source,
+            range,
+            included_from: None,
+        }
+    }
+
+    /// Create a segment covering the whole of a source.
+    pub fn whole(source: &'src Source) -> Self {
+        Self::new(source, 0..source.text.len())
+    }
+
+    /// Mark this segment as having been included by the segment at index `parent` of the splice, at the byte range
+    /// `site` of that segment's source (for example, the span of an `#include` directive or macro invocation).
+    pub fn included_from(self, parent: usize, site: Range<usize>) -> Self {
+        Self {
+            included_from: Some((parent, site.into())),
+            ..self
+        }
+    }
+
+    /// Get the source that this segment is a part of.
+    pub fn source(&self) -> &'src Source {
+        self.source
+    }
+
+    /// Get the byte range of the source covered by this segment.
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// Get the text of this segment.
+    pub fn text(&self) -> &'src str {
+        &self.source.text[self.range.clone()]
+    }
+}
+
+/// A sequence of [`Segment`]s that are parsed as a single, contiguous input.
+///
+/// Use [`Splice::input`] to parse the splice. The resulting input produces [`SpliceSpan`]s, which record the segment
+/// in which each span starts and which refer to offsets within the original sources. A span that ends in a later
+/// segment of the same source (such as one that covers an `#include` directive) extends to that point in the source;
+/// otherwise, it is cut short at the end of the segment that it starts in.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, source::{Segment, SourceSet, Splice, SpliceSpan}};
+/// let mut sources = SourceSet::new();
+/// let main = sources.add("main.c", "int a;\n#include \"b.h\"\nint c;\n");
+/// let b = sources.add("b.h", "int b;\nint 42;\n");
+///
+/// // Replace the `#include` directive with the contents of `b.h`
+/// let splice = Splice::new([
+///     Segment::new(&sources[main], 0..7),
+///     Segment::whole(&sources[b]).included_from(0, 7..21),
+///     Segment::new(&sources[main], 22..29),
+/// ]);
+///
+/// let decl = text::ascii::keyword::<_, _, extra::Err<Rich<char, SpliceSpan>>>("int")
+///     .ignore_then(text::ascii::ident().padded())
+///     .then_ignore(just(';'))
+///     .padded();
+/// let errs = decl.repeated().parse(splice.input()).into_errors();
+///
+/// let err = splice.with_include_contexts(errs[0].clone(), errs[0].span());
+/// assert_eq!(sources.locate(err.span()).unwrap().to_string(), "b.h:2:5");
+/// let (label, site) = err.contexts().next().unwrap();
+/// assert_eq!(label.to_string(), "included from main.c:2:1");
+/// assert_eq!(sources.locate(site).unwrap().to_string(), "main.c:2:1");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Splice<'src> {
+    segments: Vec<Segment<'src>>,
+    chunks: Vec<&'src str>,
+}
+
+impl<'src> Splice<'src> {
+    /// Create a splice from a sequence of segments.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no segments, or if a segment is included from a segment that does not come before it.
+    pub fn new(segments: impl IntoIterator<Item = Segment<'src>>) -> Self {
+        let segments: Vec<_> = segments.into_iter().collect();
+        assert!(
+            !segments.is_empty(),
+            "a splice must have at least one segment"
+        );
+        for (idx, segment) in segments.iter().enumerate() {
+            if let Some((parent, _)) = segment.included_from {
+                assert!(
+                    parent < idx,
+                    "segment {idx} is included from segment {parent}, which does not come before it",
+                );
+            }
+        }
+        let chunks = segments.iter().map(Segment::text).collect();
+        Self { segments, chunks }
+    }
+
+    /// Get the segments that make up this splice.
+    pub fn segments(&self) -> &[Segment<'src>] {
+        &self.segments
+    }
+
+    /// Get an input that parses the text of every segment in turn.
+    pub fn input(&self) -> SpliceInput<'_> {
+        let splice: &Splice<'_> = self;
+        SpliceInput {
+            splice,
+            text: ChunkedStr::new(&splice.chunks),
+        }
+    }
+
+    /// Iterate over the sites at which the given segment was included, innermost first.
+    pub fn include_chain(&self, segment: usize) -> impl Iterator<Item = SpliceSpan> + '_ {
+        let mut segment = self.segments.get(segment);
+        core::iter::from_fn(move || {
+            let (parent, site) = segment?.included_from?;
+            segment = self.segments.get(parent);
+            Some((
+                Origin {
+                    source: segment?.source.id,
+                    segment: parent,
+                },
+                site,
+            ))
+        })
+    }
+
+    /// Add the chain of inclusion sites of the segment in which an error occurred to the error's contexts, innermost
+    /// first, each labelled with an [`IncludedFrom`] giving the location of the site.
+    ///
+    /// `at` is the span of the error. This works with any error type that accepts [`IncludedFrom`] labels, such as
+    /// [`Rich`].
+    pub fn with_include_contexts<'a, E>(&'a self, mut err: E, at: &SpliceSpan) -> E
+    where
+        E: LabelError<'a, SpliceInput<'a>, IncludedFrom<'a>>,
+    {
+        for site in self.include_chain(at.0.segment) {
+            let source = self.segments[site.0.segment].source;
+            err.in_context(
+                IncludedFrom {
+                    source,
+                    site: site.1,
+                },
+                site,
+            );
+        }
+        err
+    }
+}
+
+/// The label given to the sites at which a segment of a [`Splice`] was included by [`Splice::with_include_contexts`].
+///
+/// When converted to a [`RichPattern`](crate::error::RichPattern), this reads `included from <name>:<line>:<col>`.
+/// Each site has its own label, so a chain that passes through the same source more than once keeps every site.
+#[derive(Copy, Clone, Debug)]
+pub struct IncludedFrom<'src> {
+    /// The source that contains the inclusion site.
+    pub source: &'src Source,
+    /// The byte range of the inclusion site within the source.
+    pub site: SimpleSpan,
+}
+
+impl fmt::Display for IncludedFrom<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let index = self.source.line_index();
+        let location = Location {
+            source: self.source,
+            span: self.site,
+            start: index.line_col(self.site.start),
+            end: index.line_col(self.site.end),
+        };
+        write!(f, "included from {location}")
+    }
+}
+
+impl<T> From<IncludedFrom<'_>> for crate::error::RichPattern<'_, T> {
+    fn from(label: IncludedFrom<'_>) -> Self {
+        Self::Label(Cow::Owned(label.to_string()))
+    }
+}
+
+/// Input type for parsing a [`Splice`]. See [`Splice::input`].
+pub struct SpliceInput<'src> {
+    splice: &'src Splice<'src>,
+    text: ChunkedStr<'src>,
+}
+
+impl<'src> SpliceInput<'src> {
+    // Translate an offset into the spliced text to the segment it lies within and an offset into that segment's source
+    fn origin(&mut self, offset: usize) -> (usize, usize) {
+        let idx = self
+            .text
+            .locate(offset)
+            .unwrap_or(self.splice.segments.len() - 1);
+        let segment = &self.splice.segments[idx];
+        let within = (offset - self.text.chunk_start(idx)).min(segment.range.len());
+        (idx, segment.range.start + within)
+    }
+}
+
+impl<'src> Input<'src> for SpliceInput<'src> {
+    type Cursor = usize;
+    type Span = SpliceSpan;
+
+    type Token = char;
+    type MaybeToken = char;
+
+    type Cache = Self;
+
+    #[inline]
+    fn begin(self) -> (Self::Cursor, Self::Cache) {
+        (0, self)
+    }
+
+    #[inline]
+    fn cursor_location(cursor: &Self::Cursor) -> usize {
+        *cursor
+    }
+
+    #[inline]
+    unsafe fn next_maybe(
+        this: &mut Self::Cache,
+        cursor: &mut Self::Cursor,
+    ) -> Option<Self::MaybeToken> {
+        ChunkedStr::next_maybe(&mut this.text, cursor)
+    }
+
+    #[inline]
+    unsafe fn span(this: &mut Self::Cache, range: Range<&Self::Cursor>) -> Self::Span {
+        let (segment, start) = this.origin(*range.start);
+        let source = this.splice.segments[segment].source.id;
+        let end = if *range.end > *range.start {
+            // Find the segment that the last character of the span lies within
+            let (end_segment, end) = this.origin(*range.end - 1);
+            let end_segment = &this.splice.segments[end_segment];
+            let last_len = end_segment.source.text[end..]
+                .chars()
+                .next()
+                .map_or(0, char::len_utf8);
+            if end_segment.source.id == source && end >= start {
+                end + last_len
+            } else {
+                this.splice.segments[segment].range.end
+            }
+        } else {
+            start
+        };
+        (Origin { source, segment }, SimpleSpan::from(start..end))
+    }
+}
+
+impl<'src> ExactSizeInput<'src> for SpliceInput<'src> {
+    #[inline]
+    unsafe fn span_from(this: &mut Self::Cache, range: RangeFrom<&Self::Cursor>) -> Self::Span {
+        let len = this.text.len();
+        Self::span(this, range.start..&len)
+    }
+}
+
+impl<'src> ValueInput<'src> for SpliceInput<'src> {
+    #[inline]
+    unsafe fn next(this: &mut Self::Cache, cursor: &mut Self::Cursor) -> Option<Self::Token> {
+        Self::next_maybe(this, cursor)
+    }
+}
+
+impl Sealed for SpliceInput<'_> {}
+impl<'src> StrInput<'src> for SpliceInput<'src> {
+    #[doc(hidden)]
+    fn stringify(slice: Self::Slice) -> String {
+        slice.into_owned()
+    }
+}
+
+impl<'src> SliceInput<'src> for SpliceInput<'src> {
+    type Slice = Cow<'src, str>;
+
+    #[inline]
+    fn full_slice(this: &mut Self::Cache) -> Self::Slice {
+        ChunkedStr::full_slice(&mut this.text)
+    }
+
+    #[inline]
+    unsafe fn slice(this: &mut Self::Cache, range: Range<&Self::Cursor>) -> Self::Slice {
+        ChunkedStr::slice(&mut this.text, range)
+    }
+
+    #[inline]
+    unsafe fn slice_from(this: &mut Self::Cache, from: RangeFrom<&Self::Cursor>) -> Self::Slice {
+        ChunkedStr::slice_from(&mut this.text, from)
+    }
+}
@@ -5,6 +5,7 @@
 //! You can use the [`Span`] trait to connect up chumsky to your compiler's knowledge of the input source.
 
 use super::*;
+use alloc::borrow::Cow;
 
 /// A trait that describes a span over a particular range of inputs.
 ///
@@ -244,3 +245,279 @@ impl<T: Clone> Span for std::range::Range<T> {
         self.end.clone()
     }
 }
+
+/// The unit in which a [`LineIndex`] measures columns.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ColumnUnit {
+    /// Columns are measured in UTF-8 bytes.
+    Bytes,
+    /// Columns are measured in unicode scalar values (i.e: [`char`]s).
+    #[default]
+    Chars,
+    /// Columns are measured in UTF-16 code units, as required by the Language Server Protocol.
+    Utf16,
+    /// Columns are measured in extended grapheme clusters, which most closely matches what a user would consider to
+    /// be a single character.
+    Graphemes,
+}
+
+impl ColumnUnit {
+    fn width(&self, s: &str) -> usize {
+        match self {
+            Self::Bytes => s.len(),
+            Self::Chars => s.chars().count(),
+            Self::Utf16 => s.chars().map(char::len_utf16).sum(),
+            Self::Graphemes => 1,
+        }
+    }
+}
+
+/// A zero-based line and column pair, as produced by [`LineIndex`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LineCol {
+    /// The line, starting from zero.
+    pub line: usize,
+    /// The column, starting from zero.
+    pub col: usize,
+}
+
+/// A table that maps byte offsets within a `&str` to lines and columns (and back again).
+///
+/// Lines are separated by `'\n'` (so `"\r\n"` is also handled, with the `'\r'` belonging to the end of a line).
+/// Columns are measured in a configurable [`ColumnUnit`], [`ColumnUnit::Chars`] being the default.
+///
+/// By default, a tab counts as a single column. If a tab width is set with [`LineIndex::with_tab_width`], tabs
+/// instead advance the column to the next multiple of the tab width, as they would in an editor.
+///
+/// A `LineIndex` can be used as (or held within) parser state, allowing [`MapExtra::line_col`] to find the position
+/// of a parser's output. See [`AsLineIndex`].
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::span::{ColumnUnit, LineCol, LineIndex};
+/// let src = "fn main() {\n\tlet 🐟 = 42;\n}";
+/// let index = LineIndex::new(src);
+///
+/// let offset = src.find('=').unwrap();
+/// assert_eq!(index.line_col(offset), LineCol { line: 1, col: 7 });
+/// assert_eq!(index.line_col_in(offset, ColumnUnit::Bytes), LineCol { line: 1, col: 10 });
+/// assert_eq!(index.line_col_in(offset, ColumnUnit::Utf16), LineCol { line: 1, col: 8 });
+/// assert_eq!(index.clone().with_tab_width(4).line_col(offset), LineCol { line: 1, col: 10 });
+///
+/// assert_eq!(index.offset(LineCol { line: 1, col: 7 }), Some(offset));
+/// ```
+#[derive(Clone, Debug)]
+pub struct LineIndex<'a> {
+    src: &'a str,
+    // The byte offset at which each line starts
+    line_starts: Cow<'a, [usize]>,
+    unit: ColumnUnit,
+    tab_width: usize,
+}
+
+impl<'a> LineIndex<'a> {
+    /// Create a new line index for the given source text.
+    pub fn new(src: &'a str) -> Self {
+        Self::from_line_starts(src, Cow::Owned(Self::line_starts(src)))
+    }
+
+    // Create a line index from line starts computed ahead of time by `LineIndex::line_starts`
+    pub(crate) fn from_line_starts(src: &'a str, line_starts: Cow<'a, [usize]>) -> Self {
+        Self {
+            src,
+            line_starts,
+            unit: ColumnUnit::default(),
+            tab_width: 1,
+        }
+    }
+
+    pub(crate) fn line_starts(src: &str) -> Vec<usize> {
+        core::iter::once(0)
+            .chain(src.match_indices('\n').map(|(i, _)| i + 1))
+            .collect()
+    }
+
+    /// Set the unit in which columns are measured.
+    pub fn with_unit(self, unit: ColumnUnit) -> Self {
+        Self { unit, ..self }
+    }
+
+    /// Set the width of a tab, in columns.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tab_width` is zero.
+    pub fn with_tab_width(self, tab_width: usize) -> Self {
+        assert!(tab_width > 0, "tab width must be non-zero");
+        Self { tab_width, ..self }
+    }
+
+    /// Get the source text that this index was created from.
+    pub fn source(&self) -> &'a str {
+        self.src
+    }
+
+    /// Get the number of lines in the source text.
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Get the byte range covered by the given line, excluding its trailing newline.
+    pub fn line_span(&self, line: usize) -> Option<SimpleSpan> {
+        let start = *self.line_starts.get(line)?;
+        let end = self
+            .line_starts
+            .get(line + 1)
+            .map_or(self.src.len(), |next| next - 1);
+        Some((start..end).into())
+    }
+
+    /// Get the line and column of the given byte offset, measuring columns in the index's [`ColumnUnit`].
+    ///
+    /// Offsets past the end of the source text are treated as pointing at its end, and offsets that lie within a
+    /// character are treated as pointing at the start of it.
+    pub fn line_col(&self, offset: usize) -> LineCol {
+        self.line_col_in(offset, self.unit)
+    }
+
+    /// Get the line and column of the given byte offset, measuring columns in the given [`ColumnUnit`].
+    pub fn line_col_in(&self, offset: usize, unit: ColumnUnit) -> LineCol {
+        let mut offset = offset.min(self.src.len());
+        while !self.src.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let line = self.line_starts.partition_point(|start| *start <= offset) - 1;
+        let mut col = 0;
+        self.walk_line(line, unit, |start, _, width| {
+            if start < offset {
+                col += width;
+                true
+            } else {
+                false
+            }
+        });
+        LineCol { line, col }
+    }
+
+    /// Get the byte offset of the given line and column, measuring columns in the index's [`ColumnUnit`].
+    ///
+    /// Returns `None` if the line does not exist or the column lies beyond the end of the line. Columns that lie
+    /// within a character (such as the middle of a tab or of a multi-unit character) resolve to the start of it.
+    pub fn offset(&self, line_col: LineCol) -> Option<usize> {
+        self.offset_in(line_col, self.unit)
+    }
+
+    /// Get the byte offset of the given line and column, measuring columns in the given [`ColumnUnit`].
+    pub fn offset_in(&self, line_col: LineCol, unit: ColumnUnit) -> Option<usize> {
+        let span = self.line_span(line_col.line)?;
+        let (mut col, mut offset) = (0, span.start);
+        self.walk_line(line_col.line, unit, |start, len, width| {
+            offset = start;
+            if col + width <= line_col.col {
+                col += width;
+                offset += len;
+                true
+            } else {
+                false
+            }
+        });
+        if col == line_col.col || offset < span.end {
+            Some(offset.min(span.end))
+        } else {
+            None
+        }
+    }
+
+    // Visit each character (or grapheme) of a line with its byte offset, byte length and width in columns, until `f`
+    // returns false
+    fn walk_line(
+        &self,
+        line: usize,
+        unit: ColumnUnit,
+        mut f: impl FnMut(usize, usize, usize) -> bool,
+    ) {
+        let span = match self.line_span(line) {
+            Some(span) => span,
+            None => return,
+        };
+        let text = &self.src[span.start..span.end];
+        let mut col = 0;
+        let mut visit = |idx: usize, s: &str| {
+            let width = if s == "\t" {
+                self.tab_width - col % self.tab_width
+            } else {
+                unit.width(s)
+            };
+            col += width;
+            f(span.start + idx, s.len(), width)
+        };
+        if unit == ColumnUnit::Graphemes {
+            use unicode_segmentation::UnicodeSegmentation;
+            for (idx, g) in text.grapheme_indices(true) {
+                if !visit(idx, g) {
+                    break;
+                }
+            }
+        } else {
+            for (idx, c) in text.char_indices() {
+                if !visit(idx, &text[idx..idx + c.len_utf8()]) {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Implemented by parser states that hold a [`LineIndex`], allowing [`MapExtra::line_col`] to be used.
+///
+/// This is implemented for [`LineIndex`] itself (which may be used directly as parser state), and for
+/// [`SimpleState`](crate::inspector::SimpleState) wrapping any type that implements it. If your parser state holds a
+/// [`LineIndex`] alongside other data, you can implement this trait for it yourself.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, span::{LineCol, LineIndex}};
+/// let src = "foo\n  bar baz";
+///
+/// let ident = text::ascii::ident::<_, extra::Full<EmptyErr, LineIndex, ()>>()
+///     .map_with(|ident, e| (ident, e.line_col()))
+///     .padded();
+///
+/// assert_eq!(
+///     ident.repeated().collect::<Vec<_>>().parse_with_state(src, &mut LineIndex::new(src)).into_result(),
+///     Ok(vec![
+///         ("foo", LineCol { line: 0, col: 0 }),
+///         ("bar", LineCol { line: 1, col: 2 }),
+///         ("baz", LineCol { line: 1, col: 6 }),
+///     ]),
+/// );
+/// ```
+pub trait AsLineIndex {
+    /// Get the line index.
+    fn as_line_index(&self) -> &LineIndex<'_>;
+}
+
+impl AsLineIndex for LineIndex<'_> {
+    fn as_line_index(&self) -> &LineIndex<'_> {
+        self
+    }
+}
+
+impl<T: AsLineIndex> AsLineIndex for inspector::SimpleState<T> {
+    fn as_line_index(&self) -> &LineIndex<'_> {
+        self.0.as_line_index()
+    }
+}
+
+impl<'src, I: Input<'src>> Inspector<'src, I> for LineIndex<'_> {
+    type Checkpoint = ();
+    const ON_TOKEN: bool = false;
+    #[inline(always)]
+    fn on_token(&mut self, _: &I::Token) {}
+    #[inline(always)]
+    fn on_save<'parse>(&self, _: &input::Cursor<'src, 'parse, I>) -> Self::Checkpoint {}
+    #[inline(always)]
+    fn on_rewind<'parse>(&mut self, _: &input::Checkpoint<'src, 'parse, I, Self::Checkpoint>) {}
+}
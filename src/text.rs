@@ -15,7 +15,7 @@ use super::*;
 ///
 /// This trait is currently sealed to minimize the impact of breaking changes. If you find a type that you think should
 /// implement this trait, please [open an issue/PR](https://github.com/zesterer/chumsky/issues/new).
-pub trait Char: Copy + PartialEq + Sealed {
+pub trait Char: Copy + PartialEq + fmt::Debug + Sealed {
     /// Returns true if the character is canonically considered to be inline whitespace (i.e: not part of a newline).
     fn is_inline_whitespace(&self) -> bool;
 
@@ -39,6 +39,11 @@ pub trait Char: Copy + PartialEq + Sealed {
 
     /// Returns this character as a [`char`].
     fn to_ascii(&self) -> Option<u8>;
+
+    // Convert a string into characters, if possible, for generating inputs, skipping whitespace and dispatching on
+    // keywords.
+    #[doc(hidden)]
+    fn encode(s: &str) -> Option<Vec<Self>>;
 }
 
 impl Sealed for &Grapheme {}
@@ -97,6 +102,11 @@ impl Char for &Grapheme {
         let mut iter = self.as_str().chars();
         iter.all(unicode_ident::is_xid_continue)
     }
+
+    fn encode(_s: &str) -> Option<Vec<Self>> {
+        // Graphemes borrow from the input, so there's nothing for new ones to borrow from
+        None
+    }
 }
 
 impl Sealed for char {}
@@ -142,6 +152,10 @@ impl Char for char {
     fn is_ident_continue(&self) -> bool {
         unicode_ident::is_xid_continue(*self)
     }
+
+    fn encode(s: &str) -> Option<Vec<Self>> {
+        Some(s.chars().collect())
+    }
 }
 
 impl Sealed for u8 {}
@@ -184,6 +198,10 @@ impl Char for u8 {
     fn is_ident_continue(&self) -> bool {
         (*self as char).is_ident_continue()
     }
+
+    fn encode(s: &str) -> Option<Vec<Self>> {
+        Some(s.bytes().collect())
+    }
 }
 
 /// A parser that accepts (and ignores) any number of whitespace characters before or after another pattern.
@@ -206,9 +224,150 @@ where
         Ok(out)
     }
 
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        let whitespace =
+            || grammar::Expr::Optional(Box::new(grammar::Expr::Special("whitespace".into())));
+        grammar::Expr::seq([whitespace(), self.parser.node_info(scope), whitespace()])
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        scope.optional(|scope| gen_one_of(scope, " \n"))?;
+        self.parser.gen_tokens(scope)?;
+        // Always finish with whitespace, so that the parser's input doesn't run into whatever follows it
+        gen_one_of(scope, " \n")
+    }
+
     go_extra!(O);
 }
 
+/// Generate the characters of a string.
+#[cfg(feature = "generate")]
+fn gen_str<C: Char>(
+    scope: &mut generate::Scope<'_, C>,
+    s: &str,
+) -> Result<(), generate::GenerateError> {
+    scope.extend(C::encode(s).ok_or_else(generate::unsupported::<C>)?);
+    Ok(())
+}
+
+/// Generate one of the characters of an ASCII string.
+#[cfg(feature = "generate")]
+fn gen_one_of<C: Char>(
+    scope: &mut generate::Scope<'_, C>,
+    chars: &str,
+) -> Result<(), generate::GenerateError> {
+    let i = scope.generator().below(chars.len());
+    gen_str(scope, &chars[i..i + 1])
+}
+
+#[cfg(feature = "generate")]
+const DIGITS: &str = "0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// Generate a C-style identifier.
+#[cfg(feature = "generate")]
+fn gen_ident<C: Char>(scope: &mut generate::Scope<'_, C>) -> Result<(), generate::GenerateError> {
+    const LETTERS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ_";
+    gen_one_of(scope, LETTERS)?;
+    scope.repeat(0, None, |_, scope| {
+        let chars = if scope.generator().below(4) == 0 {
+            &DIGITS[..10]
+        } else {
+            LETTERS
+        };
+        gen_one_of(scope, chars)
+    })
+}
+
+// The text of a keyword, if it's given as a string or a byte string.
+//
+// `keyword` only requires that its keyword can be compared with slices of the input, so the text of a keyword can't be
+// read in general. Keywords are almost always string literals though, so the common string types are recognised by
+// their type (ignoring lifetimes, which `TypeId` can't see), letting them generate inputs and be dispatched on.
+fn keyword_text<S>(keyword: &S) -> Option<&str> {
+    use core::any::TypeId;
+
+    trait NonStaticAny {
+        fn type_id(&self) -> TypeId
+        where
+            Self: 'static;
+    }
+
+    impl<T: ?Sized> NonStaticAny for PhantomData<T> {
+        fn type_id(&self) -> TypeId
+        where
+            Self: 'static,
+        {
+            TypeId::of::<T>()
+        }
+    }
+
+    let phantom = PhantomData::<S>;
+    let phantom: &dyn NonStaticAny = &phantom;
+    // SAFETY: `PhantomData` contains nothing that could outlive its lifetime, and the trait object is only used to get
+    // the `TypeId` of `S`.
+    let phantom: &(dyn NonStaticAny + 'static) = unsafe { core::mem::transmute(phantom) };
+    let id = phantom.type_id();
+
+    let keyword = keyword as *const S;
+    // SAFETY: `S` was checked to be the type that the keyword is cast to, up to lifetimes. The text returned is borrowed
+    // from the keyword, so it can't outlive it.
+    unsafe {
+        if id == TypeId::of::<&str>() {
+            Some(*keyword.cast::<&str>())
+        } else if id == TypeId::of::<String>() {
+            Some((*keyword.cast::<String>()).as_str())
+        } else if id == TypeId::of::<&[u8]>() {
+            core::str::from_utf8(*keyword.cast::<&[u8]>()).ok()
+        } else if id == TypeId::of::<Vec<u8>>() {
+            core::str::from_utf8(&*keyword.cast::<Vec<u8>>()).ok()
+        } else {
+            None
+        }
+    }
+}
+
+// Describe a keyword parser, letting it generate inputs and be dispatched on by its (case-sensitive ASCII) text if the
+// text is known.
+fn describe_keyword<'src, I, S, E, P>(
+    parser: P,
+    keyword: &S,
+) -> impl Parser<'src, I, <I as SliceInput<'src>>::Slice, E> + Clone + 'src
+where
+    I: StrInput<'src>,
+    I::Token: Char + 'src,
+    E: ParserExtra<'src, I> + 'src,
+    P: Parser<'src, I, <I as SliceInput<'src>>::Slice, E> + Clone + 'src,
+{
+    let text = keyword_text(keyword).map(String::from);
+    let prefixes = text
+        .as_deref()
+        .filter(|text| text.is_ascii())
+        .and_then(I::Token::encode)
+        .map(|prefix| vec![prefix]);
+    #[cfg(feature = "generate")]
+    let parser = parser.generate_with(
+        move |scope: &mut generate::Scope<'_, I::Token>| match &text {
+            Some(text) => gen_str(scope, text),
+            None => Err(generate::unsupported::<S>()),
+        },
+    );
+    Described {
+        parser: Prefixed { parser, prefixes },
+        desc: "keyword",
+    }
+}
+
 /// Labels denoting a variety of text-related patterns.
 #[non_exhaustive]
 pub enum TextExpected<'src, I: StrInput<'src>>
@@ -258,19 +417,21 @@ where
     E: ParserExtra<'src, I>,
     E::Error: LabelError<'src, I, TextExpected<'src, I>>,
 {
-    any()
-        .try_map(|c: I::Token, span| {
-            if c.is_whitespace() {
-                Ok(())
-            } else {
-                Err(LabelError::expected_found(
-                    [TextExpected::Whitespace],
-                    Some(MaybeRef::Val(c)),
-                    span,
-                ))
-            }
-        })
-        .repeated()
+    let parser = any().try_map(|c: I::Token, span| {
+        if c.is_whitespace() {
+            Ok(())
+        } else {
+            Err(LabelError::expected_found(
+                [TextExpected::Whitespace],
+                Some(MaybeRef::Val(c)),
+                span,
+            ))
+        }
+    });
+    #[cfg(feature = "generate")]
+    let parser = parser
+        .generate_with(|scope: &mut generate::Scope<'_, I::Token>| gen_one_of(scope, " \t\n"));
+    Skippable::new(parser, Char::is_whitespace).repeated()
 }
 
 /// A parser that accepts (and ignores) any number of inline whitespace characters.
@@ -299,19 +460,86 @@ where
     E: ParserExtra<'src, I>,
     E::Error: LabelError<'src, I, TextExpected<'src, I>>,
 {
-    any()
-        .try_map(|c: I::Token, span| {
-            if c.is_inline_whitespace() {
-                Ok(())
-            } else {
-                Err(LabelError::expected_found(
-                    [TextExpected::InlineWhitespace],
-                    Some(MaybeRef::Val(c)),
-                    span,
-                ))
+    let parser = any().try_map(|c: I::Token, span| {
+        if c.is_inline_whitespace() {
+            Ok(())
+        } else {
+            Err(LabelError::expected_found(
+                [TextExpected::InlineWhitespace],
+                Some(MaybeRef::Val(c)),
+                span,
+            ))
+        }
+    });
+    #[cfg(feature = "generate")]
+    let parser =
+        parser.generate_with(|scope: &mut generate::Scope<'_, I::Token>| gen_one_of(scope, " \t"));
+    Skippable::new(parser, Char::is_inline_whitespace).repeated()
+}
+
+/// A parser for a single character that, when repeated, can skip over runs of the ASCII characters it accepts in bulk.
+#[derive(Copy, Clone)]
+struct Skippable<A, C> {
+    parser: A,
+    ascii: [C; 6],
+    len: usize,
+}
+
+impl<A, C: Char> Skippable<A, C> {
+    fn new(parser: A, accepts: impl Fn(&C) -> bool) -> Self {
+        // No other ASCII characters are accepted by any of the whitespace predicates
+        let mut ascii = [C::digit_zero(); 6];
+        let mut len = 0;
+        for c in C::encode(" \t\n\r\x0B\x0C").into_iter().flatten() {
+            if accepts(&c) {
+                ascii[len] = c;
+                len += 1;
             }
-        })
-        .repeated()
+        }
+        Self { parser, ascii, len }
+    }
+}
+
+impl<'src, I, O, E, A> Parser<'src, I, O, E> for Skippable<A, I::Token>
+where
+    I: Input<'src>,
+    I::Token: Char,
+    E: ParserExtra<'src, I>,
+    A: Parser<'src, I, O, E>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'src, '_, I, E>) -> PResult<M, O> {
+        self.parser.go::<M>(inp)
+    }
+
+    #[cfg(feature = "grammar")]
+    fn node_info(&self, scope: &mut grammar::NodeScope) -> grammar::Expr
+    where
+        I::Token: fmt::Debug,
+    {
+        self.parser.node_info(scope)
+    }
+
+    #[cfg(feature = "generate")]
+    fn gen_tokens(
+        &self,
+        scope: &mut generate::Scope<'_, I::Token>,
+    ) -> Result<(), generate::GenerateError>
+    where
+        I::Token: Clone,
+    {
+        self.parser.gen_tokens(scope)
+    }
+
+    fn skip_many(&self, inp: &mut InputRef<'src, '_, I, E>) -> usize {
+        // Trivia gets skipped before each character, so skipping in bulk would change what gets consumed
+        if inp.trivia.is_some() {
+            return 0;
+        }
+        inp.skip_tokens(&self.ascii[..self.len], false).unwrap_or(0)
+    }
+
+    go_extra!(O);
 }
 
 /// A parser that accepts (and ignores) any newline characters or character sequences.
@@ -353,7 +581,7 @@ where
     &'src str: OrderedSeq<'src, I::Token>,
     E::Error: LabelError<'src, I, TextExpected<'src, I>>,
 {
-    custom(|inp| {
+    let parser = custom(|inp| {
         let before = inp.cursor();
 
         if inp
@@ -381,7 +609,17 @@ where
                 ))
             }
         }
-    })
+    });
+    #[cfg(feature = "generate")]
+    let parser = parser.generate_with(|scope: &mut generate::Scope<'_, I::Token>| {
+        let newline = if scope.generator().below(4) == 0 {
+            "\r\n"
+        } else {
+            "\n"
+        };
+        gen_str(scope, newline)
+    });
+    parser
 }
 
 /// A parser that accepts one or more ASCII digits.
@@ -415,20 +653,22 @@ where
     E: ParserExtra<'src, I>,
     E::Error: LabelError<'src, I, TextExpected<'src, I>>,
 {
-    any()
-        .try_map(move |c: I::Token, span| {
-            if c.is_digit(radix) {
-                Ok(c)
-            } else {
-                Err(LabelError::expected_found(
-                    [TextExpected::Digit(0..radix)],
-                    Some(MaybeRef::Val(c)),
-                    span,
-                ))
-            }
-        })
-        .repeated()
-        .at_least(1)
+    let parser = any().try_map(move |c: I::Token, span| {
+        if c.is_digit(radix) {
+            Ok(c)
+        } else {
+            Err(LabelError::expected_found(
+                [TextExpected::Digit(0..radix)],
+                Some(MaybeRef::Val(c)),
+                span,
+            ))
+        }
+    });
+    #[cfg(feature = "generate")]
+    let parser = parser.generate_with(move |scope: &mut generate::Scope<'_, I::Token>| {
+        gen_one_of(scope, &DIGITS[..radix as usize])
+    });
+    parser.repeated().at_least(1)
 }
 
 /// A parser that accepts a non-negative integer.
@@ -470,7 +710,7 @@ where
     E::Error:
         LabelError<'src, I, TextExpected<'src, I>> + LabelError<'src, I, MaybeRef<'src, I::Token>>,
 {
-    any()
+    let parser = any()
         .try_map(move |c: I::Token, span| {
             if c.is_digit(radix) && c != I::Token::digit_zero() {
                 Ok(c)
@@ -500,6 +740,21 @@ where
         .ignored()
         .or(just(I::Token::digit_zero()).ignored())
         .to_slice()
+        .no_trivia();
+    #[cfg(feature = "generate")]
+    let parser = parser.generate_with(move |scope: &mut generate::Scope<'_, I::Token>| {
+        if scope.is_limited() || scope.generator().below(8) == 0 {
+            return gen_str(scope, "0");
+        }
+        gen_one_of(scope, &DIGITS[1..radix as usize])?;
+        scope.repeat(0, None, |_, scope| {
+            gen_one_of(scope, &DIGITS[..radix as usize])
+        })
+    });
+    Described {
+        parser,
+        desc: "integer",
+    }
 }
 
 /// Parsers and utilities for working with ASCII inputs.
@@ -521,7 +776,7 @@ pub mod ascii {
         E: ParserExtra<'src, I>,
         E::Error: LabelError<'src, I, TextExpected<'src, I>>,
     {
-        any()
+        let parser = any()
             .try_map(|c: I::Token, span| {
                 if c.to_ascii()
                     .map(|i| i.is_ascii_alphabetic() || i == b'_')
@@ -554,6 +809,13 @@ pub mod ascii {
                     .repeated(),
             )
             .to_slice()
+            .no_trivia();
+        #[cfg(feature = "generate")]
+        let parser = parser.generate_with(gen_ident);
+        Described {
+            parser,
+            desc: "identifier",
+        }
     }
 
     /// Like [`ident`], but only accepts a specific identifier while rejecting trailing identifier characters.
@@ -561,6 +823,9 @@ pub mod ascii {
     /// The output type of this parser is `I::Slice` (i.e: [`&str`] when `I` is [`&str`], and [`&[u8]`]
     /// when `I::Slice` is [`&[u8]`]).
     ///
+    /// When the keyword is a `&str`, `String`, `&[u8]` or `Vec<u8>`, generated inputs contain it and
+    /// [`choice_dispatch`] indexes it by its text.
+    ///
     /// # Examples
     ///
     /// ```
@@ -603,7 +868,8 @@ pub mod ascii {
             }
         }
         */
-        ident()
+        let keyword_desc = keyword.clone();
+        let parser = ident()
             .try_map(move |s: I::Slice, span| {
                 if keyword == s {
                     Ok(())
@@ -612,6 +878,8 @@ pub mod ascii {
                 }
             })
             .to_slice()
+            .no_trivia();
+        describe_keyword::<I, S, E, _>(parser, &keyword_desc)
     }
 }
 
@@ -944,7 +1212,7 @@ pub mod unicode {
         E: ParserExtra<'src, I>,
         E::Error: LabelError<'src, I, TextExpected<'src, I>>,
     {
-        any()
+        let parser = any()
             .try_map(|c: I::Token, span| {
                 if c.is_ident_start() {
                     Ok(c)
@@ -972,6 +1240,13 @@ pub mod unicode {
                     .repeated(),
             )
             .to_slice()
+            .no_trivia();
+        #[cfg(feature = "generate")]
+        let parser = parser.generate_with(gen_ident);
+        Described {
+            parser,
+            desc: "identifier",
+        }
     }
 
     /// Like [`ident`], but only accepts a specific identifier while rejecting trailing identifier characters.
@@ -979,6 +1254,9 @@ pub mod unicode {
     /// The output type of this parser is `I::Slice` (i.e: [`&str`] when `I` is [`&str`], and [`&[u8]`]
     /// when `I::Slice` is [`&[u8]`]).
     ///
+    /// When the keyword is a `&str`, `String`, `&[u8]` or `Vec<u8>`, generated inputs contain it and
+    /// [`choice_dispatch`] indexes it by its text.
+    ///
     /// # Examples
     ///
     /// ```
@@ -1025,7 +1303,8 @@ pub mod unicode {
             }
         }
         */
-        ident()
+        let keyword_desc = keyword.clone();
+        let parser = ident()
             .try_map(move |s: I::Slice, span| {
                 if keyword.borrow() == &s {
                     Ok(())
@@ -1034,6 +1313,8 @@ pub mod unicode {
                 }
             })
             .to_slice()
+            .no_trivia();
+        describe_keyword::<I, S, E, _>(parser, &keyword_desc)
     }
 }
 
@@ -1061,22 +1342,13 @@ mod tests {
     }
 
     fn test_ok<'src, P: Parser<'src, &'src str, &'src str>>(parser: P, input: &'src str) {
-        assert_eq!(
-            parser.parse(input),
-            ParseResult {
-                output: Some(input),
-                errs: vec![]
-            }
-        );
+        assert_eq!(parser.parse(input), ParseResult::new(Some(input), vec![]));
     }
 
     fn test_err<'src, P: Parser<'src, &'src str, &'src str>>(parser: P, input: &'src str) {
         assert_eq!(
             parser.parse(input),
-            ParseResult {
-                output: None,
-                errs: vec![EmptyErr::default()]
-            }
+            ParseResult::new(None, vec![EmptyErr::default()])
         );
     }
 
@@ -1091,6 +1363,30 @@ mod tests {
         make_unicode_kw_parser::<&str>("你好");
     }
 
+    #[test]
+    fn keyword_text() {
+        use super::keyword_text;
+
+        let owned = String::from("while");
+        assert_eq!(keyword_text(&"let"), Some("let"));
+        assert_eq!(keyword_text(&owned.as_str()), Some("while"));
+        assert_eq!(keyword_text(&owned), Some("while"));
+        assert_eq!(keyword_text(&&b"fn"[..]), Some("fn"));
+        assert_eq!(keyword_text(&b"fn".to_vec()), Some("fn"));
+        assert_eq!(keyword_text(&&b"\xff"[..]), None);
+        // Other types can't be read
+        assert_eq!(keyword_text(&&owned), None);
+        assert_eq!(keyword_text(&'x'), None);
+
+        // Keywords whose text is known are indexed by it
+        let parser = text::ascii::keyword::<&str, _, extra::Default>(owned.as_str());
+        assert_eq!(parser.prefixes(), Some(vec!["while".chars().collect()]));
+        let parser = text::ascii::keyword::<&[u8], _, extra::Default>(b"fn".to_vec());
+        assert_eq!(parser.prefixes(), Some(vec![b"fn".to_vec()]));
+        let parser = text::unicode::keyword::<&str, _, extra::Default>("привет");
+        assert_eq!(parser.prefixes(), None);
+    }
+
     #[test]
     fn ident() {
         let ident = text::ident::<&str, extra::Default>();
@@ -1106,6 +1402,43 @@ mod tests {
         test_err(ident, "123");
     }
 
+    #[test]
+    fn chunked() {
+        use crate::input::ChunkedStr;
+        use std::borrow::Cow;
+
+        let chunks = ["  fn", " foo_", "", "bar", "\t", "(", "x)  "];
+        let input = || ChunkedStr::new(&chunks);
+
+        let parser = text::keyword::<_, _, extra::Default>("fn")
+            .padded()
+            .ignore_then(text::ident())
+            .then(
+                text::ascii::ident()
+                    .delimited_by(just('('), just(')'))
+                    .padded_by(text::whitespace()),
+            );
+        let (name, arg) = parser.parse(input()).into_result().unwrap();
+        assert_eq!(name, "foo_bar");
+        assert!(matches!(name, Cow::Owned(_)));
+        assert_eq!(arg, "x");
+        assert!(matches!(arg, Cow::Borrowed(_)));
+
+        let slice = any::<_, extra::Default>()
+            .repeated()
+            .to_slice()
+            .parse(input())
+            .into_result();
+        assert_eq!(slice.as_deref(), Ok(chunks.concat().as_str()));
+
+        assert!(text::keyword::<_, _, extra::Default>("fn")
+            .padded()
+            .then(text::keyword("foo"))
+            .lazy()
+            .parse(input())
+            .has_errors());
+    }
+
     /*
     #[test]
     #[should_panic]
@@ -0,0 +1,489 @@
+//! Token trees: flat token streams grouped by their delimiters.
+//!
+//! *"In the beginning the Universe was created. This has made a lot of people very angry and been widely regarded as a
+//! bad move."*
+//!
+//! Many languages are easiest to parse in two stages: a lexer that produces a flat list of tokens, and a parser that
+//! consumes them. Between the two, it is often useful to group tokens by their delimiters (parentheses, brackets,
+//! braces, etc.) into a tree. Doing so means that the parser never needs to worry about unbalanced delimiters, and that
+//! error recovery can easily skip over whole groups.
+//!
+//! [`group`] performs this grouping, producing a list of [`TokenTree`]s. Grouping never fails: unclosed and mismatched
+//! delimiters are recorded in the resulting [`Group`], and are reported as errors when [`delimited`] enters the group.
+//! [`TreeInput`] allows a list of token trees to be used as parser input.
+//!
+//! # Examples
+//!
+//! ```
+//! # use chumsky::{prelude::*, token_tree::{self, TokenTree, TreeInput}};
+//! #[derive(Clone, Debug, PartialEq)]
+//! enum Token {
+//!     Num(i64),
+//!     Add,
+//!     Open,
+//!     Close,
+//! }
+//!
+//! let tokens = [Token::Open, Token::Num(1), Token::Add, Token::Num(2), Token::Close, Token::Add, Token::Num(3)]
+//!     .into_iter()
+//!     .enumerate()
+//!     .map(|(i, tok)| (tok, SimpleSpan::from(i..i + 1)));
+//! let trees = token_tree::group(tokens, &[(Token::Open, Token::Close)]);
+//!
+//! let sum = recursive(|sum| {
+//!     let num = select_ref! { TokenTree::Token(Token::Num(x)) => *x };
+//!     let add = just::<_, _, extra::Err<Rich<_>>>(TokenTree::Token(Token::Add));
+//!     let atom = num.or(token_tree::delimited(Token::Open, sum));
+//!     atom.clone().foldl(add.ignore_then(atom).repeated(), |a, b| a + b)
+//! });
+//!
+//! let input = TreeInput::new(&trees, SimpleSpan::from(7..7));
+//! assert_eq!(sum.parse(input).into_result(), Ok(6));
+//! ```
+
+use super::*;
+
+/// A token, or a group of token trees surrounded by delimiters.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TokenTree<T, S = SimpleSpan> {
+    /// A single token.
+    Token(T),
+    /// A group of token trees surrounded by delimiters.
+    Group(Group<T, S>),
+}
+
+/// A group of token trees surrounded by delimiters, as produced by [`group`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Group<T, S = SimpleSpan> {
+    /// The opening delimiter of the group, and its span.
+    pub open: (T, S),
+    /// The closing delimiter that the opening delimiter is paired with.
+    pub expected_close: T,
+    /// The token that closed the group, and its span.
+    ///
+    /// This is `None` if the group was never closed, and will differ from [`Group::expected_close`] if the group was
+    /// closed by a mismatched delimiter.
+    pub close: Option<(T, S)>,
+    /// The token trees within the group, with their spans.
+    pub trees: Vec<(TokenTree<T, S>, S)>,
+}
+
+impl<T: PartialEq, S: Span + Clone> Group<T, S> {
+    /// Returns `true` if the group was closed by the expected delimiter.
+    pub fn is_closed(&self) -> bool {
+        self.close
+            .as_ref()
+            .map_or(false, |(close, _)| *close == self.expected_close)
+    }
+
+    /// Get an input over the token trees within this group.
+    ///
+    /// The end of the input is given the span of the closing delimiter if there is one, or an empty span at the end
+    /// of the group if not.
+    pub fn input(&self) -> TreeInput<'_, T, S> {
+        let eoi = match &self.close {
+            Some((_, span)) => span.clone(),
+            None => self.span().to_end(),
+        };
+        TreeInput::new(&self.trees, eoi)
+    }
+
+    fn span(&self) -> S {
+        let open = &self.open.1;
+        let end = match (&self.close, self.trees.last()) {
+            (Some((_, span)), _) | (None, Some((_, span))) => span.end(),
+            (None, None) => open.end(),
+        };
+        S::new(open.context(), open.start()..end)
+    }
+}
+
+/// Group a flat list of tokens into token trees, using the given `(open, close)` delimiter pairs.
+///
+/// Grouping never fails. A group that is never closed has no [`Group::close`]. A closing delimiter that does not match
+/// the innermost open group closes the nearest enclosing group that it does match (leaving the groups within that one
+/// unclosed), or, if there is no such group, closes the innermost group as a mismatch. Closing delimiters that appear
+/// outside of any group are left in the output as [`TokenTree::Token`]s.
+pub fn group<T, S>(
+    tokens: impl IntoIterator<Item = (T, S)>,
+    delimiters: &[(T, T)],
+) -> Vec<(TokenTree<T, S>, S)>
+where
+    T: PartialEq + Clone,
+    S: Span + Clone,
+{
+    // The groups that are currently open, innermost last. The outermost level is represented by `None`.
+    let mut stack: Vec<(Option<Group<T, S>>, Vec<(TokenTree<T, S>, S)>)> = vec![(None, Vec::new())];
+
+    fn close<T: PartialEq, S: Span + Clone>(
+        stack: &mut Vec<(Option<Group<T, S>>, Vec<(TokenTree<T, S>, S)>)>,
+        close: Option<(T, S)>,
+    ) {
+        let (group, trees) = stack.pop().expect("the outermost level is never closed");
+        let mut group = group.expect("the outermost level is never closed");
+        group.close = close;
+        group.trees = trees;
+        let span = group.span();
+        stack
+            .last_mut()
+            .expect("the outermost level is never closed")
+            .1
+            .push((TokenTree::Group(group), span));
+    }
+
+    for (tok, span) in tokens {
+        if let Some((_, expected_close)) = delimiters.iter().find(|(open, _)| *open == tok) {
+            let group = Group {
+                open: (tok, span),
+                expected_close: expected_close.clone(),
+                close: None,
+                trees: Vec::new(),
+            };
+            stack.push((Some(group), Vec::new()));
+        } else if delimiters.iter().any(|(_, close)| *close == tok) && stack.len() > 1 {
+            let matching = stack
+                .iter()
+                .rposition(|(group, _)| group.as_ref().map_or(false, |g| g.expected_close == tok));
+            match matching {
+                Some(depth) => {
+                    while stack.len() > depth + 1 {
+                        close(&mut stack, None);
+                    }
+                    close(&mut stack, Some((tok, span)));
+                }
+                None => close(&mut stack, Some((tok, span))),
+            }
+        } else {
+            stack
+                .last_mut()
+                .expect("the outermost level is never closed")
+                .1
+                .push((TokenTree::Token(tok), span));
+        }
+    }
+
+    while stack.len() > 1 {
+        close(&mut stack, None);
+    }
+    stack.pop().map(|(_, trees)| trees).unwrap_or_default()
+}
+
+/// A parser that enters a group opened by the delimiter `open`, and runs `parser` on the token trees within it.
+///
+/// `parser` must consume the whole of the group. If the group was not closed by the delimiter expected for `open`, an
+/// error is emitted that points at the mismatched closing delimiter (or the end of the group, if it was never closed)
+/// and that has the opening delimiter as its context. Because the group's contents are still known, this error does
+/// not prevent the rest of the input from being parsed.
+pub fn delimited<'src, T, S, P, O, E>(
+    open: T,
+    parser: P,
+) -> impl Parser<'src, TreeInput<'src, T, S>, O, E> + Clone
+where
+    T: PartialEq + Clone + 'src,
+    S: Span + Clone + 'src,
+    P: Parser<'src, TreeInput<'src, T, S>, O, E> + Clone,
+    E: ParserExtra<'src, TreeInput<'src, T, S>>,
+    E::Error: LabelError<'src, TreeInput<'src, T, S>, DefaultExpected<'src, TokenTree<T, S>>>,
+{
+    let group = custom(
+        move |inp: &mut InputRef<'src, '_, TreeInput<'src, T, S>, E>| {
+            let before = inp.save();
+            match inp.next_ref() {
+                Some(TokenTree::Group(group)) if group.open.0 == open => {
+                    if !group.is_closed() {
+                        let (found, span) = match &group.close {
+                            Some((close, span)) => (
+                                Some(MaybeRef::Val(TokenTree::Token(close.clone()))),
+                                span.clone(),
+                            ),
+                            None => (None, group.span().to_end()),
+                        };
+                        let mut err = E::Error::expected_found(
+                            [DefaultExpected::Token(MaybeRef::Val(TokenTree::Token(
+                                group.expected_close.clone(),
+                            )))],
+                            found,
+                            span,
+                        );
+                        err.in_context(
+                            DefaultExpected::Token(MaybeRef::Val(TokenTree::Token(
+                                group.open.0.clone(),
+                            ))),
+                            group.open.1.clone(),
+                        );
+                        inp.emit(None, err);
+                    }
+                    Ok(group.input())
+                }
+                found => {
+                    let span = inp.span_since(before.cursor());
+                    inp.rewind(before);
+                    Err(E::Error::expected_found(
+                        [DefaultExpected::Token(MaybeRef::Val(TokenTree::Token(
+                            open.clone(),
+                        )))],
+                        found.map(MaybeRef::Ref),
+                        span,
+                    ))
+                }
+            }
+        },
+    );
+    parser.nested_in(group)
+}
+
+/// Input type over a list of [`TokenTree`]s and their spans, such as that produced by [`group`].
+///
+/// Groups within the input are entered with [`delimited`], or with [`Parser::nested_in`] and [`Group::input`].
+#[derive(Debug)]
+pub struct TreeInput<'src, T, S = SimpleSpan> {
+    trees: &'src [(TokenTree<T, S>, S)],
+    eoi: S,
+}
+
+impl<T, S: Copy> Copy for TreeInput<'_, T, S> {}
+impl<T, S: Clone> Clone for TreeInput<'_, T, S> {
+    fn clone(&self) -> Self {
+        Self {
+            trees: self.trees,
+            eoi: self.eoi.clone(),
+        }
+    }
+}
+
+impl<'src, T, S> TreeInput<'src, T, S> {
+    /// Create a new input over the given token trees, using `eoi` as the span of the end of the input.
+    pub fn new(trees: &'src [(TokenTree<T, S>, S)], eoi: S) -> Self {
+        Self { trees, eoi }
+    }
+}
+
+impl<'src, T: 'src, S: Span + Clone + 'src> Input<'src> for TreeInput<'src, T, S> {
+    type Cursor = usize;
+    type Span = S;
+
+    type Token = TokenTree<T, S>;
+    type MaybeToken = &'src TokenTree<T, S>;
+
+    type Cache = Self;
+
+    #[inline]
+    fn begin(self) -> (Self::Cursor, Self::Cache) {
+        (0, self)
+    }
+
+    #[inline]
+    fn cursor_location(cursor: &Self::Cursor) -> usize {
+        *cursor
+    }
+
+    #[inline(always)]
+    unsafe fn next_maybe(
+        this: &mut Self::Cache,
+        cursor: &mut Self::Cursor,
+    ) -> Option<Self::MaybeToken> {
+        let (tree, _) = this.trees.get(*cursor)?;
+        *cursor += 1;
+        Some(tree)
+    }
+
+    #[inline]
+    unsafe fn span(this: &mut Self::Cache, range: Range<&Self::Cursor>) -> Self::Span {
+        match this.trees.get(*range.start) {
+            Some((_, start)) => {
+                let end = match range.end.checked_sub(1).and_then(|end| this.trees.get(end)) {
+                    Some((_, end)) if *range.end > *range.start => end.end(),
+                    _ => start.start(),
+                };
+                S::new(this.eoi.context(), start.start()..end)
+            }
+            None => this.eoi.clone(),
+        }
+    }
+}
+
+impl<'src, T: 'src, S: Span + Clone + 'src> ExactSizeInput<'src> for TreeInput<'src, T, S> {
+    #[inline]
+    unsafe fn span_from(this: &mut Self::Cache, range: RangeFrom<&Self::Cursor>) -> Self::Span {
+        match this.trees.get(*range.start) {
+            Some((_, start)) => S::new(this.eoi.context(), start.start()..this.eoi.end()),
+            None => this.eoi.clone(),
+        }
+    }
+}
+
+impl<'src, T: Clone + 'src, S: Span + Clone + 'src> ValueInput<'src> for TreeInput<'src, T, S> {
+    #[inline(always)]
+    unsafe fn next(this: &mut Self::Cache, cursor: &mut Self::Cursor) -> Option<Self::Token> {
+        Self::next_maybe(this, cursor).cloned()
+    }
+}
+
+impl<'src, T: 'src, S: Span + Clone + 'src> BorrowInput<'src> for TreeInput<'src, T, S> {
+    #[inline(always)]
+    unsafe fn next_ref(
+        this: &mut Self::Cache,
+        cursor: &mut Self::Cursor,
+    ) -> Option<&'src Self::Token> {
+        Self::next_maybe(this, cursor)
+    }
+}
+
+impl<'src, T: 'src, S: Span + Clone + 'src> SliceInput<'src> for TreeInput<'src, T, S> {
+    type Slice = &'src [(TokenTree<T, S>, S)];
+
+    #[inline(always)]
+    fn full_slice(this: &mut Self::Cache) -> Self::Slice {
+        this.trees
+    }
+
+    #[inline(always)]
+    unsafe fn slice(this: &mut Self::Cache, range: Range<&Self::Cursor>) -> Self::Slice {
+        &this.trees[*range.start..*range.end]
+    }
+
+    #[inline(always)]
+    unsafe fn slice_from(this: &mut Self::Cache, from: RangeFrom<&Self::Cursor>) -> Self::Slice {
+        &this.trees[*from.start..]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum Tok {
+        Ident(&'static str),
+        LParen,
+        RParen,
+        LBracket,
+        RBracket,
+    }
+
+    const DELIMS: &[(Tok, Tok)] = &[(Tok::LParen, Tok::RParen), (Tok::LBracket, Tok::RBracket)];
+
+    fn lex(src: &'static str) -> Vec<(Tok, SimpleSpan)> {
+        src.char_indices()
+            .filter(|(_, c)| !c.is_whitespace())
+            .map(|(i, c)| {
+                let tok = match c {
+                    '(' => Tok::LParen,
+                    ')' => Tok::RParen,
+                    '[' => Tok::LBracket,
+                    ']' => Tok::RBracket,
+                    _ => Tok::Ident(&src[i..i + 1]),
+                };
+                (tok, SimpleSpan::from(i..i + 1))
+            })
+            .collect()
+    }
+
+    // Render a list of token trees back to text, marking missing closing delimiters with `!`
+    fn render(trees: &[(TokenTree<Tok>, SimpleSpan)]) -> String {
+        let tok = |tok: &Tok| match tok {
+            Tok::Ident(x) => x.to_string(),
+            Tok::LParen => "(".to_string(),
+            Tok::RParen => ")".to_string(),
+            Tok::LBracket => "[".to_string(),
+            Tok::RBracket => "]".to_string(),
+        };
+        trees
+            .iter()
+            .map(|(tree, _)| match tree {
+                TokenTree::Token(t) => tok(t),
+                TokenTree::Group(g) => format!(
+                    "{}{}{}",
+                    tok(&g.open.0),
+                    render(&g.trees),
+                    g.close.as_ref().map_or("!".to_string(), |(t, _)| tok(t)),
+                ),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn grouping() {
+        let cases = [
+            ("a(b[c]d)e", "a(b[c]d)e"),
+            ("(a", "(a!"),
+            ("a)", "a)"),
+            ("([a)", "([a!)"),
+            ("(a]", "(a]"),
+            ("[(a]b", "[(a!]b"),
+        ];
+        for (src, expected) in cases {
+            assert_eq!(render(&group(lex(src), DELIMS)), expected, "{src}");
+        }
+
+        let trees = group(lex("a ( b ) ["), DELIMS);
+        assert_eq!(
+            trees.iter().map(|(_, span)| *span).collect::<Vec<_>>(),
+            vec![
+                SimpleSpan::from(0..1),
+                SimpleSpan::from(2..7),
+                SimpleSpan::from(8..9)
+            ],
+        );
+    }
+
+    fn list<'src>(
+    ) -> impl Parser<'src, TreeInput<'src, Tok>, Vec<String>, extra::Err<Rich<'src, TokenTree<Tok>>>>
+    {
+        recursive(|list| {
+            let ident = select_ref! { TokenTree::Token(Tok::Ident(x)) => x.to_string() };
+            let group = delimited(Tok::LParen, list.clone())
+                .or(delimited(Tok::LBracket, list))
+                .map(|xs: Vec<String>| format!("<{}>", xs.join(" ")));
+            ident.or(group).repeated().collect()
+        })
+    }
+
+    #[test]
+    fn delimited_errors() {
+        let parse = |src: &'static str, trees| {
+            let eoi = SimpleSpan::from(src.len()..src.len());
+            list()
+                .parse(TreeInput::new(trees, eoi))
+                .into_output_errors()
+        };
+
+        let src = "a (b [c]) d";
+        let trees = group(lex(src), DELIMS);
+        let (out, errs) = parse(src, &trees);
+        assert_eq!(
+            out,
+            Some(vec![
+                "a".to_string(),
+                "<b <c>>".to_string(),
+                "d".to_string()
+            ])
+        );
+        assert!(errs.is_empty());
+
+        // Unclosed delimiters are reported at the end of the group, but the group is still parsed
+        let src = "a (b c";
+        let trees = group(lex(src), DELIMS);
+        let (out, errs) = parse(src, &trees);
+        assert_eq!(out, Some(vec!["a".to_string(), "<b c>".to_string()]));
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::from(6..6));
+        assert_eq!(errs[0].found(), None);
+        assert_eq!(
+            errs[0]
+                .contexts()
+                .map(|(_, span)| *span)
+                .collect::<Vec<_>>(),
+            vec![SimpleSpan::from(2..3)],
+        );
+
+        // Mismatched delimiters are reported at the closing delimiter
+        let src = "(a] b";
+        let trees = group(lex(src), DELIMS);
+        let (out, errs) = parse(src, &trees);
+        assert_eq!(out, Some(vec!["<a>".to_string(), "b".to_string()]));
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::from(2..3));
+        assert_eq!(errs[0].found(), Some(&TokenTree::Token(Tok::RBracket)));
+    }
+}
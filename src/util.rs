@@ -222,3 +222,28 @@ impl<'src, T: 'src> IntoMaybe<'src, T> for T {
         g(self)
     }
 }
+
+/// Find the first occurrence of any of (up to a few) `needles` in `haystack`.
+///
+/// This works on eight bytes at a time, using the usual SWAR trick to detect zero bytes in `chunk ^ splat(needle)`.
+pub(crate) fn find_bytes(haystack: &[u8], needles: &[u8]) -> Option<usize> {
+    const LO: u64 = u64::from_ne_bytes([0x01; 8]);
+    const HI: u64 = u64::from_ne_bytes([0x80; 8]);
+
+    let mut chunks = haystack.chunks_exact(8);
+    for (i, chunk) in chunks.by_ref().enumerate() {
+        let word = u64::from_le_bytes(chunk.try_into().unwrap());
+        let found = needles.iter().fold(0, |found, n| {
+            let x = word ^ (LO * *n as u64);
+            found | (x.wrapping_sub(LO) & !x & HI)
+        });
+        // False positives can only occur in bytes above a genuine match, so the lowest flagged byte is the first
+        if found != 0 {
+            return Some(i * 8 + found.trailing_zeros() as usize / 8);
+        }
+    }
+    let tail = chunks.remainder();
+    tail.iter()
+        .position(|b| needles.contains(b))
+        .map(|pos| haystack.len() - tail.len() + pos)
+}